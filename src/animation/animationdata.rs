@@ -0,0 +1,88 @@
+use crate::animation::{AnimationElement, AnimationElementError};
+use crate::deserialize::{
+    read_f32_le, read_referenced_null_terminated_ascii_string, read_u16_le, read_u32_le, read_u8,
+    read_vec_pointer, ReadVecError,
+};
+use std::io;
+use std::io::{Read, Seek, SeekFrom};
+
+#[derive(Debug)]
+pub enum AnimationError {
+    IOError(io::Error, &'static str),
+    NullReference(&'static str),
+    ReadElementError(ReadVecError<AnimationElementError>),
+}
+
+fn ioe(err: io::Error, content: &'static str) -> AnimationError {
+    AnimationError::IOError(err, content)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AnimationLoopMode {
+    Once,
+    Loop,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Animation {
+    pub name: String,
+    pub loop_mode: AnimationLoopMode,
+    /// the kind of animation, which is also given by the dictionary it is stored in
+    pub animation_type: u8,
+    pub curve_count: u16,
+    pub frame_count: f32,
+    /// the animated targets. For skeletal animations, there is one per bone, with the bone name.
+    pub elements: Vec<AnimationElement>,
+    pub meta_data_pointer_offset: u32,
+}
+
+impl Animation {
+    pub fn read<F: Read + Seek>(file: &mut F) -> Result<Self, AnimationError> {
+        let name = read_referenced_null_terminated_ascii_string(file)
+            .map_err(|e| ioe(e, "animation name"))?
+            .ok_or(AnimationError::NullReference("animation name"))?;
+
+        let flags = read_u8(file).map_err(|e| ioe(e, "animation flags"))?;
+        let loop_mode = if flags & 1 != 0 {
+            AnimationLoopMode::Loop
+        } else {
+            AnimationLoopMode::Once
+        };
+        let animation_type = read_u8(file).map_err(|e| ioe(e, "animation type"))?;
+        let curve_count = read_u16_le(file).map_err(|e| ioe(e, "curve count"))?;
+        let frame_count = read_f32_le(file).map_err(|e| ioe(e, "frame count"))?;
+
+        let elements_offset = read_u32_le(file).map_err(|e| ioe(e, "elements offset"))?;
+        let element_count = read_u32_le(file).map_err(|e| ioe(e, "element count"))?;
+        let meta_data_pointer_offset =
+            read_u32_le(file).map_err(|e| ioe(e, "meta data pointer offset"))?;
+
+        file.seek(SeekFrom::Start(elements_offset as u64))
+            .map_err(|e| ioe(e, "elements"))?;
+        let elements = read_vec_pointer(file, AnimationElement::read, element_count as u64)
+            .map_err(AnimationError::ReadElementError)?;
+
+        Ok(Self {
+            name,
+            loop_mode,
+            animation_type,
+            curve_count,
+            frame_count,
+            elements,
+            meta_data_pointer_offset,
+        })
+    }
+
+    /// return the animation of the bone or other target with the given name
+    pub fn get_element(&self, name: &str) -> Option<&AnimationElement> {
+        self.elements.iter().find(|element| element.name == name)
+    }
+
+    /// return every element animating the target with the given name, like the different
+    /// properties of a material
+    pub fn get_elements<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a AnimationElement> {
+        self.elements
+            .iter()
+            .filter(move |element| element.name == name)
+    }
+}
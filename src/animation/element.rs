@@ -0,0 +1,283 @@
+use crate::animation::{BakedTrack, KeyFrameError, KeyFrameGroup};
+use crate::animation::{MatrixTransformAnimation, QuaternionTransformAnimation};
+use crate::animation::{TransformAnimation, TransformAnimationError};
+use crate::deserialize::{
+    read_f32_le, read_referenced_null_terminated_ascii_string, read_u16_le, read_u32_le, read_u8,
+};
+use std::io;
+use std::io::{Read, Seek, SeekFrom};
+
+#[derive(Debug)]
+pub enum AnimationElementError {
+    IOError(io::Error, &'static str),
+    NullReference(&'static str),
+    TransformAnimationError(TransformAnimationError),
+    KeyFrameError(KeyFrameError),
+    InvalidPrimitiveType(u8),
+}
+
+fn ioe(err: io::Error, content: &'static str) -> AnimationElementError {
+    AnimationElementError::IOError(err, content)
+}
+
+/// The kind of value animated by an element
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PrimitiveType {
+    Float,
+    Integer,
+    Boolean,
+    Vector2D,
+    Vector3D,
+    Transform,
+    RGBA,
+    Texture,
+    QuaternionTransform,
+    MatrixTransform,
+}
+
+impl PrimitiveType {
+    pub fn new(value: u8) -> Option<Self> {
+        Some(match value {
+            0 => Self::Float,
+            1 => Self::Integer,
+            2 => Self::Boolean,
+            3 => Self::Vector2D,
+            4 => Self::Vector3D,
+            5 => Self::Transform,
+            6 => Self::RGBA,
+            7 => Self::Texture,
+            8 => Self::QuaternionTransform,
+            9 => Self::MatrixTransform,
+            _ => return None,
+        })
+    }
+}
+
+/// The property of its target animated by an element
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AnimationTargetType {
+    Bone,
+    MaterialEmission,
+    MaterialAmbient,
+    MaterialDiffuse,
+    MaterialSpecular0,
+    MaterialSpecular1,
+    /// one of the six constant colors of the texture combiners
+    MaterialConstant(u8),
+    MaterialBlendColor,
+    /// the scale of the texture coordinator with the given index
+    MaterialTextureCoordinatorScale(u8),
+    MaterialTextureCoordinatorRotation(u8),
+    MaterialTextureCoordinatorTranslation(u8),
+    /// the texture bound to the texture mapper with the given index
+    MaterialTextureMapperTexture(u8),
+    MaterialTextureMapperBorderColor(u8),
+    /// the visibility of the object node with the name of the element
+    MeshNodeVisibility,
+    LightTransform,
+    LightAmbient,
+    LightDiffuse,
+    LightSpecular0,
+    LightSpecular1,
+    LightDirection,
+    LightDistanceAttenuationStart,
+    LightDistanceAttenuationEnd,
+    LightIsEnabled,
+    CameraTransform,
+    CameraUpVector,
+    CameraTwist,
+    CameraViewRotation,
+    CameraTarget,
+    CameraNear,
+    CameraFar,
+    /// the vertical field of view of a perspective camera, or the height of an orthogonal one
+    CameraFovYOrHeight,
+    CameraAspectRatio,
+    FogColor,
+}
+
+impl AnimationTargetType {
+    pub fn new(value: u16) -> Option<Self> {
+        Some(match value {
+            0 => Self::Bone,
+            1 => Self::MaterialEmission,
+            2 => Self::MaterialAmbient,
+            3 => Self::MaterialDiffuse,
+            4 => Self::MaterialSpecular0,
+            5 => Self::MaterialSpecular1,
+            6..=11 => Self::MaterialConstant((value - 6) as u8),
+            12 => Self::MaterialBlendColor,
+            13 | 16 | 19 => Self::MaterialTextureCoordinatorScale(((value - 13) / 3) as u8),
+            14 | 17 | 20 => Self::MaterialTextureCoordinatorRotation(((value - 14) / 3) as u8),
+            15 | 18 | 21 => Self::MaterialTextureCoordinatorTranslation(((value - 15) / 3) as u8),
+            22..=24 => Self::MaterialTextureMapperTexture((value - 22) as u8),
+            25..=27 => Self::MaterialTextureMapperBorderColor((value - 25) as u8),
+            28 => Self::MeshNodeVisibility,
+            29 => Self::LightTransform,
+            30 => Self::LightAmbient,
+            31 => Self::LightDiffuse,
+            32 => Self::LightSpecular0,
+            33 => Self::LightSpecular1,
+            34 => Self::LightDirection,
+            35 => Self::LightDistanceAttenuationStart,
+            36 => Self::LightDistanceAttenuationEnd,
+            37 => Self::LightIsEnabled,
+            38 => Self::CameraTransform,
+            39 => Self::CameraUpVector,
+            40 => Self::CameraTwist,
+            41 => Self::CameraViewRotation,
+            42 => Self::CameraTarget,
+            43 => Self::CameraNear,
+            44 => Self::CameraFar,
+            45 => Self::CameraFovYOrHeight,
+            46 => Self::CameraAspectRatio,
+            47 => Self::FogColor,
+            _ => return None,
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum AnimationContent {
+    Float(Option<KeyFrameGroup>),
+    /// stored like a float curve, whose values are whole numbers
+    Integer(Option<KeyFrameGroup>),
+    Boolean(BakedTrack<bool>),
+    Vector2D([Option<KeyFrameGroup>; 2]),
+    Vector3D(Box<[Option<KeyFrameGroup>; 3]>),
+    Transform(Box<TransformAnimation>),
+    RGBA(Box<[Option<KeyFrameGroup>; 4]>),
+    /// the curve give the index of the texture in the texture names of the animation
+    Texture(Option<KeyFrameGroup>),
+    QuaternionTransform(QuaternionTransformAnimation),
+    MatrixTransform(MatrixTransformAnimation),
+}
+
+/// read a value for each frame from `start_frame` to `end_frame`, packed as bits
+fn read_boolean_track<F: Read + Seek>(
+    file: &mut F,
+) -> Result<BakedTrack<bool>, AnimationElementError> {
+    let start_frame = read_f32_le(file).map_err(|e| ioe(e, "start frame"))?;
+    let end_frame = read_f32_le(file).map_err(|e| ioe(e, "end frame"))?;
+    // the repeat modes and the curve index
+    read_u32_le(file).map_err(|e| ioe(e, "boolean curve informations"))?;
+    let flags = read_u32_le(file).map_err(|e| ioe(e, "boolean flags"))?;
+    let values_offset = read_u32_le(file).map_err(|e| ioe(e, "values offset"))?;
+    let word_count = read_u32_le(file).map_err(|e| ioe(e, "value word count"))?;
+
+    let value_count = if flags & 1 != 0 {
+        1
+    } else {
+        ((end_frame - start_frame).max(0.0) as usize + 1).min(word_count as usize * 32)
+    };
+    file.seek(SeekFrom::Start(values_offset as u64))
+        .map_err(|e| ioe(e, "values"))?;
+    let mut values = Vec::with_capacity(value_count);
+    let mut word = 0;
+    for index in 0..value_count {
+        if index % 32 == 0 {
+            word = read_u32_le(file).map_err(|e| ioe(e, "value word"))?;
+        };
+        values.push(word >> (index % 32) & 1 != 0);
+    }
+    Ok(BakedTrack {
+        start_frame,
+        end_frame,
+        values,
+    })
+}
+
+/// read a flag word followed by a word for each of the `N` curves. A curve can be constant, in
+/// which case the word is its value, or missing.
+fn read_curves<F: Read + Seek, const N: usize>(
+    file: &mut F,
+) -> Result<[Option<KeyFrameGroup>; N], AnimationElementError> {
+    let flags = read_u32_le(file).map_err(|e| ioe(e, "curves flags"))?;
+    let mut position = file.stream_position().map_err(|e| ioe(e, "curves"))?;
+    let mut curves: [Option<KeyFrameGroup>; N] = std::array::from_fn(|_| None);
+    for (index, curve) in curves.iter_mut().enumerate() {
+        let constant = flags >> index & 1 != 0;
+        let exists = flags >> (index + N) & 1 == 0;
+
+        file.seek(SeekFrom::Start(position))
+            .map_err(|e| ioe(e, "curve"))?;
+        position += 4;
+        if exists {
+            *curve = Some(
+                KeyFrameGroup::read_maybe_constant(file, constant)
+                    .map_err(AnimationElementError::KeyFrameError)?,
+            );
+        };
+    }
+    file.seek(SeekFrom::Start(position))
+        .map_err(|e| ioe(e, "after curves"))?;
+    Ok(curves)
+}
+
+/// The animation of a single target, like a bone
+#[derive(Debug, Clone, PartialEq)]
+pub struct AnimationElement {
+    /// the name of the animated object, like a bone or a material
+    pub name: String,
+    /// which property of the target is animated
+    pub target_type: u16,
+    pub primitive_type: PrimitiveType,
+    pub content: AnimationContent,
+}
+
+impl AnimationElement {
+    /// return the animated property, or `None` if the target type is unknown
+    pub fn get_target_type(&self) -> Option<AnimationTargetType> {
+        AnimationTargetType::new(self.target_type)
+    }
+
+    pub fn read<F: Read + Seek>(file: &mut F) -> Result<Self, AnimationElementError> {
+        let name = read_referenced_null_terminated_ascii_string(file)
+            .map_err(|e| ioe(e, "element name"))?
+            .ok_or(AnimationElementError::NullReference("element name"))?;
+        let target_type = read_u16_le(file).map_err(|e| ioe(e, "target type"))?;
+        let primitive_type_value = read_u8(file).map_err(|e| ioe(e, "primitive type"))?;
+        let primitive_type = PrimitiveType::new(primitive_type_value).ok_or(
+            AnimationElementError::InvalidPrimitiveType(primitive_type_value),
+        )?;
+        read_u8(file).map_err(|e| ioe(e, "padding"))?;
+
+        let content = match primitive_type {
+            PrimitiveType::Boolean => AnimationContent::Boolean(read_boolean_track(file)?),
+            PrimitiveType::Float => {
+                let [value] = read_curves(file)?;
+                AnimationContent::Float(value)
+            }
+            PrimitiveType::Integer => {
+                let [value] = read_curves(file)?;
+                AnimationContent::Integer(value)
+            }
+            PrimitiveType::Vector2D => AnimationContent::Vector2D(read_curves(file)?),
+            PrimitiveType::Vector3D => AnimationContent::Vector3D(Box::new(read_curves(file)?)),
+            PrimitiveType::RGBA => AnimationContent::RGBA(Box::new(read_curves(file)?)),
+            PrimitiveType::Texture => {
+                let [texture] = read_curves(file)?;
+                AnimationContent::Texture(texture)
+            }
+            PrimitiveType::Transform => AnimationContent::Transform(Box::new(
+                TransformAnimation::read(file)
+                    .map_err(AnimationElementError::TransformAnimationError)?,
+            )),
+            PrimitiveType::QuaternionTransform => AnimationContent::QuaternionTransform(
+                QuaternionTransformAnimation::read(file)
+                    .map_err(AnimationElementError::TransformAnimationError)?,
+            ),
+            PrimitiveType::MatrixTransform => AnimationContent::MatrixTransform(
+                MatrixTransformAnimation::read(file)
+                    .map_err(AnimationElementError::TransformAnimationError)?,
+            ),
+        };
+
+        Ok(Self {
+            name,
+            target_type,
+            primitive_type,
+            content,
+        })
+    }
+}
@@ -0,0 +1,296 @@
+use crate::deserialize::{read_f32_le, read_i16_le, read_u16_le, read_u32_le, read_u8};
+use std::io;
+use std::io::{Read, Seek, SeekFrom};
+
+#[derive(Debug)]
+pub enum KeyFrameError {
+    IOError(io::Error, &'static str),
+    InvalidInterpolationType(u8),
+    InvalidQuantization(u8),
+    InvalidLoopType(u8),
+}
+
+fn ioe(err: io::Error, content: &'static str) -> KeyFrameError {
+    KeyFrameError::IOError(err, content)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum InterpolationType {
+    Step,
+    Linear,
+    Hermite,
+}
+
+impl InterpolationType {
+    pub fn new(value: u8) -> Option<Self> {
+        Some(match value {
+            0 => Self::Step,
+            1 => Self::Linear,
+            2 => Self::Hermite,
+            _ => return None,
+        })
+    }
+}
+
+/// How the key frames of a group are stored
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum KeyFrameQuantization {
+    /// f32 frame, value, in slope and out slope
+    Hermite128,
+    /// 12 bits frame, 20 bits value, 16 bits in and out slopes
+    Hermite64,
+    /// 8 bits frame, 16 bits value, 12 bits in and out slopes
+    Hermite48,
+    /// f32 frame, value and slope
+    UnifiedHermite96,
+    /// 16 bits frame, value and slope
+    UnifiedHermite48,
+    /// 8 bits frame, 12 bits value and slope
+    UnifiedHermite32,
+    /// f32 frame and value
+    StepLinear64,
+    /// 12 bits frame, 20 bits value
+    StepLinear32,
+}
+
+impl KeyFrameQuantization {
+    pub fn new(value: u8) -> Option<Self> {
+        Some(match value {
+            0 => Self::Hermite128,
+            1 => Self::Hermite64,
+            2 => Self::Hermite48,
+            3 => Self::UnifiedHermite96,
+            4 => Self::UnifiedHermite48,
+            5 => Self::UnifiedHermite32,
+            6 => Self::StepLinear64,
+            7 => Self::StepLinear32,
+            _ => return None,
+        })
+    }
+}
+
+/// What happen before the first frame or after the last frame of a group
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LoopType {
+    None,
+    Repeat,
+    Mirror,
+    RelativeRepeat,
+}
+
+impl LoopType {
+    pub fn new(value: u8) -> Option<Self> {
+        Some(match value {
+            0 => Self::None,
+            1 => Self::Repeat,
+            2 => Self::Mirror,
+            3 => Self::RelativeRepeat,
+            _ => return None,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct KeyFrame {
+    pub frame: f32,
+    pub value: f32,
+    pub in_slope: f32,
+    pub out_slope: f32,
+}
+
+/// sign extend the lowest 12 bits of `value`
+fn sign_extend_12(value: u32) -> f32 {
+    (((value << 20) as i32) >> 20) as f32
+}
+
+impl KeyFrame {
+    fn read<F: Read>(
+        file: &mut F,
+        quantization: KeyFrameQuantization,
+    ) -> Result<Self, KeyFrameError> {
+        let mut key_frame = KeyFrame::default();
+        match quantization {
+            KeyFrameQuantization::Hermite128 => {
+                key_frame.frame = read_f32_le(file).map_err(|e| ioe(e, "hermite 128 frame"))?;
+                key_frame.value = read_f32_le(file).map_err(|e| ioe(e, "hermite 128 value"))?;
+                key_frame.in_slope =
+                    read_f32_le(file).map_err(|e| ioe(e, "hermite 128 in slope"))?;
+                key_frame.out_slope =
+                    read_f32_le(file).map_err(|e| ioe(e, "hermite 128 out slope"))?;
+            }
+            KeyFrameQuantization::Hermite64 => {
+                let frame_and_value =
+                    read_u32_le(file).map_err(|e| ioe(e, "hermite 64 frame and value"))?;
+                key_frame.frame = (frame_and_value & 0xfff) as f32;
+                key_frame.value = (frame_and_value >> 12) as f32;
+                key_frame.in_slope =
+                    read_i16_le(file).map_err(|e| ioe(e, "hermite 64 in slope"))? as f32 / 256.0;
+                key_frame.out_slope =
+                    read_i16_le(file).map_err(|e| ioe(e, "hermite 64 out slope"))? as f32 / 256.0;
+            }
+            KeyFrameQuantization::Hermite48 => {
+                key_frame.frame = read_u8(file).map_err(|e| ioe(e, "hermite 48 frame"))? as f32;
+                key_frame.value = read_u16_le(file).map_err(|e| ioe(e, "hermite 48 value"))? as f32;
+                let mut slopes = [0; 3];
+                file.read_exact(&mut slopes)
+                    .map_err(|e| ioe(e, "hermite 48 slopes"))?;
+                let slopes = slopes[0] as u32 | (slopes[1] as u32) << 8 | (slopes[2] as u32) << 16;
+                key_frame.in_slope = sign_extend_12(slopes) / 32.0;
+                key_frame.out_slope = sign_extend_12(slopes >> 12) / 32.0;
+            }
+            KeyFrameQuantization::UnifiedHermite96 => {
+                key_frame.frame =
+                    read_f32_le(file).map_err(|e| ioe(e, "unified hermite 96 frame"))?;
+                key_frame.value =
+                    read_f32_le(file).map_err(|e| ioe(e, "unified hermite 96 value"))?;
+                key_frame.in_slope =
+                    read_f32_le(file).map_err(|e| ioe(e, "unified hermite 96 slope"))?;
+                key_frame.out_slope = key_frame.in_slope;
+            }
+            KeyFrameQuantization::UnifiedHermite48 => {
+                key_frame.frame =
+                    read_u16_le(file).map_err(|e| ioe(e, "unified hermite 48 frame"))? as f32
+                        / 32.0;
+                key_frame.value =
+                    read_u16_le(file).map_err(|e| ioe(e, "unified hermite 48 value"))? as f32;
+                key_frame.in_slope =
+                    read_i16_le(file).map_err(|e| ioe(e, "unified hermite 48 slope"))? as f32
+                        / 256.0;
+                key_frame.out_slope = key_frame.in_slope;
+            }
+            KeyFrameQuantization::UnifiedHermite32 => {
+                let mut data = [0; 4];
+                file.read_exact(&mut data)
+                    .map_err(|e| ioe(e, "unified hermite 32 key frame"))?;
+                let value_and_slope =
+                    data[1] as u32 | (data[2] as u32) << 8 | (data[3] as u32) << 16;
+                key_frame.frame = data[0] as f32;
+                key_frame.value = (value_and_slope & 0xfff) as f32;
+                key_frame.in_slope = sign_extend_12(value_and_slope >> 12) / 32.0;
+                key_frame.out_slope = key_frame.in_slope;
+            }
+            KeyFrameQuantization::StepLinear64 => {
+                key_frame.frame = read_f32_le(file).map_err(|e| ioe(e, "step linear 64 frame"))?;
+                key_frame.value = read_f32_le(file).map_err(|e| ioe(e, "step linear 64 value"))?;
+            }
+            KeyFrameQuantization::StepLinear32 => {
+                let frame_and_value =
+                    read_u32_le(file).map_err(|e| ioe(e, "step linear 32 key frame"))?;
+                key_frame.frame = (frame_and_value & 0xfff) as f32;
+                key_frame.value = (frame_and_value >> 12) as f32;
+            }
+        };
+        Ok(key_frame)
+    }
+}
+
+/// An animation curve of a single float value
+#[derive(Debug, Clone, PartialEq)]
+pub struct KeyFrameGroup {
+    pub start_frame: f32,
+    pub end_frame: f32,
+    pub pre_repeat: LoopType,
+    pub post_repeat: LoopType,
+    pub curve_index: u16,
+    pub interpolation_type: InterpolationType,
+    pub quantization: KeyFrameQuantization,
+    /// the key frames, with their frame, value and slopes already dequantized
+    pub key_frames: Vec<KeyFrame>,
+}
+
+impl KeyFrameGroup {
+    /// a group that always return the same value
+    pub fn new_constant(value: f32) -> Self {
+        Self {
+            start_frame: 0.0,
+            end_frame: 0.0,
+            pre_repeat: LoopType::None,
+            post_repeat: LoopType::None,
+            curve_index: 0,
+            interpolation_type: InterpolationType::Linear,
+            quantization: KeyFrameQuantization::StepLinear64,
+            key_frames: vec![KeyFrame {
+                frame: 0.0,
+                value,
+                in_slope: 0.0,
+                out_slope: 0.0,
+            }],
+        }
+    }
+
+    pub fn read<F: Read + Seek>(file: &mut F) -> Result<Self, KeyFrameError> {
+        let start_frame = read_f32_le(file).map_err(|e| ioe(e, "start frame"))?;
+        let end_frame = read_f32_le(file).map_err(|e| ioe(e, "end frame"))?;
+
+        let pre_repeat_value = read_u8(file).map_err(|e| ioe(e, "pre repeat"))?;
+        let pre_repeat = LoopType::new(pre_repeat_value)
+            .ok_or(KeyFrameError::InvalidLoopType(pre_repeat_value))?;
+        let post_repeat_value = read_u8(file).map_err(|e| ioe(e, "post repeat"))?;
+        let post_repeat = LoopType::new(post_repeat_value)
+            .ok_or(KeyFrameError::InvalidLoopType(post_repeat_value))?;
+        let curve_index = read_u16_le(file).map_err(|e| ioe(e, "curve index"))?;
+
+        let flags = read_u32_le(file).map_err(|e| ioe(e, "key frame flags"))?;
+        let interpolation_type_value = (flags & 0xf) as u8;
+        let interpolation_type = InterpolationType::new(interpolation_type_value).ok_or(
+            KeyFrameError::InvalidInterpolationType(interpolation_type_value),
+        )?;
+        let quantization_value = (flags >> 8 & 0x1f) as u8;
+        let quantization = KeyFrameQuantization::new(quantization_value)
+            .ok_or(KeyFrameError::InvalidQuantization(quantization_value))?;
+        let key_frame_count = flags >> 16;
+
+        let value_scale = read_f32_le(file).map_err(|e| ioe(e, "value scale"))?;
+        let value_offset = read_f32_le(file).map_err(|e| ioe(e, "value offset"))?;
+        let frame_scale = read_f32_le(file).map_err(|e| ioe(e, "frame scale"))?;
+        // the inverse of the duration of the group, which can be computed from the frames
+        read_f32_le(file).map_err(|e| ioe(e, "inverse duration"))?;
+
+        let key_frames_offset = read_u32_le(file).map_err(|e| ioe(e, "key frames offset"))?;
+        file.seek(SeekFrom::Start(key_frames_offset as u64))
+            .map_err(|e| ioe(e, "key frames"))?;
+
+        let mut key_frames = Vec::with_capacity(key_frame_count as usize);
+        for _ in 0..key_frame_count {
+            let mut key_frame = KeyFrame::read(file, quantization)?;
+            key_frame.frame *= frame_scale;
+            key_frame.value = key_frame.value * value_scale + value_offset;
+            key_frame.in_slope *= value_scale;
+            key_frame.out_slope *= value_scale;
+            key_frames.push(key_frame);
+        }
+
+        Ok(Self {
+            start_frame,
+            end_frame,
+            pre_repeat,
+            post_repeat,
+            curve_index,
+            interpolation_type,
+            quantization,
+            key_frames,
+        })
+    }
+
+    /// read a group referenced by the next word, or stored directly in it when `constant` is set
+    pub fn read_maybe_constant<F: Read + Seek>(
+        file: &mut F,
+        constant: bool,
+    ) -> Result<Self, KeyFrameError> {
+        if constant {
+            return Ok(Self::new_constant(
+                read_f32_le(file).map_err(|e| ioe(e, "constant value"))?,
+            ));
+        };
+        let group_offset = read_u32_le(file).map_err(|e| ioe(e, "key frame group offset"))?;
+        let position = file
+            .stream_position()
+            .map_err(|e| ioe(e, "key frame group offset"))?;
+        file.seek(SeekFrom::Start(group_offset as u64))
+            .map_err(|e| ioe(e, "key frame group"))?;
+        let group = Self::read(file)?;
+        file.seek(SeekFrom::Start(position))
+            .map_err(|e| ioe(e, "after key frame group"))?;
+        Ok(group)
+    }
+}
@@ -0,0 +1,174 @@
+use crate::animation::sampler::evaluate_color;
+use crate::animation::{Animation, AnimationContent, AnimationError, AnimationTargetType};
+use crate::animation::{AnimationElement, KeyFrameGroup};
+use crate::deserialize::{read_referenced_null_terminated_ascii_string, read_u32_le};
+use crate::model::{Material, MaterialColors, TextureCoordinator};
+use std::io;
+use std::io::{Read, Seek, SeekFrom};
+
+fn ioe(err: io::Error, content: &'static str) -> AnimationError {
+    AnimationError::IOError(err, content)
+}
+
+/// An animation of the colors, texture coordinates and textures of materials. Each element
+/// animates a property of the material with the same name.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MaterialAnimation {
+    pub animation: Animation,
+    /// the textures a texture pattern curve can switch to, by index
+    pub texture_names: Vec<String>,
+}
+
+impl MaterialAnimation {
+    pub fn read<F: Read + Seek>(file: &mut F) -> Result<Self, AnimationError> {
+        let start = file
+            .stream_position()
+            .map_err(|e| ioe(e, "material animation"))?;
+        let animation = Animation::read(file)?;
+
+        // the texture names follow the fields shared by every animation
+        file.seek(SeekFrom::Start(start + 0x18))
+            .map_err(|e| ioe(e, "texture names offset"))?;
+        let texture_names_offset = read_u32_le(file).map_err(|e| ioe(e, "texture names offset"))?;
+        let texture_name_count = read_u32_le(file).map_err(|e| ioe(e, "texture name count"))?;
+        file.seek(SeekFrom::Start(texture_names_offset as u64))
+            .map_err(|e| ioe(e, "texture names"))?;
+        let mut texture_names = Vec::with_capacity(texture_name_count as usize);
+        for _ in 0..texture_name_count {
+            texture_names.push(
+                read_referenced_null_terminated_ascii_string(file)
+                    .map_err(|e| ioe(e, "texture name"))?
+                    .ok_or(AnimationError::NullReference("texture name"))?,
+            );
+        }
+
+        Ok(Self {
+            animation,
+            texture_names,
+        })
+    }
+
+    /// return the elements animating the material with the given name
+    pub fn get_material_elements<'a>(
+        &'a self,
+        material_name: &'a str,
+    ) -> impl Iterator<Item = &'a AnimationElement> {
+        self.animation.get_elements(material_name)
+    }
+
+    /// compute the animated properties of a material at the given frame. Properties that aren't
+    /// animated keep the value of the material.
+    pub fn sample_material(&self, material: &Material, frame: f32) -> MaterialState {
+        let mut state = MaterialState::new(material);
+        let frame = self.animation.wrap_frame(frame);
+        for element in self.get_material_elements(&material.name) {
+            let target_type = match element.get_target_type() {
+                Some(target_type) => target_type,
+                None => continue,
+            };
+            match (target_type, &element.content) {
+                (target_type, AnimationContent::RGBA(curves)) => {
+                    let color = match state.get_color_mut(target_type) {
+                        Some(color) => color,
+                        None => continue,
+                    };
+                    evaluate_color(curves, color, frame);
+                }
+                (
+                    AnimationTargetType::MaterialTextureCoordinatorScale(index),
+                    AnimationContent::Vector2D(curves),
+                ) => {
+                    if let Some(coordinator) = state.texture_coordinators.get_mut(index as usize) {
+                        evaluate_vector2(curves, &mut coordinator.scale, frame);
+                    };
+                }
+                (
+                    AnimationTargetType::MaterialTextureCoordinatorTranslation(index),
+                    AnimationContent::Vector2D(curves),
+                ) => {
+                    if let Some(coordinator) = state.texture_coordinators.get_mut(index as usize) {
+                        evaluate_vector2(curves, &mut coordinator.translation, frame);
+                    };
+                }
+                (
+                    AnimationTargetType::MaterialTextureCoordinatorRotation(index),
+                    AnimationContent::Float(Some(curve)),
+                ) => {
+                    if let Some(coordinator) = state.texture_coordinators.get_mut(index as usize) {
+                        coordinator.rotation = curve.evaluate(frame);
+                    };
+                }
+                (
+                    AnimationTargetType::MaterialTextureMapperTexture(index),
+                    AnimationContent::Texture(Some(curve)),
+                ) => {
+                    let texture_id = curve.evaluate(frame).max(0.0) as usize;
+                    if let (Some(texture_name), Some(name)) = (
+                        state.texture_names.get_mut(index as usize),
+                        self.texture_names.get(texture_id),
+                    ) {
+                        *texture_name = Some(name.clone());
+                    };
+                }
+                _ => (),
+            }
+        }
+        state
+    }
+}
+
+fn evaluate_vector2(curves: &[Option<KeyFrameGroup>; 2], value: &mut [f32; 2], frame: f32) {
+    for (component, curve) in value.iter_mut().zip(curves.iter()) {
+        if let Some(curve) = curve {
+            *component = curve.evaluate(frame);
+        };
+    }
+}
+
+/// The properties of a material that can be animated, at a frame of an animation
+#[derive(Debug, Clone, PartialEq)]
+pub struct MaterialState {
+    pub colors: MaterialColors,
+    pub texture_coordinators: Vec<TextureCoordinator>,
+    /// the texture bound to each texture mapper
+    pub texture_names: [Option<String>; 3],
+    pub border_colors: Vec<[u8; 4]>,
+}
+
+impl MaterialState {
+    /// the properties of the material when it isn't animated
+    pub fn new(material: &Material) -> Self {
+        Self {
+            colors: material.parameters.colors,
+            texture_coordinators: material.parameters.texture_coordinators.clone(),
+            texture_names: [
+                material.texture0_name.clone(),
+                material.texture1_name.clone(),
+                material.texture2_name.clone(),
+            ],
+            border_colors: material
+                .texture_mappers
+                .iter()
+                .map(|mapper| mapper.border_color)
+                .collect(),
+        }
+    }
+
+    fn get_color_mut(&mut self, target_type: AnimationTargetType) -> Option<&mut [u8; 4]> {
+        Some(match target_type {
+            AnimationTargetType::MaterialEmission => &mut self.colors.emission,
+            AnimationTargetType::MaterialAmbient => &mut self.colors.ambient,
+            AnimationTargetType::MaterialDiffuse => &mut self.colors.diffuse,
+            AnimationTargetType::MaterialSpecular0 => &mut self.colors.specular0,
+            AnimationTargetType::MaterialSpecular1 => &mut self.colors.specular1,
+            AnimationTargetType::MaterialConstant(index) => {
+                self.colors.constant.get_mut(index as usize)?
+            }
+            AnimationTargetType::MaterialBlendColor => &mut self.colors.blend,
+            AnimationTargetType::MaterialTextureMapperBorderColor(index) => {
+                self.border_colors.get_mut(index as usize)?
+            }
+            _ => return None,
+        })
+    }
+}
@@ -0,0 +1,23 @@
+mod animationdata;
+pub use animationdata::{Animation, AnimationError, AnimationLoopMode};
+
+mod element;
+pub use element::{AnimationContent, AnimationElement, AnimationElementError};
+pub use element::{AnimationTargetType, PrimitiveType};
+
+mod keyframe;
+pub use keyframe::{
+    InterpolationType, KeyFrame, KeyFrameError, KeyFrameGroup, KeyFrameQuantization, LoopType,
+};
+
+mod transform;
+pub use transform::{BakedTrack, MatrixTransformAnimation, QuaternionTransformAnimation};
+pub use transform::{TransformAnimation, TransformAnimationError};
+
+mod material;
+pub use material::{MaterialAnimation, MaterialState};
+
+mod sampler;
+pub use sampler::SkeletonPose;
+
+mod scene;
@@ -0,0 +1,450 @@
+use crate::animation::{Animation, AnimationContent, AnimationLoopMode, AnimationTargetType};
+use crate::animation::{BakedTrack, InterpolationType, KeyFrame, KeyFrameGroup, LoopType};
+use crate::animation::{
+    MatrixTransformAnimation, QuaternionTransformAnimation, TransformAnimation,
+};
+use crate::model::{compose_world_transforms, Bone};
+use crate::{Matrix3x4, Quaternion, Vector3};
+
+/// wrap `frame` in the range from `start` to `end`, and return it with the number of cycles whose
+/// value change should be added to the curve (only non-zero for relative repeat)
+fn wrap_frame(frame: f32, start: f32, end: f32, loop_type: LoopType) -> (f32, f32) {
+    let duration = end - start;
+    if duration <= 0.0 {
+        return (start, 0.0);
+    };
+    let cycle = ((frame - start) / duration).floor();
+    let in_cycle = (frame - start) - cycle * duration;
+    match loop_type {
+        LoopType::None => (frame.clamp(start, end), 0.0),
+        LoopType::Repeat => (start + in_cycle, 0.0),
+        LoopType::RelativeRepeat => (start + in_cycle, cycle),
+        LoopType::Mirror => {
+            if cycle.rem_euclid(2.0) == 0.0 {
+                (start + in_cycle, 0.0)
+            } else {
+                (end - in_cycle, 0.0)
+            }
+        }
+    }
+}
+
+fn interpolate(
+    first: &KeyFrame,
+    second: &KeyFrame,
+    frame: f32,
+    interpolation: InterpolationType,
+) -> f32 {
+    let duration = second.frame - first.frame;
+    if duration <= 0.0 {
+        return second.value;
+    };
+    let t = (frame - first.frame) / duration;
+    match interpolation {
+        InterpolationType::Step => first.value,
+        InterpolationType::Linear => first.value + (second.value - first.value) * t,
+        InterpolationType::Hermite => {
+            let t2 = t * t;
+            let t3 = t2 * t;
+            (2.0 * t3 - 3.0 * t2 + 1.0) * first.value
+                + (t3 - 2.0 * t2 + t) * duration * first.out_slope
+                + (-2.0 * t3 + 3.0 * t2) * second.value
+                + (t3 - t2) * duration * second.in_slope
+        }
+    }
+}
+
+impl KeyFrameGroup {
+    /// return the value of the curve at the given frame
+    pub fn evaluate(&self, frame: f32) -> f32 {
+        let (first, last) = match (self.key_frames.first(), self.key_frames.last()) {
+            (Some(first), Some(last)) => (first, last),
+            _ => return 0.0,
+        };
+        if self.key_frames.len() == 1 {
+            return first.value;
+        };
+
+        let (start, end) = if self.end_frame > self.start_frame {
+            (self.start_frame, self.end_frame)
+        } else {
+            (first.frame, last.frame)
+        };
+        let (frame, cycle) = if frame < start {
+            wrap_frame(frame, start, end, self.pre_repeat)
+        } else if frame > end {
+            wrap_frame(frame, start, end, self.post_repeat)
+        } else {
+            (frame, 0.0)
+        };
+        let repeat_offset = (last.value - first.value) * cycle;
+
+        if frame <= first.frame {
+            return first.value + repeat_offset;
+        };
+        if frame >= last.frame {
+            return last.value + repeat_offset;
+        };
+        let next = self
+            .key_frames
+            .iter()
+            .position(|key_frame| key_frame.frame > frame)
+            .unwrap_or(self.key_frames.len() - 1);
+        interpolate(
+            &self.key_frames[next - 1],
+            &self.key_frames[next],
+            frame,
+            self.interpolation_type,
+        ) + repeat_offset
+    }
+}
+
+impl<T: Copy> BakedTrack<T> {
+    /// return the two values around the frame, and the position of the frame between them
+    fn get_surrounding(&self, frame: f32) -> Option<(T, T, f32)> {
+        let last = self.values.len().checked_sub(1)?;
+        let position = (frame - self.start_frame).clamp(0.0, last as f32);
+        let index = position.floor() as usize;
+        let next = (index + 1).min(last);
+        Some((
+            self.values[index],
+            self.values[next],
+            position - index as f32,
+        ))
+    }
+}
+
+impl BakedTrack<bool> {
+    pub fn sample(&self, frame: f32) -> Option<bool> {
+        let (value, _, _) = self.get_surrounding(frame)?;
+        Some(value)
+    }
+}
+
+impl BakedTrack<Vector3> {
+    pub fn sample(&self, frame: f32) -> Option<Vector3> {
+        let (first, second, factor) = self.get_surrounding(frame)?;
+        Some(first.lerp(second, factor))
+    }
+}
+
+impl BakedTrack<Quaternion> {
+    pub fn sample(&self, frame: f32) -> Option<Quaternion> {
+        let (first, second, factor) = self.get_surrounding(frame)?;
+        Some(first.slerp(second, factor))
+    }
+}
+
+pub(crate) fn evaluate_axes(
+    groups: &[Option<KeyFrameGroup>; 3],
+    default: Vector3,
+    frame: f32,
+) -> Vector3 {
+    let evaluate = |group: &Option<KeyFrameGroup>, default: f32| match group {
+        Some(group) => group.evaluate(frame),
+        None => default,
+    };
+    Vector3::new(
+        evaluate(&groups[0], default.x),
+        evaluate(&groups[1], default.y),
+        evaluate(&groups[2], default.z),
+    )
+}
+
+/// replace each component of a color with the value of its curve, if it has one. The curves go
+/// from 0 to 1.
+pub(crate) fn evaluate_color(curves: &[Option<KeyFrameGroup>; 4], color: &mut [u8; 4], frame: f32) {
+    for (component, curve) in color.iter_mut().zip(curves.iter()) {
+        if let Some(curve) = curve {
+            *component = (curve.evaluate(frame).clamp(0.0, 1.0) * 255.0).round() as u8;
+        };
+    }
+}
+
+impl TransformAnimation {
+    /// return the scale, euler rotation and translation at the given frame. The components
+    /// without curve keep the given value.
+    pub fn sample_components(
+        &self,
+        scale: Vector3,
+        rotation: Vector3,
+        translation: Vector3,
+        frame: f32,
+    ) -> (Vector3, Vector3, Vector3) {
+        (
+            evaluate_axes(&self.scale, scale, frame),
+            evaluate_axes(&self.rotation, rotation, frame),
+            evaluate_axes(&self.translation, translation, frame),
+        )
+    }
+
+    /// return the local transform of the bone at the given frame
+    pub fn sample(&self, bone: &Bone, frame: f32) -> Matrix3x4 {
+        let (scale, rotation, translation) =
+            self.sample_components(bone.scale, bone.rotation, bone.translation, frame);
+        Matrix3x4::from_srt_euler(scale, rotation, translation)
+    }
+}
+
+impl QuaternionTransformAnimation {
+    /// return the local transform of the bone at the given frame
+    pub fn sample(&self, bone: &Bone, frame: f32) -> Matrix3x4 {
+        let scale = self.scale.as_ref().and_then(|track| track.sample(frame));
+        let rotation = self.rotation.as_ref().and_then(|track| track.sample(frame));
+        let translation = self
+            .translation
+            .as_ref()
+            .and_then(|track| track.sample(frame));
+        Matrix3x4::from_srt(
+            scale.unwrap_or(bone.scale),
+            rotation.unwrap_or_else(|| Quaternion::from_euler(bone.rotation)),
+            translation.unwrap_or(bone.translation),
+        )
+    }
+}
+
+impl MatrixTransformAnimation {
+    /// return the local transform stored for the frame, or `None` if there is no matrix
+    pub fn sample(&self, frame: f32) -> Option<Matrix3x4> {
+        let last = self.matrices.len().checked_sub(1)?;
+        let index = (frame - self.start_frame).clamp(0.0, last as f32) as usize;
+        Some(self.matrices[index])
+    }
+}
+
+/// The transforms of every bone of a skeleton at a frame of an animation
+#[derive(Debug, Clone, PartialEq)]
+pub struct SkeletonPose {
+    /// the transform of each bone relative to its parent
+    pub local_transforms: Vec<Matrix3x4>,
+    /// the transform of each bone relative to the model
+    pub world_transforms: Vec<Matrix3x4>,
+}
+
+impl Animation {
+    /// bring a frame in the range of the animation, looping it if the animation loop
+    pub fn wrap_frame(&self, frame: f32) -> f32 {
+        if self.frame_count <= 0.0 {
+            return 0.0;
+        };
+        match self.loop_mode {
+            AnimationLoopMode::Loop => frame.rem_euclid(self.frame_count),
+            AnimationLoopMode::Once => frame.clamp(0.0, self.frame_count),
+        }
+    }
+
+    /// return the local transform of a bone at the given frame, or `None` if the bone isn't
+    /// animated
+    pub fn sample_bone(&self, bone: &Bone, frame: f32) -> Option<Matrix3x4> {
+        let frame = self.wrap_frame(frame);
+        match &self.get_element(&bone.name)?.content {
+            AnimationContent::Transform(transform) => Some(transform.sample(bone, frame)),
+            AnimationContent::QuaternionTransform(transform) => Some(transform.sample(bone, frame)),
+            AnimationContent::MatrixTransform(transform) => transform.sample(frame),
+            _ => None,
+        }
+    }
+
+    /// return the visibility of the object node with the given name at the given frame, or `None`
+    /// if its visibility isn't animated
+    pub fn sample_node_visibility(&self, node_name: &str, frame: f32) -> Option<bool> {
+        let frame = self.wrap_frame(frame);
+        self.get_elements(node_name)
+            .filter(|element| {
+                element.get_target_type() == Some(AnimationTargetType::MeshNodeVisibility)
+            })
+            .find_map(|element| match &element.content {
+                AnimationContent::Boolean(track) => track.sample(frame),
+                _ => None,
+            })
+    }
+
+    /// compute the pose of a skeleton at the given frame. Bones that aren't animated keep their
+    /// bind pose.
+    pub fn sample_skeleton(&self, skeleton: &[Bone], frame: f32) -> SkeletonPose {
+        let local_transforms: Vec<Matrix3x4> = skeleton
+            .iter()
+            .map(|bone| {
+                self.sample_bone(bone, frame)
+                    .unwrap_or_else(|| bone.get_local_transform())
+            })
+            .collect();
+        let world_transforms = compose_world_transforms(skeleton, &local_transforms);
+        SkeletonPose {
+            local_transforms,
+            world_transforms,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::animation::KeyFrameQuantization;
+
+    /// build a curve from (frame, value, in slope, out slope) key frames
+    fn curve(
+        key_frames: &[(f32, f32, f32, f32)],
+        interpolation_type: InterpolationType,
+        start_frame: f32,
+        end_frame: f32,
+        pre_repeat: LoopType,
+        post_repeat: LoopType,
+    ) -> KeyFrameGroup {
+        KeyFrameGroup {
+            start_frame,
+            end_frame,
+            pre_repeat,
+            post_repeat,
+            curve_index: 0,
+            interpolation_type,
+            quantization: KeyFrameQuantization::Hermite128,
+            key_frames: key_frames
+                .iter()
+                .map(|&(frame, value, in_slope, out_slope)| KeyFrame {
+                    frame,
+                    value,
+                    in_slope,
+                    out_slope,
+                })
+                .collect(),
+        }
+    }
+
+    /// a linear ramp from 0 at frame 0 to 10 at frame 10
+    fn ramp(pre_repeat: LoopType, post_repeat: LoopType) -> KeyFrameGroup {
+        curve(
+            &[(0.0, 0.0, 0.0, 0.0), (10.0, 10.0, 0.0, 0.0)],
+            InterpolationType::Linear,
+            0.0,
+            10.0,
+            pre_repeat,
+            post_repeat,
+        )
+    }
+
+    fn assert_close(value: f32, expected: f32) {
+        assert!(
+            (value - expected).abs() < 1e-5,
+            "{} is not {}",
+            value,
+            expected
+        );
+    }
+
+    #[test]
+    fn hermite_interpolation() {
+        let group = curve(
+            &[(0.0, 0.0, 0.0, 1.0), (10.0, 10.0, 2.0, 0.0)],
+            InterpolationType::Hermite,
+            0.0,
+            10.0,
+            LoopType::None,
+            LoopType::None,
+        );
+        // at t = 0.5: 0.125 * 10 * 1 + 0.5 * 10 - 0.125 * 10 * 2
+        assert_close(group.evaluate(5.0), 3.75);
+        // at t = 0.2: 0.128 * 10 * 1 + 0.104 * 10 - 0.032 * 10 * 2
+        assert_close(group.evaluate(2.0), 1.68);
+        assert_close(group.evaluate(0.0), 0.0);
+        assert_close(group.evaluate(10.0), 10.0);
+    }
+
+    #[test]
+    fn linear_interpolation() {
+        let group = curve(
+            &[
+                (0.0, 0.0, 0.0, 0.0),
+                (10.0, 20.0, 0.0, 0.0),
+                (20.0, 10.0, 0.0, 0.0),
+            ],
+            InterpolationType::Linear,
+            0.0,
+            20.0,
+            LoopType::None,
+            LoopType::None,
+        );
+        assert_close(group.evaluate(2.5), 5.0);
+        assert_close(group.evaluate(15.0), 15.0);
+        assert_close(group.evaluate(10.0), 20.0);
+    }
+
+    #[test]
+    fn step_interpolation() {
+        let group = curve(
+            &[(0.0, 1.0, 0.0, 0.0), (10.0, 5.0, 0.0, 0.0)],
+            InterpolationType::Step,
+            0.0,
+            10.0,
+            LoopType::None,
+            LoopType::None,
+        );
+        assert_close(group.evaluate(0.0), 1.0);
+        assert_close(group.evaluate(9.9), 1.0);
+        assert_close(group.evaluate(10.0), 5.0);
+    }
+
+    #[test]
+    fn no_repeat_clamps() {
+        let group = ramp(LoopType::None, LoopType::None);
+        assert_close(group.evaluate(-5.0), 0.0);
+        assert_close(group.evaluate(15.0), 10.0);
+    }
+
+    #[test]
+    fn repeat() {
+        let group = ramp(LoopType::Repeat, LoopType::Repeat);
+        assert_close(group.evaluate(13.0), 3.0);
+        assert_close(group.evaluate(27.5), 7.5);
+        assert_close(group.evaluate(-2.0), 8.0);
+    }
+
+    #[test]
+    fn mirror() {
+        let group = ramp(LoopType::Mirror, LoopType::Mirror);
+        assert_close(group.evaluate(13.0), 7.0);
+        assert_close(group.evaluate(23.0), 3.0);
+        assert_close(group.evaluate(-2.0), 2.0);
+    }
+
+    #[test]
+    fn relative_repeat() {
+        let group = ramp(LoopType::RelativeRepeat, LoopType::RelativeRepeat);
+        // each cycle adds the change of value over the curve, 10
+        assert_close(group.evaluate(13.0), 13.0);
+        assert_close(group.evaluate(25.0), 25.0);
+        assert_close(group.evaluate(-2.0), -2.0);
+        assert_close(group.evaluate(-12.0), -12.0);
+    }
+
+    #[test]
+    fn single_key_frame() {
+        let group = curve(
+            &[(3.0, 4.0, 1.0, 1.0)],
+            InterpolationType::Hermite,
+            0.0,
+            10.0,
+            LoopType::Repeat,
+            LoopType::Repeat,
+        );
+        assert_close(group.evaluate(-5.0), 4.0);
+        assert_close(group.evaluate(3.0), 4.0);
+        assert_close(group.evaluate(100.0), 4.0);
+    }
+
+    #[test]
+    fn range_from_key_frames() {
+        // without a valid range, the curve loops between its first and last key frames
+        let group = curve(
+            &[(2.0, 0.0, 0.0, 0.0), (6.0, 8.0, 0.0, 0.0)],
+            InterpolationType::Linear,
+            0.0,
+            0.0,
+            LoopType::None,
+            LoopType::Repeat,
+        );
+        assert_close(group.evaluate(4.0), 4.0);
+        assert_close(group.evaluate(7.0), 2.0);
+        assert_close(group.evaluate(1.0), 0.0);
+    }
+}
@@ -0,0 +1,203 @@
+use crate::animation::sampler::{evaluate_axes, evaluate_color};
+use crate::animation::{Animation, AnimationContent, AnimationTargetType, KeyFrameGroup};
+use crate::scene::{Camera, CameraProjection, CameraView, Fog, Light, LightContent};
+
+fn evaluate_float(curve: &Option<KeyFrameGroup>, value: &mut f32, frame: f32) {
+    if let Some(curve) = curve {
+        *value = curve.evaluate(frame);
+    };
+}
+
+fn evaluate_color_f32(curves: &[Option<KeyFrameGroup>; 4], color: &mut [f32; 4], frame: f32) {
+    for (component, curve) in color.iter_mut().zip(curves.iter()) {
+        evaluate_float(curve, component, frame);
+    }
+}
+
+impl Animation {
+    /// iterate over the animated properties of the target with the given name
+    fn get_target_contents<'a>(
+        &'a self,
+        name: &'a str,
+    ) -> impl Iterator<Item = (AnimationTargetType, &'a AnimationContent)> {
+        self.get_elements(name).filter_map(|element| {
+            element
+                .get_target_type()
+                .map(|target_type| (target_type, &element.content))
+        })
+    }
+
+    /// return the light with the properties animated by this light animation at the given frame
+    pub fn sample_light(&self, light: &Light, frame: f32) -> Light {
+        let mut light = light.clone();
+        let frame = self.wrap_frame(frame);
+        for (target_type, content) in self.get_target_contents(&light.name) {
+            match (target_type, content) {
+                (AnimationTargetType::LightTransform, AnimationContent::Transform(transform)) => {
+                    let (scale, rotation, translation) = transform.sample_components(
+                        light.scale,
+                        light.rotation,
+                        light.translation,
+                        frame,
+                    );
+                    light.scale = scale;
+                    light.rotation = rotation;
+                    light.translation = translation;
+                }
+                (AnimationTargetType::LightIsEnabled, AnimationContent::Boolean(track)) => {
+                    if let Some(is_enabled) = track.sample(frame) {
+                        light.is_enabled = is_enabled;
+                    };
+                }
+                (target_type, AnimationContent::RGBA(curves)) => match &mut light.content {
+                    LightContent::Hemisphere(_) => (),
+                    LightContent::Ambient(ambient) => {
+                        if target_type == AnimationTargetType::LightAmbient {
+                            evaluate_color_f32(curves, &mut ambient.color, frame);
+                        };
+                    }
+                    LightContent::Vertex(vertex) => {
+                        let color = match target_type {
+                            AnimationTargetType::LightAmbient => &mut vertex.ambient_color,
+                            AnimationTargetType::LightDiffuse => &mut vertex.diffuse_color,
+                            AnimationTargetType::LightSpecular0 => &mut vertex.specular0_color,
+                            AnimationTargetType::LightSpecular1 => &mut vertex.specular1_color,
+                            _ => continue,
+                        };
+                        evaluate_color_f32(curves, color, frame);
+                    }
+                    LightContent::Fragment(fragment) => {
+                        let color = match target_type {
+                            AnimationTargetType::LightAmbient => &mut fragment.ambient_color,
+                            AnimationTargetType::LightDiffuse => &mut fragment.diffuse_color,
+                            AnimationTargetType::LightSpecular0 => &mut fragment.specular0_color,
+                            AnimationTargetType::LightSpecular1 => &mut fragment.specular1_color,
+                            _ => continue,
+                        };
+                        evaluate_color(curves, color, frame);
+                    }
+                },
+                (AnimationTargetType::LightDirection, AnimationContent::Vector3D(curves)) => {
+                    let direction = match &mut light.content {
+                        LightContent::Hemisphere(hemisphere) => &mut hemisphere.direction,
+                        LightContent::Ambient(_) => continue,
+                        LightContent::Vertex(vertex) => &mut vertex.direction,
+                        LightContent::Fragment(fragment) => &mut fragment.direction,
+                    };
+                    *direction = evaluate_axes(curves, *direction, frame);
+                }
+                (
+                    AnimationTargetType::LightDistanceAttenuationStart,
+                    AnimationContent::Float(curve),
+                ) => {
+                    if let LightContent::Fragment(fragment) = &mut light.content {
+                        evaluate_float(curve, &mut fragment.attenuation_start, frame);
+                    };
+                }
+                (
+                    AnimationTargetType::LightDistanceAttenuationEnd,
+                    AnimationContent::Float(curve),
+                ) => {
+                    if let LightContent::Fragment(fragment) = &mut light.content {
+                        evaluate_float(curve, &mut fragment.attenuation_end, frame);
+                    };
+                }
+                _ => (),
+            }
+        }
+        light
+    }
+
+    /// return the camera with the properties animated by this camera animation at the given
+    /// frame
+    pub fn sample_camera(&self, camera: &Camera, frame: f32) -> Camera {
+        let mut camera = camera.clone();
+        let frame = self.wrap_frame(frame);
+        for (target_type, content) in self.get_target_contents(&camera.name) {
+            match (target_type, content, &mut camera.view) {
+                (
+                    AnimationTargetType::CameraTransform,
+                    AnimationContent::Transform(transform),
+                    _,
+                ) => {
+                    let (scale, rotation, translation) = transform.sample_components(
+                        camera.scale,
+                        camera.rotation,
+                        camera.translation,
+                        frame,
+                    );
+                    camera.scale = scale;
+                    camera.rotation = rotation;
+                    camera.translation = translation;
+                }
+                (
+                    AnimationTargetType::CameraTarget,
+                    AnimationContent::Vector3D(curves),
+                    CameraView::Aim { target, .. },
+                )
+                | (
+                    AnimationTargetType::CameraTarget,
+                    AnimationContent::Vector3D(curves),
+                    CameraView::LookAt { target, .. },
+                ) => *target = evaluate_axes(curves, *target, frame),
+                (
+                    AnimationTargetType::CameraUpVector,
+                    AnimationContent::Vector3D(curves),
+                    CameraView::LookAt { up_vector, .. },
+                ) => *up_vector = evaluate_axes(curves, *up_vector, frame),
+                (
+                    AnimationTargetType::CameraViewRotation,
+                    AnimationContent::Vector3D(curves),
+                    CameraView::Rotate { rotation },
+                ) => *rotation = evaluate_axes(curves, *rotation, frame),
+                (
+                    AnimationTargetType::CameraTwist,
+                    AnimationContent::Float(curve),
+                    CameraView::Aim { twist, .. },
+                ) => evaluate_float(curve, twist, frame),
+                (target_type, AnimationContent::Float(curve), _) => {
+                    let value = match (target_type, &mut camera.projection) {
+                        (
+                            AnimationTargetType::CameraNear,
+                            CameraProjection::Perspective { near, .. }
+                            | CameraProjection::Orthogonal { near, .. },
+                        ) => near,
+                        (
+                            AnimationTargetType::CameraFar,
+                            CameraProjection::Perspective { far, .. }
+                            | CameraProjection::Orthogonal { far, .. },
+                        ) => far,
+                        (
+                            AnimationTargetType::CameraAspectRatio,
+                            CameraProjection::Perspective { aspect_ratio, .. }
+                            | CameraProjection::Orthogonal { aspect_ratio, .. },
+                        ) => aspect_ratio,
+                        (
+                            AnimationTargetType::CameraFovYOrHeight,
+                            CameraProjection::Perspective { fov_y: value, .. }
+                            | CameraProjection::Orthogonal { height: value, .. },
+                        ) => value,
+                        _ => continue,
+                    };
+                    evaluate_float(curve, value, frame);
+                }
+                _ => (),
+            }
+        }
+        camera
+    }
+
+    /// return the fog with the properties animated by this fog animation at the given frame
+    pub fn sample_fog(&self, fog: &Fog, frame: f32) -> Fog {
+        let mut fog = fog.clone();
+        let frame = self.wrap_frame(frame);
+        for (target_type, content) in self.get_target_contents(&fog.name) {
+            if let (AnimationTargetType::FogColor, AnimationContent::RGBA(curves)) =
+                (target_type, content)
+            {
+                evaluate_color(curves, &mut fog.color, frame);
+            };
+        }
+        fog
+    }
+}
@@ -0,0 +1,197 @@
+use crate::animation::{KeyFrameError, KeyFrameGroup, LoopType};
+use crate::deserialize::{
+    read_f32_le, read_matrix3x4_f32, read_quaternion_f32, read_u32_le, read_u8, read_vector3_f32,
+};
+use crate::{Matrix3x4, Quaternion, Vector3};
+use std::io;
+use std::io::{Read, Seek, SeekFrom};
+
+#[derive(Debug)]
+pub enum TransformAnimationError {
+    IOError(io::Error, &'static str),
+    KeyFrameError(KeyFrameError, &'static str),
+    InvalidLoopType(u8),
+}
+
+fn ioe(err: io::Error, content: &'static str) -> TransformAnimationError {
+    TransformAnimationError::IOError(err, content)
+}
+
+/// Independent curves for the scale, the euler rotation and the translation of each axis. A
+/// missing curve keep the value of the bind pose.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TransformAnimation {
+    pub scale: [Option<KeyFrameGroup>; 3],
+    pub rotation: [Option<KeyFrameGroup>; 3],
+    pub translation: [Option<KeyFrameGroup>; 3],
+}
+
+impl TransformAnimation {
+    pub fn read<F: Read + Seek>(file: &mut F) -> Result<Self, TransformAnimationError> {
+        let flags = read_u32_le(file).map_err(|e| ioe(e, "transform flags"))?;
+
+        let mut groups: [Option<KeyFrameGroup>; 9] = Default::default();
+        let mut position = file
+            .stream_position()
+            .map_err(|e| ioe(e, "transform curves"))?;
+        for (index, group) in groups.iter_mut().enumerate() {
+            // there is a constant flag for the unused W component of the rotation
+            let constant_bit = if index >= 6 { index + 7 } else { index + 6 };
+            let constant = flags >> constant_bit & 1 != 0;
+            let exists = flags >> (index + 16) & 1 == 0;
+
+            file.seek(SeekFrom::Start(position))
+                .map_err(|e| ioe(e, "transform curve"))?;
+            position += 4;
+            if exists {
+                *group = Some(
+                    KeyFrameGroup::read_maybe_constant(file, constant).map_err(|e| {
+                        TransformAnimationError::KeyFrameError(e, "transform curve")
+                    })?,
+                );
+            };
+        }
+        file.seek(SeekFrom::Start(position))
+            .map_err(|e| ioe(e, "after transform curves"))?;
+
+        let [scale_x, scale_y, scale_z, rotation_x, rotation_y, rotation_z, translation_x, translation_y, translation_z] =
+            groups;
+        Ok(Self {
+            scale: [scale_x, scale_y, scale_z],
+            rotation: [rotation_x, rotation_y, rotation_z],
+            translation: [translation_x, translation_y, translation_z],
+        })
+    }
+}
+
+/// A value for each frame, from `start_frame` to `end_frame`
+#[derive(Debug, Clone, PartialEq)]
+pub struct BakedTrack<T> {
+    pub start_frame: f32,
+    pub end_frame: f32,
+    pub values: Vec<T>,
+}
+
+impl<T> BakedTrack<T> {
+    fn read<F: Read + Seek, R: Fn(&mut F) -> Result<T, io::Error>>(
+        file: &mut F,
+        constant: bool,
+        read_value: R,
+    ) -> Result<Self, TransformAnimationError> {
+        if constant {
+            return Ok(Self {
+                start_frame: 0.0,
+                end_frame: 0.0,
+                values: vec![read_value(file).map_err(|e| ioe(e, "constant value"))?],
+            });
+        };
+        let start_frame = read_f32_le(file).map_err(|e| ioe(e, "start frame"))?;
+        let end_frame = read_f32_le(file).map_err(|e| ioe(e, "end frame"))?;
+        read_u32_le(file).map_err(|e| ioe(e, "baked track flags"))?;
+        let values_offset = read_u32_le(file).map_err(|e| ioe(e, "values offset"))?;
+        let value_count = read_u32_le(file).map_err(|e| ioe(e, "value count"))?;
+        file.seek(SeekFrom::Start(values_offset as u64))
+            .map_err(|e| ioe(e, "values"))?;
+        let mut values = Vec::with_capacity(value_count as usize);
+        for _ in 0..value_count {
+            values.push(read_value(file).map_err(|e| ioe(e, "value"))?);
+        }
+        Ok(Self {
+            start_frame,
+            end_frame,
+            values,
+        })
+    }
+}
+
+/// Scale, rotation and translation stored for every frame, with the rotation as a quaternion
+#[derive(Debug, Clone, PartialEq)]
+pub struct QuaternionTransformAnimation {
+    pub scale: Option<BakedTrack<Vector3>>,
+    pub rotation: Option<BakedTrack<Quaternion>>,
+    pub translation: Option<BakedTrack<Vector3>>,
+}
+
+impl QuaternionTransformAnimation {
+    pub fn read<F: Read + Seek>(file: &mut F) -> Result<Self, TransformAnimationError> {
+        let flags = read_u32_le(file).map_err(|e| ioe(e, "quaternion transform flags"))?;
+        let scale_offset = read_u32_le(file).map_err(|e| ioe(e, "scale offset"))?;
+        let rotation_offset = read_u32_le(file).map_err(|e| ioe(e, "rotation offset"))?;
+        let translation_offset = read_u32_le(file).map_err(|e| ioe(e, "translation offset"))?;
+
+        let mut scale = None;
+        if flags & 0x8 == 0 {
+            file.seek(SeekFrom::Start(scale_offset as u64))
+                .map_err(|e| ioe(e, "scale"))?;
+            scale = Some(BakedTrack::read(file, flags & 0x1 != 0, |file| {
+                read_vector3_f32(file).map(Vector3::from)
+            })?);
+        };
+        let mut rotation = None;
+        if flags & 0x10 == 0 {
+            file.seek(SeekFrom::Start(rotation_offset as u64))
+                .map_err(|e| ioe(e, "rotation"))?;
+            rotation = Some(BakedTrack::read(file, flags & 0x2 != 0, |file| {
+                read_quaternion_f32(file).map(Quaternion::from)
+            })?);
+        };
+        let mut translation = None;
+        if flags & 0x20 == 0 {
+            file.seek(SeekFrom::Start(translation_offset as u64))
+                .map_err(|e| ioe(e, "translation"))?;
+            translation = Some(BakedTrack::read(file, flags & 0x4 != 0, |file| {
+                read_vector3_f32(file).map(Vector3::from)
+            })?);
+        };
+
+        Ok(Self {
+            scale,
+            rotation,
+            translation,
+        })
+    }
+}
+
+/// The full local transform matrix of the bone, stored for every frame
+#[derive(Debug, Clone, PartialEq)]
+pub struct MatrixTransformAnimation {
+    pub start_frame: f32,
+    pub end_frame: f32,
+    pub pre_repeat: LoopType,
+    pub post_repeat: LoopType,
+    pub matrices: Vec<Matrix3x4>,
+}
+
+impl MatrixTransformAnimation {
+    pub fn read<F: Read + Seek>(file: &mut F) -> Result<Self, TransformAnimationError> {
+        let start_frame = read_f32_le(file).map_err(|e| ioe(e, "start frame"))?;
+        let end_frame = read_f32_le(file).map_err(|e| ioe(e, "end frame"))?;
+        let pre_repeat_value = read_u8(file).map_err(|e| ioe(e, "pre repeat"))?;
+        let pre_repeat = LoopType::new(pre_repeat_value)
+            .ok_or(TransformAnimationError::InvalidLoopType(pre_repeat_value))?;
+        let post_repeat_value = read_u8(file).map_err(|e| ioe(e, "post repeat"))?;
+        let post_repeat = LoopType::new(post_repeat_value)
+            .ok_or(TransformAnimationError::InvalidLoopType(post_repeat_value))?;
+        file.seek(SeekFrom::Current(2))
+            .map_err(|e| ioe(e, "padding"))?;
+
+        let matrices_offset = read_u32_le(file).map_err(|e| ioe(e, "matrices offset"))?;
+        let matrix_count = read_u32_le(file).map_err(|e| ioe(e, "matrix count"))?;
+        file.seek(SeekFrom::Start(matrices_offset as u64))
+            .map_err(|e| ioe(e, "matrices"))?;
+        let mut matrices = Vec::with_capacity(matrix_count as usize);
+        for _ in 0..matrix_count {
+            matrices.push(Matrix3x4::from(
+                read_matrix3x4_f32(file).map_err(|e| ioe(e, "matrix"))?,
+            ));
+        }
+
+        Ok(Self {
+            start_frame,
+            end_frame,
+            pre_repeat,
+            post_repeat,
+            matrices,
+        })
+    }
+}
@@ -1,8 +1,12 @@
+use crate::animation::{Animation, AnimationError, MaterialAnimation};
 use crate::deserialize::{read_vec_pointer, ReadVecError};
-use crate::model::{Model, ModelError};
+use crate::model::{MaterialError, MaterialParameters, Model, ModelError};
+use crate::scene::{Camera, CameraError, Fog, FogError, Light, LightError, Scene, SceneError};
+use crate::shader::{Shader, ShaderError, ShaderProgram};
 use crate::{bch_to_absolute, BCHRelocateError};
 use crate::{BCHContentHeader, ReferenceDictError};
 use crate::{BCHHeader, BCHHeaderError};
+use crate::{Texture, TextureError};
 use std::io;
 use std::io::{Cursor, Read, Seek, SeekFrom};
 
@@ -16,11 +20,37 @@ pub enum BCHError {
     FetchError(io::Error, &'static str),
     BCHContentHeaderError(ReferenceDictError),
     ModelReadError(ReadVecError<ModelError>),
+    MaterialReadError(ReadVecError<MaterialError>),
+    ShaderReadError(ReadVecError<ShaderError>),
+    TextureReadError(ReadVecError<TextureError>),
+    LightReadError(ReadVecError<LightError>),
+    CameraReadError(ReadVecError<CameraError>),
+    FogReadError(ReadVecError<FogError>),
+    SkeletalAnimationReadError(ReadVecError<AnimationError>),
+    MaterialAnimationReadError(ReadVecError<AnimationError>),
+    VisibilityAnimationReadError(ReadVecError<AnimationError>),
+    LightAnimationReadError(ReadVecError<AnimationError>),
+    CameraAnimationReadError(ReadVecError<AnimationError>),
+    FogAnimationReadError(ReadVecError<AnimationError>),
+    SceneReadError(ReadVecError<SceneError>),
 }
 
 #[derive(Debug)]
 pub struct BCH {
     pub models: Vec<Model>,
+    pub materials: Vec<MaterialParameters>,
+    pub shaders: Vec<Shader>,
+    pub textures: Vec<Texture>,
+    pub lights: Vec<Light>,
+    pub cameras: Vec<Camera>,
+    pub fogs: Vec<Fog>,
+    pub skeletal_animations: Vec<Animation>,
+    pub material_animations: Vec<MaterialAnimation>,
+    pub visibility_animations: Vec<Animation>,
+    pub light_animations: Vec<Animation>,
+    pub camera_animations: Vec<Animation>,
+    pub fog_animations: Vec<Animation>,
+    pub scenes: Vec<Scene>,
 }
 
 impl BCH {
@@ -53,13 +83,223 @@ impl BCH {
 
         let models: Vec<Model> = read_vec_pointer(
             &mut file,
-            |file| Model::read(file),
+            Model::read,
             content_header.models.pointer_table_entries as u64,
         )
         .map_err(BCHError::ModelReadError)?;
 
+        // read materials
+        file.seek(SeekFrom::Start(
+            content_header.materials.pointer_table_offset as u64,
+        ))
+        .map_err(|err| BCHError::FetchError(err, "material"))?;
+
+        let materials: Vec<MaterialParameters> = read_vec_pointer(
+            &mut file,
+            MaterialParameters::read,
+            content_header.materials.pointer_table_entries as u64,
+        )
+        .map_err(BCHError::MaterialReadError)?;
+
+        // read shaders
+        file.seek(SeekFrom::Start(
+            content_header.shaders.pointer_table_offset as u64,
+        ))
+        .map_err(|err| BCHError::FetchError(err, "shader"))?;
+
+        let shaders: Vec<Shader> = read_vec_pointer(
+            &mut file,
+            Shader::read,
+            content_header.shaders.pointer_table_entries as u64,
+        )
+        .map_err(BCHError::ShaderReadError)?;
+
+        // read textures
+        file.seek(SeekFrom::Start(
+            content_header.textures.pointer_table_offset as u64,
+        ))
+        .map_err(|err| BCHError::FetchError(err, "texture"))?;
+
+        let textures: Vec<Texture> = read_vec_pointer(
+            &mut file,
+            Texture::read,
+            content_header.textures.pointer_table_entries as u64,
+        )
+        .map_err(BCHError::TextureReadError)?;
+
+        // read lights
+        file.seek(SeekFrom::Start(
+            content_header.lights.pointer_table_offset as u64,
+        ))
+        .map_err(|err| BCHError::FetchError(err, "light"))?;
+
+        let lights: Vec<Light> = read_vec_pointer(
+            &mut file,
+            Light::read,
+            content_header.lights.pointer_table_entries as u64,
+        )
+        .map_err(BCHError::LightReadError)?;
+
+        // read cameras
+        file.seek(SeekFrom::Start(
+            content_header.cameras.pointer_table_offset as u64,
+        ))
+        .map_err(|err| BCHError::FetchError(err, "camera"))?;
+
+        let cameras: Vec<Camera> = read_vec_pointer(
+            &mut file,
+            Camera::read,
+            content_header.cameras.pointer_table_entries as u64,
+        )
+        .map_err(BCHError::CameraReadError)?;
+
+        // read fogs
+        file.seek(SeekFrom::Start(
+            content_header.fogs.pointer_table_offset as u64,
+        ))
+        .map_err(|err| BCHError::FetchError(err, "fog"))?;
+
+        let fogs: Vec<Fog> = read_vec_pointer(
+            &mut file,
+            Fog::read,
+            content_header.fogs.pointer_table_entries as u64,
+        )
+        .map_err(BCHError::FogReadError)?;
+
+        // read skeletal animations
+        file.seek(SeekFrom::Start(
+            content_header.skeletal_animations.pointer_table_offset as u64,
+        ))
+        .map_err(|err| BCHError::FetchError(err, "skeletal animation"))?;
+
+        let skeletal_animations: Vec<Animation> = read_vec_pointer(
+            &mut file,
+            Animation::read,
+            content_header.skeletal_animations.pointer_table_entries as u64,
+        )
+        .map_err(BCHError::SkeletalAnimationReadError)?;
+
+        // read material animations
+        file.seek(SeekFrom::Start(
+            content_header.material_animations.pointer_table_offset as u64,
+        ))
+        .map_err(|err| BCHError::FetchError(err, "material animation"))?;
+
+        let material_animations: Vec<MaterialAnimation> = read_vec_pointer(
+            &mut file,
+            MaterialAnimation::read,
+            content_header.material_animations.pointer_table_entries as u64,
+        )
+        .map_err(BCHError::MaterialAnimationReadError)?;
+
+        // read visibility animations
+        file.seek(SeekFrom::Start(
+            content_header.visibility_animations.pointer_table_offset as u64,
+        ))
+        .map_err(|err| BCHError::FetchError(err, "visibility animation"))?;
+
+        let visibility_animations: Vec<Animation> = read_vec_pointer(
+            &mut file,
+            Animation::read,
+            content_header.visibility_animations.pointer_table_entries as u64,
+        )
+        .map_err(BCHError::VisibilityAnimationReadError)?;
+
+        // read light animations
+        file.seek(SeekFrom::Start(
+            content_header.light_animation.pointer_table_offset as u64,
+        ))
+        .map_err(|err| BCHError::FetchError(err, "light animation"))?;
+
+        let light_animations: Vec<Animation> = read_vec_pointer(
+            &mut file,
+            Animation::read,
+            content_header.light_animation.pointer_table_entries as u64,
+        )
+        .map_err(BCHError::LightAnimationReadError)?;
+
+        // read camera animations
+        file.seek(SeekFrom::Start(
+            content_header.camera_animation.pointer_table_offset as u64,
+        ))
+        .map_err(|err| BCHError::FetchError(err, "camera animation"))?;
+
+        let camera_animations: Vec<Animation> = read_vec_pointer(
+            &mut file,
+            Animation::read,
+            content_header.camera_animation.pointer_table_entries as u64,
+        )
+        .map_err(BCHError::CameraAnimationReadError)?;
+
+        // read fog animations
+        file.seek(SeekFrom::Start(
+            content_header.fog_animation.pointer_table_offset as u64,
+        ))
+        .map_err(|err| BCHError::FetchError(err, "fog animation"))?;
+
+        let fog_animations: Vec<Animation> = read_vec_pointer(
+            &mut file,
+            Animation::read,
+            content_header.fog_animation.pointer_table_entries as u64,
+        )
+        .map_err(BCHError::FogAnimationReadError)?;
+
+        // read scenes
+        file.seek(SeekFrom::Start(
+            content_header.scene.pointer_table_offset as u64,
+        ))
+        .map_err(|err| BCHError::FetchError(err, "scene"))?;
+
+        let scenes: Vec<Scene> = read_vec_pointer(
+            &mut file,
+            Scene::read,
+            content_header.scene.pointer_table_entries as u64,
+        )
+        .map_err(BCHError::SceneReadError)?;
+
         Ok(BCH {
-            models
+            models,
+            materials,
+            shaders,
+            textures,
+            lights,
+            cameras,
+            fogs,
+            skeletal_animations,
+            material_animations,
+            visibility_animations,
+            light_animations,
+            camera_animations,
+            fog_animations,
+            scenes,
         })
     }
+
+    pub fn get_shader(&self, name: &str) -> Option<&Shader> {
+        self.shaders.iter().find(|shader| shader.name == name)
+    }
+
+    /// return the shader referenced by a material, and the program it uses. The reference is the
+    /// index of the program in the shader, followed by `@` and the name of the shader.
+    pub fn get_material_shader(
+        &self,
+        material: &MaterialParameters,
+    ) -> Option<(&Shader, &ShaderProgram)> {
+        let (index, name) = material.shader_reference.as_ref()?.split_once('@')?;
+        let shader = self.get_shader(name)?;
+        let program = shader.binary.programs.get(index.parse::<usize>().ok()?)?;
+        Some((shader, program))
+    }
+
+    pub fn get_light(&self, name: &str) -> Option<&Light> {
+        self.lights.iter().find(|light| light.name == name)
+    }
+
+    pub fn get_camera(&self, name: &str) -> Option<&Camera> {
+        self.cameras.iter().find(|camera| camera.name == name)
+    }
+
+    pub fn get_fog(&self, name: &str) -> Option<&Fog> {
+        self.fogs.iter().find(|fog| fog.name == name)
+    }
 }
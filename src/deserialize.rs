@@ -328,6 +328,39 @@ pub fn read_vector2_f32<T: Read>(file: &mut T) -> Result<[f32; 2], io::Error> {
     Ok(result)
 }
 
+pub fn read_vector3_f32<T: Read>(file: &mut T) -> Result<[f32; 3], io::Error> {
+    let mut result = [0.0; 3];
+    for value in &mut result {
+        *value = read_f32_le(file)?;
+    }
+    if cfg!(feature = "compare") {
+        println!("found Vector3 <{}  {}  {}>", result[0], result[1], result[2]);
+    };
+    Ok(result)
+}
+
+pub fn read_quaternion_f32<T: Read>(file: &mut T) -> Result<[f32; 4], io::Error> {
+    let mut result = [0.0; 4];
+    for value in &mut result {
+        *value = read_f32_le(file)?;
+    }
+    if cfg!(feature = "compare") {
+        println!(
+            "found Quaternion <{}  {}  {}  {}>",
+            result[0], result[1], result[2], result[3]
+        );
+    };
+    Ok(result)
+}
+
+pub fn read_matrix3_f32<T: Read>(file: &mut T) -> Result<[[f32; 3]; 3], io::Error> {
+    let mut result = [[0.0; 3]; 3];
+    for row in &mut result {
+        *row = read_vector3_f32(file)?;
+    }
+    Ok(result)
+}
+
 pub fn read_matrix4x3_f32_le<T: Read>(file: &mut T) -> Result<[[f32; 3]; 4], io::Error> {
     let mut result = [[0.0; 3]; 4];
     for column in &mut result {
@@ -348,3 +381,15 @@ where
     }
     Ok(())
 }
+
+pub fn read_rgba<T: Read>(file: &mut T) -> Result<[u8; 4], io::Error> {
+    let mut result = [0; 4];
+    file.read_exact(&mut result)?;
+    if cfg!(feature = "compare") {
+        println!(
+            "found RGBA {{R:{} G:{} B:{} A:{}}}",
+            result[0], result[1], result[2], result[3]
+        );
+    };
+    Ok(result)
+}
@@ -0,0 +1,744 @@
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::io;
+use std::io::Write;
+
+use crate::animation::Animation;
+use crate::model::{
+    Bone, Indices, Material, Object, TextureMagFilter, TextureMinFilter, TextureWrap,
+};
+use crate::{Matrix4, Quaternion, Texture, TexturePngError, Vector3, BCH};
+
+const ARRAY_BUFFER: u32 = 34962;
+const ELEMENT_ARRAY_BUFFER: u32 = 34963;
+
+const UNSIGNED_SHORT: u32 = 5123;
+const UNSIGNED_INT: u32 = 5125;
+const FLOAT: u32 = 5126;
+
+/// the number of animation frames per second
+const ANIMATION_FRAME_RATE: f32 = 30.0;
+
+#[derive(Debug)]
+pub enum GltfExportError {
+    TexturePngError(TexturePngError, String),
+    JsonError(serde_json::Error),
+    IOError(io::Error),
+}
+
+/// A glTF 2.0 document, with all its binary data in a single buffer
+#[derive(Debug)]
+pub struct GltfDocument {
+    pub json: Value,
+    pub buffer: Vec<u8>,
+}
+
+impl GltfDocument {
+    /// write the document as a .gltf file, with the buffer embedded as a data uri
+    pub fn write_gltf<W: Write>(&self, output: &mut W) -> Result<(), GltfExportError> {
+        let mut json = self.json.clone();
+        json["buffers"][0]["uri"] = Value::String(format!(
+            "data:application/octet-stream;base64,{}",
+            encode_base64(&self.buffer)
+        ));
+        serde_json::to_writer_pretty(output, &json).map_err(GltfExportError::JsonError)
+    }
+
+    /// write the document as a binary .glb file
+    pub fn write_glb<W: Write>(&self, output: &mut W) -> Result<(), GltfExportError> {
+        let mut json = serde_json::to_vec(&self.json).map_err(GltfExportError::JsonError)?;
+        while !json.len().is_multiple_of(4) {
+            json.push(b' ');
+        }
+        let mut buffer = self.buffer.clone();
+        while !buffer.len().is_multiple_of(4) {
+            buffer.push(0);
+        }
+
+        let total_length = 12 + 8 + json.len() + 8 + buffer.len();
+        let mut glb = Vec::with_capacity(total_length);
+        glb.extend_from_slice(b"glTF");
+        glb.extend_from_slice(&2u32.to_le_bytes());
+        glb.extend_from_slice(&(total_length as u32).to_le_bytes());
+        glb.extend_from_slice(&(json.len() as u32).to_le_bytes());
+        glb.extend_from_slice(b"JSON");
+        glb.extend_from_slice(&json);
+        glb.extend_from_slice(&(buffer.len() as u32).to_le_bytes());
+        glb.extend_from_slice(b"BIN\0");
+        glb.extend_from_slice(&buffer);
+
+        output.write_all(&glb).map_err(GltfExportError::IOError)
+    }
+}
+
+fn encode_base64(data: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] =
+        b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut result = String::with_capacity(data.len().div_ceil(3) * 4);
+    for chunk in data.chunks(3) {
+        let value = (chunk[0] as u32) << 16
+            | (*chunk.get(1).unwrap_or(&0) as u32) << 8
+            | *chunk.get(2).unwrap_or(&0) as u32;
+        for position in 0..4 {
+            if position <= chunk.len() {
+                result.push(ALPHABET[(value >> (18 - position * 6) & 0x3f) as usize] as char);
+            } else {
+                result.push('=');
+            }
+        }
+    }
+    result
+}
+
+fn wrap_to_gltf(wrap: TextureWrap) -> u32 {
+    match wrap {
+        TextureWrap::ClampToEdge | TextureWrap::ClampToBorder => 33071,
+        TextureWrap::Repeat => 10497,
+        TextureWrap::MirroredRepeat => 33648,
+    }
+}
+
+fn mag_filter_to_gltf(filter: TextureMagFilter) -> u32 {
+    match filter {
+        TextureMagFilter::Nearest => 9728,
+        TextureMagFilter::Linear => 9729,
+    }
+}
+
+fn min_filter_to_gltf(filter: TextureMinFilter) -> u32 {
+    match filter {
+        TextureMinFilter::Nearest => 9728,
+        TextureMinFilter::Linear => 9729,
+        TextureMinFilter::NearestMipmapNearest => 9984,
+        TextureMinFilter::LinearMipmapNearest => 9985,
+        TextureMinFilter::NearestMipmapLinear => 9986,
+        TextureMinFilter::LinearMipmapLinear => 9987,
+    }
+}
+
+fn color_to_factor(color: [u8; 4]) -> [f32; 4] {
+    [
+        color[0] as f32 / 255.0,
+        color[1] as f32 / 255.0,
+        color[2] as f32 / 255.0,
+        color[3] as f32 / 255.0,
+    ]
+}
+
+/// return the vector scaled to a length of 1, or `fallback` if it has no length, as glTF
+/// requires unit normals and tangents
+fn normalize_or(vector: Vector3, fallback: Vector3) -> Vector3 {
+    if vector.length() == 0.0 {
+        fallback
+    } else {
+        vector.normalize()
+    }
+}
+
+/// Accumulate the json arrays and the binary buffer of a glTF document
+#[derive(Default)]
+struct GltfBuilder {
+    buffer: Vec<u8>,
+    buffer_views: Vec<Value>,
+    accessors: Vec<Value>,
+    images: Vec<Value>,
+    samplers: Vec<Value>,
+    textures: Vec<Value>,
+    materials: Vec<Value>,
+    meshes: Vec<Value>,
+    nodes: Vec<Value>,
+    skins: Vec<Value>,
+    animations: Vec<Value>,
+    /// the glTF image of each bch texture, by name
+    image_ids: HashMap<String, usize>,
+}
+
+impl GltfBuilder {
+    /// add data at the end of the buffer, aligned to 4 bytes, and return its buffer view
+    fn add_buffer_view(&mut self, data: &[u8], target: Option<u32>) -> usize {
+        while !self.buffer.len().is_multiple_of(4) {
+            self.buffer.push(0);
+        }
+        let mut view = json!({
+            "buffer": 0,
+            "byteOffset": self.buffer.len(),
+            "byteLength": data.len(),
+        });
+        if let Some(target) = target {
+            view["target"] = json!(target);
+        };
+        self.buffer.extend_from_slice(data);
+        self.buffer_views.push(view);
+        self.buffer_views.len() - 1
+    }
+
+    fn add_accessor(&mut self, accessor: Value) -> usize {
+        self.accessors.push(accessor);
+        self.accessors.len() - 1
+    }
+
+    /// add an accessor to float vectors of `N` component
+    fn add_float_accessor<const N: usize>(
+        &mut self,
+        values: &[[f32; N]],
+        with_bounds: bool,
+        target: Option<u32>,
+    ) -> usize {
+        let data: Vec<u8> = values
+            .iter()
+            .flat_map(|value| value.iter().flat_map(|component| component.to_le_bytes()))
+            .collect();
+        let view = self.add_buffer_view(&data, target);
+        let mut accessor = json!({
+            "bufferView": view,
+            "componentType": FLOAT,
+            "count": values.len(),
+            "type": match N {
+                1 => "SCALAR",
+                2 => "VEC2",
+                3 => "VEC3",
+                4 => "VEC4",
+                _ => "MAT4",
+            },
+        });
+        if with_bounds {
+            let mut min = [f32::MAX; N];
+            let mut max = [f32::MIN; N];
+            for value in values {
+                for component in 0..N {
+                    min[component] = min[component].min(value[component]);
+                    max[component] = max[component].max(value[component]);
+                }
+            }
+            accessor["min"] = json!(min.to_vec());
+            accessor["max"] = json!(max.to_vec());
+        };
+        self.add_accessor(accessor)
+    }
+
+    fn add_index_accessor(&mut self, object: &Object) -> usize {
+        let (data, component_type): (Vec<u8>, u32) = match &object.indices {
+            Indices::U16(indices) => (
+                indices
+                    .iter()
+                    .flat_map(|index| index.to_le_bytes())
+                    .collect(),
+                UNSIGNED_SHORT,
+            ),
+            Indices::U32(indices) => (
+                indices
+                    .iter()
+                    .flat_map(|index| index.to_le_bytes())
+                    .collect(),
+                UNSIGNED_INT,
+            ),
+        };
+        let view = self.add_buffer_view(&data, Some(ELEMENT_ARRAY_BUFFER));
+        self.add_accessor(json!({
+            "bufferView": view,
+            "componentType": component_type,
+            "count": object.indices.len(),
+            "type": "SCALAR",
+        }))
+    }
+
+    fn add_joint_accessor(&mut self, joints: &[[u16; 4]]) -> usize {
+        let data: Vec<u8> = joints
+            .iter()
+            .flat_map(|joint| joint.iter().flat_map(|component| component.to_le_bytes()))
+            .collect();
+        let view = self.add_buffer_view(&data, Some(ARRAY_BUFFER));
+        self.add_accessor(json!({
+            "bufferView": view,
+            "componentType": UNSIGNED_SHORT,
+            "count": joints.len(),
+            "type": "VEC4",
+        }))
+    }
+
+    fn add_texture_image(&mut self, texture: &Texture) -> Result<(), GltfExportError> {
+        let png = texture
+            .encode_png()
+            .map_err(|e| GltfExportError::TexturePngError(e, texture.name.clone()))?;
+        let view = self.add_buffer_view(&png, None);
+        self.images.push(json!({
+            "name": texture.name,
+            "bufferView": view,
+            "mimeType": "image/png",
+        }));
+        self.image_ids
+            .insert(texture.name.clone(), self.images.len() - 1);
+        Ok(())
+    }
+
+    /// return the glTF texture info for a texture slot of a material, if it has a known texture
+    fn add_texture_reference(
+        &mut self,
+        material: &Material,
+        texture_name: &Option<String>,
+        slot: usize,
+    ) -> Option<Value> {
+        let image = *self.image_ids.get(texture_name.as_ref()?)?;
+        let mut sampler = json!({});
+        if let Some(mapper) = material.texture_mappers.get(slot) {
+            sampler = json!({
+                "magFilter": mag_filter_to_gltf(mapper.mag_filter),
+                "minFilter": min_filter_to_gltf(mapper.min_filter),
+                "wrapS": wrap_to_gltf(mapper.wrap_u),
+                "wrapT": wrap_to_gltf(mapper.wrap_v),
+            });
+        };
+        self.samplers.push(sampler);
+        self.textures.push(json!({
+            "source": image,
+            "sampler": self.samplers.len() - 1,
+        }));
+        Some(json!({
+            "index": self.textures.len() - 1,
+            "texCoord": slot,
+        }))
+    }
+
+    fn add_material(&mut self, material: &Material) -> usize {
+        let parameters = &material.parameters;
+        let mut pbr = json!({
+            "baseColorFactor": color_to_factor(parameters.colors.diffuse),
+            "metallicFactor": 0.0,
+            "roughnessFactor": 1.0,
+        });
+        if let Some(texture) = self.add_texture_reference(material, &material.texture0_name, 0) {
+            pbr["baseColorTexture"] = texture;
+        };
+        let mut result = json!({
+            "name": material.name,
+            "pbrMetallicRoughness": pbr,
+            "emissiveFactor": &color_to_factor(parameters.colors.emission)[0..3],
+        });
+        if parameters.blend_function.blend_enabled {
+            result["alphaMode"] = json!("BLEND");
+        } else if parameters.alpha_test.enabled {
+            result["alphaMode"] = json!("MASK");
+            result["alphaCutoff"] = json!(parameters.alpha_test.reference as f32 / 255.0);
+        };
+        self.materials.push(result);
+        self.materials.len() - 1
+    }
+
+    /// add the mesh of an object, and return its id. The joints and weights are only written for
+    /// a `skinned` mesh, as the node of a mesh with joints must have a skin.
+    fn add_mesh(&mut self, object: &Object, material: Option<usize>, skinned: bool) -> usize {
+        let positions: Vec<[f32; 3]> = object.vertices.iter().map(|v| v.position).collect();
+        let mut attributes = json!({
+            "POSITION": self.add_float_accessor(&positions, true, Some(ARRAY_BUFFER)),
+        });
+        if object.has_normal {
+            let normals: Vec<[f32; 3]> = object
+                .vertices
+                .iter()
+                .map(|v| normalize_or(Vector3::from(v.normal), Vector3::new(0.0, 0.0, 1.0)).into())
+                .collect();
+            attributes["NORMAL"] =
+                json!(self.add_float_accessor(&normals, false, Some(ARRAY_BUFFER)));
+        };
+        if object.has_tangent {
+            let tangents: Vec<[f32; 4]> = object
+                .vertices
+                .iter()
+                .map(|v| {
+                    let tangent =
+                        normalize_or(Vector3::from(v.tangent), Vector3::new(1.0, 0.0, 0.0));
+                    [tangent.x, tangent.y, tangent.z, 1.0]
+                })
+                .collect();
+            attributes["TANGENT"] =
+                json!(self.add_float_accessor(&tangents, false, Some(ARRAY_BUFFER)));
+        };
+        for uv_set in 0..object.tex_uv_count.clamp(0, 3) as usize {
+            // glTF put the origin of texture coordinates at the top of the image
+            let uvs: Vec<[f32; 2]> = object
+                .vertices
+                .iter()
+                .map(|v| {
+                    let uv = [v.texture0, v.texture1, v.texture2][uv_set];
+                    [uv[0], 1.0 - uv[1]]
+                })
+                .collect();
+            attributes[format!("TEXCOORD_{}", uv_set)] =
+                json!(self.add_float_accessor(&uvs, false, Some(ARRAY_BUFFER)));
+        }
+        if object.has_color {
+            let colors: Vec<[f32; 4]> = object
+                .vertices
+                .iter()
+                .map(|v| {
+                    let color = v.diffuse_color;
+                    color_to_factor([
+                        (color >> 16) as u8,
+                        (color >> 8) as u8,
+                        color as u8,
+                        (color >> 24) as u8,
+                    ])
+                })
+                .collect();
+            attributes["COLOR_0"] =
+                json!(self.add_float_accessor(&colors, false, Some(ARRAY_BUFFER)));
+        };
+        if skinned {
+            let (joints, weights) = get_joints_and_weights(object);
+            attributes["JOINTS_0"] = json!(self.add_joint_accessor(&joints));
+            attributes["WEIGHTS_0"] =
+                json!(self.add_float_accessor(&weights, false, Some(ARRAY_BUFFER)));
+        };
+
+        let mut primitive = json!({
+            "attributes": attributes,
+            "indices": self.add_index_accessor(object),
+            "mode": 4,
+        });
+        if let Some(material) = material {
+            primitive["material"] = json!(material);
+        };
+        self.meshes.push(json!({
+            "name": object.name,
+            "primitives": [primitive],
+        }));
+        self.meshes.len() - 1
+    }
+
+    fn add_node(&mut self, node: Value) -> usize {
+        self.nodes.push(node);
+        self.nodes.len() - 1
+    }
+
+    /// add a node per bone, and return the node id of each bone and of the root bones
+    fn add_skeleton(&mut self, skeleton: &[Bone]) -> (Vec<usize>, Vec<usize>) {
+        let first_node = self.nodes.len();
+        let mut roots = Vec::new();
+        let mut children = vec![Vec::new(); skeleton.len()];
+        for (bone_id, bone) in skeleton.iter().enumerate() {
+            match bone.parent_id {
+                parent_id if parent_id >= 0 && (parent_id as usize) < skeleton.len() => {
+                    children[parent_id as usize].push(first_node + bone_id)
+                }
+                _ => roots.push(first_node + bone_id),
+            };
+        }
+        for (bone, children) in skeleton.iter().zip(children) {
+            let rotation = Quaternion::from_euler(bone.rotation);
+            let mut node = json!({
+                "name": bone.name,
+                "translation": <[f32; 3]>::from(bone.translation),
+                "rotation": [rotation.x, rotation.y, rotation.z, rotation.w],
+                "scale": <[f32; 3]>::from(bone.scale),
+            });
+            if !children.is_empty() {
+                node["children"] = json!(children);
+            };
+            self.add_node(node);
+        }
+        ((first_node..self.nodes.len()).collect(), roots)
+    }
+
+    fn add_skin(&mut self, skeleton: &[Bone], joints: &[usize], roots: &[usize]) -> usize {
+        let inverse_bind_matrices: Vec<[f32; 16]> = skeleton
+            .iter()
+            .map(|bone| Matrix4::from(bone.inverse_transform).to_column_major())
+            .collect();
+        let accessor = self.add_float_accessor(&inverse_bind_matrices, false, None);
+        let mut skin = json!({
+            "joints": joints,
+            "inverseBindMatrices": accessor,
+        });
+        if let Some(root) = roots.first() {
+            skin["skeleton"] = json!(root);
+        };
+        self.skins.push(skin);
+        self.skins.len() - 1
+    }
+
+    /// sample every animated bone of a skeleton at each frame of an animation, and add a channel
+    /// per animated property of its joint node
+    fn add_skeleton_animation(
+        &mut self,
+        animation: &Animation,
+        skeleton: &[Bone],
+        joints: &[usize],
+        samplers: &mut Vec<Value>,
+        channels: &mut Vec<Value>,
+    ) {
+        let animated_bones: Vec<(&Bone, usize)> = skeleton
+            .iter()
+            .zip(joints.iter().copied())
+            .filter(|(bone, _)| animation.get_element(&bone.name).is_some())
+            .collect();
+        if animated_bones.is_empty() {
+            return;
+        };
+
+        let frame_count = animation.frame_count.max(0.0).ceil() as usize;
+        let times: Vec<[f32; 1]> = (0..=frame_count)
+            .map(|frame| [frame as f32 / ANIMATION_FRAME_RATE])
+            .collect();
+        let input = self.add_float_accessor(&times, true, None);
+
+        for (bone, joint) in animated_bones {
+            let mut scales = Vec::with_capacity(times.len());
+            let mut rotations = Vec::with_capacity(times.len());
+            let mut translations = Vec::with_capacity(times.len());
+            for frame in 0..=frame_count {
+                let transform = animation
+                    .sample_bone(bone, frame as f32)
+                    .unwrap_or_else(|| bone.get_local_transform());
+                let (scale, rotation, translation) = transform.decompose();
+                scales.push(<[f32; 3]>::from(scale));
+                rotations.push([rotation.x, rotation.y, rotation.z, rotation.w]);
+                translations.push(<[f32; 3]>::from(translation));
+            }
+            for (path, output) in [
+                ("scale", self.add_float_accessor(&scales, false, None)),
+                ("rotation", self.add_float_accessor(&rotations, false, None)),
+                (
+                    "translation",
+                    self.add_float_accessor(&translations, false, None),
+                ),
+            ] {
+                samplers.push(json!({
+                    "input": input,
+                    "output": output,
+                    "interpolation": "LINEAR",
+                }));
+                channels.push(json!({
+                    "sampler": samplers.len() - 1,
+                    "target": { "node": joint, "path": path },
+                }));
+            }
+        }
+    }
+
+    fn build(self, scene_nodes: Vec<usize>) -> GltfDocument {
+        let mut json = json!({
+            "asset": {
+                "version": "2.0",
+                "generator": "bch3ds",
+            },
+            "scene": 0,
+            "scenes": [{ "nodes": scene_nodes }],
+            "buffers": [{ "byteLength": self.buffer.len() }],
+        });
+        for (key, values) in [
+            ("bufferViews", self.buffer_views),
+            ("accessors", self.accessors),
+            ("images", self.images),
+            ("samplers", self.samplers),
+            ("textures", self.textures),
+            ("materials", self.materials),
+            ("meshes", self.meshes),
+            ("nodes", self.nodes),
+            ("skins", self.skins),
+            ("animations", self.animations),
+        ] {
+            if !values.is_empty() {
+                json[key] = Value::Array(values);
+            };
+        }
+        GltfDocument {
+            json,
+            buffer: self.buffer,
+        }
+    }
+}
+
+/// return the four most important bones of each vertex, with their weights normalized
+fn get_joints_and_weights(object: &Object) -> (Vec<[u16; 4]>, Vec<[f32; 4]>) {
+    let mut joints = Vec::with_capacity(object.vertices.len());
+    let mut weights = Vec::with_capacity(object.vertices.len());
+    for vertex in &object.vertices {
+        let mut influences: Vec<(u16, f32)> = vertex
+            .node
+            .iter()
+            .enumerate()
+            .map(|(position, node)| (*node as u16, *vertex.weight.get(position).unwrap_or(&0.0)))
+            .collect();
+        influences.sort_by(|first, second| second.1.total_cmp(&first.1));
+        influences.truncate(4);
+
+        let mut vertex_joints = [0; 4];
+        let mut vertex_weights = [0.0; 4];
+        let total: f32 = influences.iter().map(|influence| influence.1).sum();
+        if total > 0.0 {
+            for (position, (joint, weight)) in influences.into_iter().enumerate() {
+                vertex_joints[position] = joint;
+                vertex_weights[position] = weight / total;
+            }
+        } else {
+            // a vertex without weight follow its first bone, or the root of the skeleton
+            vertex_joints[0] = influences.first().map(|influence| influence.0).unwrap_or(0);
+            vertex_weights[0] = 1.0;
+        };
+        joints.push(vertex_joints);
+        weights.push(vertex_weights);
+    }
+    (joints, weights)
+}
+
+/// convert the models of a BCH file to a glTF document, with a root node per model, and the
+/// skeleton of each model as a skin shared by its skinned meshes. Skeletal animations are baked
+/// at every frame on the bones they animate.
+pub fn bch_to_gltf(bch: &BCH) -> Result<GltfDocument, GltfExportError> {
+    let mut builder = GltfBuilder::default();
+
+    for texture in &bch.textures {
+        builder.add_texture_image(texture)?;
+    }
+
+    let mut scene_nodes = Vec::new();
+    let mut skeleton_joints = Vec::new();
+    for model in &bch.models {
+        let materials: Vec<usize> = model
+            .material
+            .iter()
+            .map(|material| builder.add_material(material))
+            .collect();
+
+        let mut children = Vec::new();
+        let mut skin = None;
+        if !model.skeleton.is_empty() {
+            let (joints, roots) = builder.add_skeleton(&model.skeleton);
+            skin = Some(builder.add_skin(&model.skeleton, &joints, &roots));
+            children.extend(roots);
+            skeleton_joints.push((&model.skeleton, joints));
+        };
+
+        for object in &model.mesh {
+            // hidden objects are usually alternative versions of visible ones
+            if object.indices.is_empty() || !object.is_visible {
+                continue;
+            };
+            let material = materials.get(object.material_id as usize).copied();
+            let skin = skin.filter(|_| object.has_node);
+            let mesh = builder.add_mesh(object, material, skin.is_some());
+            let mut node = json!({
+                "name": object.name,
+                "mesh": mesh,
+            });
+            if let Some(skin) = skin {
+                node["skin"] = json!(skin);
+            };
+            children.push(builder.add_node(node));
+        }
+
+        let world_transform = Matrix4::from(model.world_transform);
+        scene_nodes.push(builder.add_node(json!({
+            "name": model.name,
+            "matrix": world_transform.to_column_major().to_vec(),
+            "children": children,
+        })));
+    }
+
+    for animation in &bch.skeletal_animations {
+        let mut samplers = Vec::new();
+        let mut channels = Vec::new();
+        for (skeleton, joints) in &skeleton_joints {
+            builder.add_skeleton_animation(
+                animation,
+                skeleton,
+                joints,
+                &mut samplers,
+                &mut channels,
+            );
+        }
+        if !channels.is_empty() {
+            builder.animations.push(json!({
+                "name": animation.name,
+                "samplers": samplers,
+                "channels": channels,
+            }));
+        };
+    }
+
+    Ok(builder.build(scene_nodes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::convert::TryInto;
+
+    fn read_u32(data: &[u8], offset: usize) -> u32 {
+        u32::from_le_bytes(data[offset..offset + 4].try_into().unwrap())
+    }
+
+    fn read_f32(data: &[u8], offset: usize) -> f32 {
+        f32::from_le_bytes(data[offset..offset + 4].try_into().unwrap())
+    }
+
+    #[test]
+    fn base64() {
+        assert_eq!(encode_base64(b"bch3ds"), "YmNoM2Rz");
+        assert_eq!(encode_base64(b"bch"), "YmNo");
+        assert_eq!(encode_base64(b"bc"), "YmM=");
+        assert_eq!(encode_base64(b"b"), "Yg==");
+    }
+
+    #[test]
+    fn degenerate_normal() {
+        let fallback = Vector3::new(0.0, 0.0, 1.0);
+        assert_eq!(normalize_or(Vector3::ZERO, fallback), fallback);
+        assert_eq!(
+            normalize_or(Vector3::new(0.0, 3.0, 0.0), fallback),
+            Vector3::new(0.0, 1.0, 0.0)
+        );
+    }
+
+    #[test]
+    fn glb_layout() {
+        let positions = [[0.0, 0.0, 0.0], [1.0, 2.0, 3.0], [-1.0, 0.5, 4.0]];
+        let indices: Vec<u8> = [0u16, 1, 2].iter().flat_map(|i| i.to_le_bytes()).collect();
+        let mut builder = GltfBuilder::default();
+        let position_accessor = builder.add_float_accessor(&positions, true, Some(ARRAY_BUFFER));
+        let index_view = builder.add_buffer_view(&indices, Some(ELEMENT_ARRAY_BUFFER));
+        let uv_accessor = builder.add_float_accessor(&[[0.25, 0.75]], false, None);
+        let document = builder.build(Vec::new());
+
+        // the views are aligned to 4 bytes
+        let views = &document.json["bufferViews"];
+        assert_eq!(views[0]["byteOffset"], 0);
+        assert_eq!(views[0]["byteLength"], 36);
+        assert_eq!(views[0]["target"], ARRAY_BUFFER);
+        assert_eq!(views[index_view]["byteOffset"], 36);
+        assert_eq!(views[index_view]["byteLength"], 6);
+        assert_eq!(views[2]["byteOffset"], 44);
+        assert!(views[2].get("target").is_none());
+        assert_eq!(document.json["buffers"][0]["byteLength"], 52);
+
+        let accessors = &document.json["accessors"];
+        assert_eq!(accessors[position_accessor]["type"], "VEC3");
+        assert_eq!(accessors[position_accessor]["count"], 3);
+        assert_eq!(accessors[position_accessor]["min"], json!([-1.0, 0.0, 0.0]));
+        assert_eq!(accessors[position_accessor]["max"], json!([1.0, 2.0, 4.0]));
+        assert_eq!(accessors[uv_accessor]["type"], "VEC2");
+        assert!(accessors[uv_accessor].get("min").is_none());
+
+        let mut glb = Vec::new();
+        document.write_glb(&mut glb).unwrap();
+        assert_eq!(&glb[0..4], b"glTF");
+        assert_eq!(read_u32(&glb, 4), 2);
+        assert_eq!(read_u32(&glb, 8) as usize, glb.len());
+
+        let json_length = read_u32(&glb, 12) as usize;
+        assert_eq!(json_length % 4, 0);
+        assert_eq!(&glb[16..20], b"JSON");
+        let json: Value = serde_json::from_slice(&glb[20..20 + json_length]).unwrap();
+        assert_eq!(json, document.json);
+
+        let bin = 20 + json_length;
+        assert_eq!(read_u32(&glb, bin), 52);
+        assert_eq!(&glb[bin + 4..bin + 8], b"BIN\0");
+        let data = &glb[bin + 8..];
+        assert_eq!(data, &document.buffer[..]);
+        assert_eq!(read_f32(data, 12), 1.0);
+        assert_eq!(read_f32(data, 32), 4.0);
+        assert_eq!(&data[36..42], &indices[..]);
+        assert_eq!(read_f32(data, 48), 0.75);
+    }
+}
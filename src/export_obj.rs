@@ -1,96 +1,235 @@
 extern crate obj_exporter as obj;
 
-use obj::{Geometry, ObjSet, Object, Primitive, Shape, TVertex, Vertex};
+use obj::{Geometry, GroupName, ObjSet, Object, Primitive, Shape, TVertex, Vertex};
+use std::collections::HashSet;
+use std::fs::File;
+use std::io;
+use std::io::Write;
+use std::path::Path;
 
-use crate::BCH;
+use crate::model::{Material, Model};
+use crate::{TexturePngError, BCH};
 
-pub fn bch_to_obj(bch: &BCH) -> ObjSet { //TODO: error handling
+#[derive(Debug)]
+pub enum ObjExportError {
+    TexturePngError(TexturePngError, String),
+    IOError(io::Error, String),
+}
+
+/// return the name of the PNG file a texture is exported to
+pub fn get_texture_file_name(texture_name: &str) -> String {
+    format!("{}.png", texture_name)
+}
+
+/// return the name of a material in the MTL library. Materials of different models can share a
+/// name, so it is prefixed with the name of the model.
+pub fn get_material_name(model: &Model, material: &Material) -> String {
+    format!("{}_{}", model.name, material.name)
+}
+
+/// convert the models of a BCH file to an OBJ set, with an object per model and a geometry per
+/// material. Each mesh is put in its own group, and hidden meshes are skipped.
+pub fn bch_to_obj(bch: &BCH, material_library: Option<String>) -> ObjSet {
     let mut objects: Vec<Object> = Vec::new();
     for model in &bch.models {
-        for object in &model.mesh {
-            println!("len vertices: {}", object.vertices.len());
-            let name = object.name.clone();
-            println!("added {}", name);
-            let mut vertices = Vec::new();
-            for vertice in &object.vertices {
-                vertices.push(Vertex { x: vertice.position[0] as f64, y: vertice.position[1] as f64, z: vertice.position[2] as f64})
+        let mut vertices = Vec::new();
+        let mut tex_vertices = Vec::new();
+        let mut normals = Vec::new();
+        let mut geometry: Vec<Geometry> = Vec::new();
+
+        for object in model.mesh.iter().filter(|object| object.is_visible) {
+            let vertex_base = vertices.len();
+            let tex_vertex_base = tex_vertices.len();
+            let normal_base = normals.len();
+
+            for vertex in &object.vertices {
+                vertices.push(Vertex {
+                    x: vertex.position[0] as f64,
+                    y: vertex.position[1] as f64,
+                    z: vertex.position[2] as f64,
+                });
+                if object.tex_uv_count > 0 {
+                    tex_vertices.push(TVertex {
+                        u: vertex.texture0[0] as f64,
+                        v: vertex.texture0[1] as f64,
+                        w: 0.0,
+                    });
+                };
+                if object.has_normal {
+                    normals.push(Vertex {
+                        x: vertex.normal[0] as f64,
+                        y: vertex.normal[1] as f64,
+                        z: vertex.normal[2] as f64,
+                    });
+                };
+            }
+
+            let indices: Vec<usize> = object.indices.iter().map(|index| index as usize).collect();
+            let vtn = |index: usize| {
+                (
+                    vertex_base + index,
+                    if object.tex_uv_count > 0 {
+                        Some(tex_vertex_base + index)
+                    } else {
+                        None
+                    },
+                    if object.has_normal {
+                        Some(normal_base + index)
+                    } else {
+                        None
+                    },
+                )
             };
-            let tex_vertices = Vec::new();
-            let normals = Vec::new();
-            let mut shapes = Vec::new();
-            // vertices are paired by 3
-            for entry_id in 0..object.vertices.len()/3 {
-                shapes.push(Shape {
-                    primitive: Primitive::Triangle (
-                        (entry_id*3, Some(entry_id*3), Some(0)),
-                        (entry_id*3 + 1, Some(entry_id*3 + 1), Some(0)),
-                        (entry_id*3 + 2, Some(entry_id*3 + 2), Some(0))
-                    ),
-                    groups: vec![],
-                    smoothing_groups: vec![],
-                })
+            let shapes = indices.chunks_exact(3).map(|triangle| Shape {
+                primitive: Primitive::Triangle(vtn(triangle[0]), vtn(triangle[1]), vtn(triangle[2])),
+                groups: vec![GroupName::from(object.name.clone())],
+                smoothing_groups: vec![],
+            });
+
+            let material_name = model
+                .material
+                .get(object.material_id as usize)
+                .map(|material| get_material_name(model, material));
+            match geometry
+                .iter_mut()
+                .find(|geometry| geometry.material_name == material_name)
+            {
+                Some(geometry) => geometry.shapes.extend(shapes),
+                None => geometry.push(Geometry {
+                    material_name,
+                    shapes: shapes.collect(),
+                }),
             };
+        }
 
-            objects.push(Object {
-                name,
-                vertices,
-                tex_vertices,
-                normals,
-                geometry: vec![Geometry {
-                    material_name: None,
-                    shapes
-                }],
-            })
-        };
-    };
+        objects.push(Object {
+            name: model.name.clone(),
+            vertices,
+            tex_vertices,
+            normals,
+            geometry,
+        })
+    }
 
     ObjSet {
-        material_library: None,
+        material_library,
         objects,
     }
+}
+
+/// write an OBJ set, including the material library and the material of each geometry, which
+/// `obj_exporter` ignore
+pub fn write_obj<W: Write>(obj_set: &ObjSet, output: &mut W) -> io::Result<()> {
+    if let Some(material_library) = &obj_set.material_library {
+        writeln!(output, "mtllib {}", material_library)?;
+    };
+
+    let mut vertex_base = 1;
+    let mut tex_vertex_base = 1;
+    let mut normal_base = 1;
+    for object in &obj_set.objects {
+        writeln!(output, "o {}", object.name)?;
+        for vertex in &object.vertices {
+            writeln!(output, "v {:.6} {:.6} {:.6}", vertex.x, vertex.y, vertex.z)?;
+        }
+        for tex_vertex in &object.tex_vertices {
+            writeln!(output, "vt {:.6} {:.6}", tex_vertex.u, tex_vertex.v)?;
+        }
+        for normal in &object.normals {
+            writeln!(output, "vn {:.6} {:.6} {:.6}", normal.x, normal.y, normal.z)?;
+        }
+
+        for geometry in &object.geometry {
+            if let Some(material_name) = &geometry.material_name {
+                writeln!(output, "usemtl {}", material_name)?;
+            };
+            let mut current_groups = None;
+            for shape in &geometry.shapes {
+                if current_groups != Some(&shape.groups) {
+                    if !shape.groups.is_empty() {
+                        let groups: Vec<String> =
+                            shape.groups.iter().map(|group| group.to_string()).collect();
+                        writeln!(output, "g {}", groups.join(" "))?;
+                    };
+                    current_groups = Some(&shape.groups);
+                };
+                if let Primitive::Triangle(first, second, third) = shape.primitive {
+                    write!(output, "f")?;
+                    for (vertex, tex_vertex, normal) in [first, second, third] {
+                        write!(output, " {}", vertex + vertex_base)?;
+                        match (tex_vertex, normal) {
+                            (None, None) => (),
+                            (Some(tex_vertex), None) => {
+                                write!(output, "/{}", tex_vertex + tex_vertex_base)?
+                            }
+                            (None, Some(normal)) => write!(output, "//{}", normal + normal_base)?,
+                            (Some(tex_vertex), Some(normal)) => write!(
+                                output,
+                                "/{}/{}",
+                                tex_vertex + tex_vertex_base,
+                                normal + normal_base
+                            )?,
+                        };
+                    }
+                    writeln!(output)?;
+                };
+            }
+        }
+
+        vertex_base += object.vertices.len();
+        tex_vertex_base += object.tex_vertices.len();
+        normal_base += object.normals.len();
+    }
+    Ok(())
+}
+
+fn write_color<W: Write>(output: &mut W, key: &str, color: [u8; 4]) -> io::Result<()> {
+    writeln!(
+        output,
+        "{} {:.6} {:.6} {:.6}",
+        key,
+        color[0] as f32 / 255.0,
+        color[1] as f32 / 255.0,
+        color[2] as f32 / 255.0
+    )
+}
 
-    /*ObjSet {
-      material_library: None,
-      objects: vec![
-        Object {
-          name: "Square".to_owned(),
-          vertices: vec![
-            (-1.0, -1.0, 0.0),
-            (1.0, -1.0, 0.0),
-            (1.0, 1.0, 0.0),
-            (-1.0, 1.0, 0.0),
-          ].into_iter()
-            .map(|(x, y, z)| Vertex { x, y, z })
-            .collect(),
-          tex_vertices: vec![(0.0, 0.0), (1.0, 0.0), (1.0, 1.0), (0.0, 1.0)]
-            .into_iter()
-            .map(|(u, v)| TVertex { u, v, w: 0.0 })
-            .collect(),
-          normals: vec![
-            Vertex {
-              x: 0.0,
-              y: 0.0,
-              z: -1.0,
-            },
-          ],
-          geometry: vec![
-            Geometry {
-              material_name: None,
-              shapes: vec![(0, 1, 2), (0, 2, 3)]
-                .into_iter()
-                .map(|(x, y, z)| Shape {
-                  primitive: Primitive::Triangle(
-                    (x, Some(x), Some(0)),
-                    (y, Some(y), Some(0)),
-                    (z, Some(z), Some(0)),
-                  ),
-                  groups: vec![],
-                  smoothing_groups: vec![],
-                })
-                .collect(),
-            },
-          ],
-        },
-      ],
-  }*/
+/// write the material library of the models of a BCH file, with the diffuse texture of each
+/// material referencing the file named by `get_texture_file_name`
+pub fn bch_to_mtl<W: Write>(bch: &BCH, output: &mut W) -> io::Result<()> {
+    let mut written_materials = HashSet::new();
+    for model in &bch.models {
+        for material in &model.material {
+            let name = get_material_name(model, material);
+            if !written_materials.insert(name.clone()) {
+                continue;
+            };
+            let colors = &material.parameters.colors;
+            writeln!(output, "newmtl {}", name)?;
+            write_color(output, "Ka", colors.ambient)?;
+            write_color(output, "Kd", colors.diffuse)?;
+            write_color(output, "Ks", colors.specular0)?;
+            write_color(output, "Ke", colors.emission)?;
+            writeln!(output, "d {:.6}", colors.diffuse[3] as f32 / 255.0)?;
+            if let Some(texture_name) = &material.texture0_name {
+                writeln!(output, "map_Kd {}", get_texture_file_name(texture_name))?;
+            };
+            writeln!(output)?;
+        }
+    }
+    Ok(())
+}
+
+/// write every texture of a BCH file as a PNG image in `directory`
+pub fn export_textures_to_png(bch: &BCH, directory: &Path) -> Result<(), ObjExportError> {
+    for texture in &bch.textures {
+        let png = texture
+            .encode_png()
+            .map_err(|e| ObjExportError::TexturePngError(e, texture.name.clone()))?;
+        let path = directory.join(get_texture_file_name(&texture.name));
+        File::create(&path)
+            .and_then(|mut file| file.write_all(&png))
+            .map_err(|e| ObjExportError::IOError(e, path.to_string_lossy().to_string()))?;
+    }
+    Ok(())
 }
@@ -20,18 +20,63 @@ pub use bchcontentheader::{BCHContentHeader, ReferenceDict, ReferenceDictError};
 
 pub mod model;
 
+pub mod animation;
+
+pub mod scene;
+
+pub mod shader;
+
+mod metadata;
+pub use metadata::{BoundingBox, MetaData, MetaDataContent, MetaDataError, MetaDataValue};
+pub use metadata::VertexData;
+
+mod texture;
+pub use texture::{Texture, TextureError, TexturePngError};
+
 mod picacommandreader;
 pub use picacommandreader::{PICACommandReader, PICACommandReaderError};
 
 mod picacommand;
 pub use picacommand::{IndexBufferFormat, PicaCommand, VSHAttribute, AttributeFormat, AttributeFormatType, AttributeData};
+pub use picacommand::{AlphaTest, BlendEquation, BlendFactor, BlendFunction, DepthOperation};
+pub use picacommand::{StencilOperation, StencilTest, TestFunction, TextureFormat};
+pub use picacommand::LookUpTableSelect;
+
+mod picaregisters;
+pub use picaregisters::{pica_fixed_to_f32, pica_float_to_f32, rgba8_to_array};
+pub use picaregisters::{ColorOperation, CullMode, DepthMap, FragmentOperationMode, LogicOperation};
+pub use picaregisters::{ColorBufferFormat, DepthBufferFormat, Framebuffer};
+pub use picaregisters::{BumpMode, FragmentLightConfig, FresnelSelector, LightingConfig};
+pub use picaregisters::{LookUpTableInput, LookUpTableInputType, PrimitiveMode};
+pub use picaregisters::{Scissor, ScissorMode, ShaderOutputSemantic, Viewport};
+pub use picaregisters::{ShaderSetup, ShaderUnit};
+pub use picaregisters::{TextureCombiner, TextureCombinerAlphaOperand, TextureCombinerColorOperand};
+pub use picaregisters::{FogMode, TextureCombinerBufferConfig, TextureCombinerMode, TextureCombinerSource};
+pub use picaregisters::{TextureLevelOfDetail, TextureType, TextureUnit, TextureUnitConfig, TextureUnitParameters};
+
+mod picashader;
+pub use picashader::{disassemble_shader, OperandDescriptor, ShaderInstruction, ShaderOpcode};
+pub use picashader::{CompareOperation, ConditionOperation, FlowCondition};
+pub use picashader::{RelativeAddress, ShaderSource};
+
+mod picashaderinterpreter;
+pub use picashaderinterpreter::{ShaderInterpreter, ShaderInterpreterError, ShaderUniforms};
+
+mod texturecodec;
+pub use texturecodec::{decode_texture, get_texture_length, TextureDecodeError};
 
 mod skinningmode;
 pub use skinningmode::SkinningMode;
 
 mod math;
+pub use math::{Matrix3x4, Matrix4, Quaternion, Vector3};
 
 mod export_obj;
-pub use export_obj::bch_to_obj;
+pub use export_obj::{bch_to_mtl, bch_to_obj, export_textures_to_png};
+pub use export_obj::{get_material_name, get_texture_file_name};
+pub use export_obj::{write_obj, ObjExportError};
+
+mod export_gltf;
+pub use export_gltf::{bch_to_gltf, GltfDocument, GltfExportError};
 
 mod deserialize;
@@ -1,9 +1,9 @@
 use std::fs::File;
-use std::path::PathBuf;
-extern crate obj_exporter as obj;
+use std::io::BufWriter;
+use std::path::{Path, PathBuf};
 
-use bch3ds::bch_to_obj;
 use bch3ds::BCH;
+use bch3ds::{bch_to_mtl, bch_to_obj, export_textures_to_png, write_obj};
 
 fn main() {
     env_logger::init();
@@ -11,5 +11,16 @@ fn main() {
     let mut file = File::open(file_path).unwrap();
 
     let bch = BCH::read(&mut file).unwrap();
-    obj::export_to_file(&bch_to_obj(&bch), "output_single.obj").unwrap();
+    let obj_set = bch_to_obj(&bch, Some("output_single.mtl".to_string()));
+    write_obj(
+        &obj_set,
+        &mut BufWriter::new(File::create("output_single.obj").unwrap()),
+    )
+    .unwrap();
+    bch_to_mtl(
+        &bch,
+        &mut BufWriter::new(File::create("output_single.mtl").unwrap()),
+    )
+    .unwrap();
+    export_textures_to_png(&bch, Path::new(".")).unwrap();
 }
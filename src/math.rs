@@ -1,3 +1,729 @@
-pub fn vector3_tranform(input: [f32; 3], matrix: [[f32; 3]; 4]) -> [f32; 3] {
-    todo!("vector3 transform")
+//! Vectors, matrices and quaternions used by the model and animation code.
+//!
+//! Matrices use the column vector convention: a point is transformed with `matrix * point`, and
+//! `parent * child` apply `child` first. Euler angles are in radian, and are applied along the X,
+//! then Y, then Z axis, as done by the 3DS.
+use std::ops::{Add, Mul, Neg, Sub};
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Vector3 {
+    pub x: f32,
+    pub y: f32,
+    pub z: f32,
+}
+
+impl Vector3 {
+    pub const ZERO: Vector3 = Vector3::new(0.0, 0.0, 0.0);
+    pub const ONE: Vector3 = Vector3::new(1.0, 1.0, 1.0);
+
+    pub const fn new(x: f32, y: f32, z: f32) -> Self {
+        Self { x, y, z }
+    }
+
+    pub fn dot(self, other: Self) -> f32 {
+        self.x * other.x + self.y * other.y + self.z * other.z
+    }
+
+    pub fn cross(self, other: Self) -> Self {
+        Self::new(
+            self.y * other.z - self.z * other.y,
+            self.z * other.x - self.x * other.z,
+            self.x * other.y - self.y * other.x,
+        )
+    }
+
+    pub fn length(self) -> f32 {
+        self.dot(self).sqrt()
+    }
+
+    /// return the vector scaled to a length of 1, or the zero vector if it has no length
+    pub fn normalize(self) -> Self {
+        let length = self.length();
+        if length == 0.0 {
+            Self::ZERO
+        } else {
+            self * (1.0 / length)
+        }
+    }
+
+    /// multiply each component with the matching one of `other`
+    pub fn scale(self, other: Self) -> Self {
+        Self::new(self.x * other.x, self.y * other.y, self.z * other.z)
+    }
+
+    pub fn lerp(self, other: Self, factor: f32) -> Self {
+        self + (other - self) * factor
+    }
+}
+
+impl From<[f32; 3]> for Vector3 {
+    fn from(value: [f32; 3]) -> Self {
+        Self::new(value[0], value[1], value[2])
+    }
+}
+
+impl From<Vector3> for [f32; 3] {
+    fn from(value: Vector3) -> Self {
+        [value.x, value.y, value.z]
+    }
+}
+
+impl Add for Vector3 {
+    type Output = Self;
+    fn add(self, other: Self) -> Self {
+        Self::new(self.x + other.x, self.y + other.y, self.z + other.z)
+    }
+}
+
+impl Sub for Vector3 {
+    type Output = Self;
+    fn sub(self, other: Self) -> Self {
+        Self::new(self.x - other.x, self.y - other.y, self.z - other.z)
+    }
+}
+
+impl Neg for Vector3 {
+    type Output = Self;
+    fn neg(self) -> Self {
+        Self::new(-self.x, -self.y, -self.z)
+    }
+}
+
+impl Mul<f32> for Vector3 {
+    type Output = Self;
+    fn mul(self, factor: f32) -> Self {
+        Self::new(self.x * factor, self.y * factor, self.z * factor)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Quaternion {
+    pub x: f32,
+    pub y: f32,
+    pub z: f32,
+    pub w: f32,
+}
+
+impl Default for Quaternion {
+    fn default() -> Self {
+        Self::IDENTITY
+    }
+}
+
+impl Quaternion {
+    pub const IDENTITY: Quaternion = Quaternion::new(0.0, 0.0, 0.0, 1.0);
+
+    pub const fn new(x: f32, y: f32, z: f32, w: f32) -> Self {
+        Self { x, y, z, w }
+    }
+
+    /// create a rotation of `angle` radian around `axis`, which should be normalized
+    pub fn from_axis_angle(axis: Vector3, angle: f32) -> Self {
+        let (sin, cos) = (angle / 2.0).sin_cos();
+        Self::new(axis.x * sin, axis.y * sin, axis.z * sin, cos)
+    }
+
+    /// create a rotation from euler angles, applied along the X, then Y, then Z axis
+    pub fn from_euler(rotation: Vector3) -> Self {
+        Self::from_axis_angle(Vector3::new(0.0, 0.0, 1.0), rotation.z)
+            * Self::from_axis_angle(Vector3::new(0.0, 1.0, 0.0), rotation.y)
+            * Self::from_axis_angle(Vector3::new(1.0, 0.0, 0.0), rotation.x)
+    }
+
+    /// return the euler angles of the rotation, in the order used by `from_euler`
+    pub fn to_euler(self) -> Vector3 {
+        Matrix3x4::from_rotation(self).get_euler()
+    }
+
+    pub fn dot(self, other: Self) -> f32 {
+        self.x * other.x + self.y * other.y + self.z * other.z + self.w * other.w
+    }
+
+    pub fn normalize(self) -> Self {
+        let length = self.dot(self).sqrt();
+        if length == 0.0 {
+            Self::IDENTITY
+        } else {
+            Self::new(
+                self.x / length,
+                self.y / length,
+                self.z / length,
+                self.w / length,
+            )
+        }
+    }
+
+    pub fn conjugate(self) -> Self {
+        Self::new(-self.x, -self.y, -self.z, self.w)
+    }
+
+    pub fn inverse(self) -> Self {
+        let length_squared = self.dot(self);
+        let conjugate = self.conjugate();
+        Self::new(
+            conjugate.x / length_squared,
+            conjugate.y / length_squared,
+            conjugate.z / length_squared,
+            conjugate.w / length_squared,
+        )
+    }
+
+    pub fn rotate(self, vector: Vector3) -> Vector3 {
+        let axis = Vector3::new(self.x, self.y, self.z);
+        let t = axis.cross(vector) * 2.0;
+        vector + t * self.w + axis.cross(t)
+    }
+
+    /// spherical linear interpolation, taking the shortest path
+    pub fn slerp(self, other: Self, factor: f32) -> Self {
+        let mut cos = self.dot(other);
+        let mut other = other;
+        if cos < 0.0 {
+            cos = -cos;
+            other = Self::new(-other.x, -other.y, -other.z, -other.w);
+        };
+        let (from_factor, to_factor) = if cos > 0.9995 {
+            (1.0 - factor, factor)
+        } else {
+            let angle = cos.acos();
+            let sin = angle.sin();
+            (
+                ((1.0 - factor) * angle).sin() / sin,
+                (factor * angle).sin() / sin,
+            )
+        };
+        Self::new(
+            self.x * from_factor + other.x * to_factor,
+            self.y * from_factor + other.y * to_factor,
+            self.z * from_factor + other.z * to_factor,
+            self.w * from_factor + other.w * to_factor,
+        )
+        .normalize()
+    }
+}
+
+impl From<[f32; 4]> for Quaternion {
+    fn from(value: [f32; 4]) -> Self {
+        Self::new(value[0], value[1], value[2], value[3])
+    }
+}
+
+impl Mul for Quaternion {
+    type Output = Self;
+    fn mul(self, other: Self) -> Self {
+        Self::new(
+            self.w * other.x + self.x * other.w + self.y * other.z - self.z * other.y,
+            self.w * other.y - self.x * other.z + self.y * other.w + self.z * other.x,
+            self.w * other.z + self.x * other.y - self.y * other.x + self.z * other.w,
+            self.w * other.w - self.x * other.x - self.y * other.y - self.z * other.z,
+        )
+    }
+}
+
+/// An affine transform, stored as 3 rows of 4 values, with the translation in the last column
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Matrix3x4 {
+    pub rows: [[f32; 4]; 3],
+}
+
+impl Default for Matrix3x4 {
+    fn default() -> Self {
+        Self::IDENTITY
+    }
+}
+
+impl From<[[f32; 4]; 3]> for Matrix3x4 {
+    fn from(rows: [[f32; 4]; 3]) -> Self {
+        Self { rows }
+    }
+}
+
+impl Matrix3x4 {
+    pub const IDENTITY: Matrix3x4 = Matrix3x4 {
+        rows: [
+            [1.0, 0.0, 0.0, 0.0],
+            [0.0, 1.0, 0.0, 0.0],
+            [0.0, 0.0, 1.0, 0.0],
+        ],
+    };
+
+    pub fn from_translation(translation: Vector3) -> Self {
+        let mut result = Self::IDENTITY;
+        result.rows[0][3] = translation.x;
+        result.rows[1][3] = translation.y;
+        result.rows[2][3] = translation.z;
+        result
+    }
+
+    pub fn from_scale(scale: Vector3) -> Self {
+        let mut result = Self::IDENTITY;
+        result.rows[0][0] = scale.x;
+        result.rows[1][1] = scale.y;
+        result.rows[2][2] = scale.z;
+        result
+    }
+
+    pub fn from_rotation(rotation: Quaternion) -> Self {
+        let Quaternion { x, y, z, w } = rotation.normalize();
+        Self {
+            rows: [
+                [
+                    1.0 - 2.0 * (y * y + z * z),
+                    2.0 * (x * y - z * w),
+                    2.0 * (x * z + y * w),
+                    0.0,
+                ],
+                [
+                    2.0 * (x * y + z * w),
+                    1.0 - 2.0 * (x * x + z * z),
+                    2.0 * (y * z - x * w),
+                    0.0,
+                ],
+                [
+                    2.0 * (x * z - y * w),
+                    2.0 * (y * z + x * w),
+                    1.0 - 2.0 * (x * x + y * y),
+                    0.0,
+                ],
+            ],
+        }
+    }
+
+    pub fn from_euler(rotation: Vector3) -> Self {
+        let (sin_x, cos_x) = rotation.x.sin_cos();
+        let (sin_y, cos_y) = rotation.y.sin_cos();
+        let (sin_z, cos_z) = rotation.z.sin_cos();
+        Self {
+            rows: [
+                [
+                    cos_y * cos_z,
+                    sin_x * sin_y * cos_z - cos_x * sin_z,
+                    cos_x * sin_y * cos_z + sin_x * sin_z,
+                    0.0,
+                ],
+                [
+                    cos_y * sin_z,
+                    sin_x * sin_y * sin_z + cos_x * cos_z,
+                    cos_x * sin_y * sin_z - sin_x * cos_z,
+                    0.0,
+                ],
+                [-sin_y, sin_x * cos_y, cos_x * cos_y, 0.0],
+            ],
+        }
+    }
+
+    /// create a transform that scale, then rotate, then translate
+    pub fn from_srt(scale: Vector3, rotation: Quaternion, translation: Vector3) -> Self {
+        let mut result = Self::from_rotation(rotation);
+        for row in &mut result.rows {
+            row[0] *= scale.x;
+            row[1] *= scale.y;
+            row[2] *= scale.z;
+        }
+        result.rows[0][3] = translation.x;
+        result.rows[1][3] = translation.y;
+        result.rows[2][3] = translation.z;
+        result
+    }
+
+    /// same as `from_srt`, with the rotation as euler angles
+    pub fn from_srt_euler(scale: Vector3, rotation: Vector3, translation: Vector3) -> Self {
+        let mut result = Self::from_euler(rotation) * Self::from_scale(scale);
+        result.rows[0][3] = translation.x;
+        result.rows[1][3] = translation.y;
+        result.rows[2][3] = translation.z;
+        result
+    }
+
+    pub fn get_translation(&self) -> Vector3 {
+        Vector3::new(self.rows[0][3], self.rows[1][3], self.rows[2][3])
+    }
+
+    fn get_column(&self, column: usize) -> Vector3 {
+        Vector3::new(
+            self.rows[0][column],
+            self.rows[1][column],
+            self.rows[2][column],
+        )
+    }
+
+    /// return the scale of each axis. A negative determinant is reported on the X axis.
+    pub fn get_scale(&self) -> Vector3 {
+        let mut scale = Vector3::new(
+            self.get_column(0).length(),
+            self.get_column(1).length(),
+            self.get_column(2).length(),
+        );
+        if self.determinant() < 0.0 {
+            scale.x = -scale.x;
+        };
+        scale
+    }
+
+    /// return the rotation part of the matrix, with the scale removed
+    fn get_rotation_matrix(&self) -> Self {
+        let scale = self.get_scale();
+        let mut result = *self;
+        for row in &mut result.rows {
+            for (column, axis_scale) in [scale.x, scale.y, scale.z].iter().enumerate() {
+                if *axis_scale != 0.0 {
+                    row[column] /= axis_scale;
+                };
+            }
+            row[3] = 0.0;
+        }
+        result
+    }
+
+    pub fn get_rotation(&self) -> Quaternion {
+        let m = self.get_rotation_matrix().rows;
+        let trace = m[0][0] + m[1][1] + m[2][2];
+        let result = if trace > 0.0 {
+            let s = (trace + 1.0).sqrt() * 2.0;
+            Quaternion::new(
+                (m[2][1] - m[1][2]) / s,
+                (m[0][2] - m[2][0]) / s,
+                (m[1][0] - m[0][1]) / s,
+                s / 4.0,
+            )
+        } else if m[0][0] > m[1][1] && m[0][0] > m[2][2] {
+            let s = (1.0 + m[0][0] - m[1][1] - m[2][2]).sqrt() * 2.0;
+            Quaternion::new(
+                s / 4.0,
+                (m[0][1] + m[1][0]) / s,
+                (m[0][2] + m[2][0]) / s,
+                (m[2][1] - m[1][2]) / s,
+            )
+        } else if m[1][1] > m[2][2] {
+            let s = (1.0 + m[1][1] - m[0][0] - m[2][2]).sqrt() * 2.0;
+            Quaternion::new(
+                (m[0][1] + m[1][0]) / s,
+                s / 4.0,
+                (m[1][2] + m[2][1]) / s,
+                (m[0][2] - m[2][0]) / s,
+            )
+        } else {
+            let s = (1.0 + m[2][2] - m[0][0] - m[1][1]).sqrt() * 2.0;
+            Quaternion::new(
+                (m[0][2] + m[2][0]) / s,
+                (m[1][2] + m[2][1]) / s,
+                s / 4.0,
+                (m[1][0] - m[0][1]) / s,
+            )
+        };
+        result.normalize()
+    }
+
+    /// return the rotation as euler angles, in the order used by `from_euler`
+    pub fn get_euler(&self) -> Vector3 {
+        let m = self.get_rotation_matrix().rows;
+        let sin_y = (-m[2][0]).clamp(-1.0, 1.0);
+        if sin_y.abs() < 0.99999 {
+            Vector3::new(m[2][1].atan2(m[2][2]), sin_y.asin(), m[1][0].atan2(m[0][0]))
+        } else {
+            // gimbal lock: only the difference between the X and Z rotation is known
+            Vector3::new((-m[1][2]).atan2(m[1][1]), sin_y.asin(), 0.0)
+        }
+    }
+
+    /// split the transform into its scale, rotation and translation
+    pub fn decompose(&self) -> (Vector3, Quaternion, Vector3) {
+        (self.get_scale(), self.get_rotation(), self.get_translation())
+    }
+
+    pub fn determinant(&self) -> f32 {
+        let m = &self.rows;
+        m[0][0] * (m[1][1] * m[2][2] - m[1][2] * m[2][1])
+            - m[0][1] * (m[1][0] * m[2][2] - m[1][2] * m[2][0])
+            + m[0][2] * (m[1][0] * m[2][1] - m[1][1] * m[2][0])
+    }
+
+    /// return the inverse transform, or `None` if the matrix isn't invertible
+    pub fn inverse(&self) -> Option<Self> {
+        let determinant = self.determinant();
+        if determinant == 0.0 {
+            return None;
+        };
+        let m = &self.rows;
+        let inverse_determinant = 1.0 / determinant;
+        let mut result = Self::IDENTITY;
+        result.rows[0][0] = (m[1][1] * m[2][2] - m[1][2] * m[2][1]) * inverse_determinant;
+        result.rows[0][1] = (m[0][2] * m[2][1] - m[0][1] * m[2][2]) * inverse_determinant;
+        result.rows[0][2] = (m[0][1] * m[1][2] - m[0][2] * m[1][1]) * inverse_determinant;
+        result.rows[1][0] = (m[1][2] * m[2][0] - m[1][0] * m[2][2]) * inverse_determinant;
+        result.rows[1][1] = (m[0][0] * m[2][2] - m[0][2] * m[2][0]) * inverse_determinant;
+        result.rows[1][2] = (m[0][2] * m[1][0] - m[0][0] * m[1][2]) * inverse_determinant;
+        result.rows[2][0] = (m[1][0] * m[2][1] - m[1][1] * m[2][0]) * inverse_determinant;
+        result.rows[2][1] = (m[0][1] * m[2][0] - m[0][0] * m[2][1]) * inverse_determinant;
+        result.rows[2][2] = (m[0][0] * m[1][1] - m[0][1] * m[1][0]) * inverse_determinant;
+        let translation = result.transform_vector(self.get_translation());
+        result.rows[0][3] = -translation.x;
+        result.rows[1][3] = -translation.y;
+        result.rows[2][3] = -translation.z;
+        Some(result)
+    }
+
+    /// transform a position, applying the translation
+    pub fn transform_point(&self, point: Vector3) -> Vector3 {
+        self.transform_vector(point) + self.get_translation()
+    }
+
+    /// transform a direction, ignoring the translation
+    pub fn transform_vector(&self, vector: Vector3) -> Vector3 {
+        let m = &self.rows;
+        Vector3::new(
+            m[0][0] * vector.x + m[0][1] * vector.y + m[0][2] * vector.z,
+            m[1][0] * vector.x + m[1][1] * vector.y + m[1][2] * vector.z,
+            m[2][0] * vector.x + m[2][1] * vector.y + m[2][2] * vector.z,
+        )
+    }
+
+    /// transform a normal, so it stay perpendicular to the surface when the scale isn't uniform
+    pub fn transform_normal(&self, normal: Vector3) -> Vector3 {
+        match self.inverse() {
+            Some(inverse) => inverse.transpose_vector(normal).normalize(),
+            None => self.transform_vector(normal).normalize(),
+        }
+    }
+
+    /// multiply the vector with the transpose of the 3x3 part
+    fn transpose_vector(&self, vector: Vector3) -> Vector3 {
+        Vector3::new(
+            self.get_column(0).dot(vector),
+            self.get_column(1).dot(vector),
+            self.get_column(2).dot(vector),
+        )
+    }
+}
+
+impl Mul for Matrix3x4 {
+    type Output = Self;
+    fn mul(self, other: Self) -> Self {
+        Matrix3x4::from(Matrix4::from(self) * Matrix4::from(other))
+    }
+}
+
+/// A 4x4 matrix, stored by row
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Matrix4 {
+    pub rows: [[f32; 4]; 4],
+}
+
+impl Default for Matrix4 {
+    fn default() -> Self {
+        Self::IDENTITY
+    }
+}
+
+impl From<[[f32; 4]; 4]> for Matrix4 {
+    fn from(rows: [[f32; 4]; 4]) -> Self {
+        Self { rows }
+    }
+}
+
+impl From<Matrix3x4> for Matrix4 {
+    fn from(matrix: Matrix3x4) -> Self {
+        Self {
+            rows: [
+                matrix.rows[0],
+                matrix.rows[1],
+                matrix.rows[2],
+                [0.0, 0.0, 0.0, 1.0],
+            ],
+        }
+    }
+}
+
+/// drop the last row, which is expected to be `[0, 0, 0, 1]`
+impl From<Matrix4> for Matrix3x4 {
+    fn from(matrix: Matrix4) -> Self {
+        Self {
+            rows: [matrix.rows[0], matrix.rows[1], matrix.rows[2]],
+        }
+    }
+}
+
+impl Matrix4 {
+    pub const IDENTITY: Matrix4 = Matrix4 {
+        rows: [
+            [1.0, 0.0, 0.0, 0.0],
+            [0.0, 1.0, 0.0, 0.0],
+            [0.0, 0.0, 1.0, 0.0],
+            [0.0, 0.0, 0.0, 1.0],
+        ],
+    };
+
+    pub fn transpose(&self) -> Self {
+        let mut result = Self::IDENTITY;
+        for (row, values) in self.rows.iter().enumerate() {
+            for (column, value) in values.iter().enumerate() {
+                result.rows[column][row] = *value;
+            }
+        }
+        result
+    }
+
+    /// return the matrix as 16 values, one column after the other
+    pub fn to_column_major(&self) -> [f32; 16] {
+        let mut result = [0.0; 16];
+        for (column, values) in self.transpose().rows.iter().enumerate() {
+            result[column * 4..column * 4 + 4].copy_from_slice(values);
+        }
+        result
+    }
+
+    /// return the inverse of the matrix, or `None` if it isn't invertible
+    pub fn inverse(&self) -> Option<Self> {
+        // gauss-jordan elimination with partial pivoting
+        let mut left = self.rows;
+        let mut right = Self::IDENTITY.rows;
+        for column in 0..4 {
+            let mut pivot = column;
+            for row in column + 1..4 {
+                if left[row][column].abs() > left[pivot][column].abs() {
+                    pivot = row;
+                };
+            }
+            if left[pivot][column] == 0.0 {
+                return None;
+            };
+            left.swap(column, pivot);
+            right.swap(column, pivot);
+
+            let factor = 1.0 / left[column][column];
+            for value in 0..4 {
+                left[column][value] *= factor;
+                right[column][value] *= factor;
+            }
+            for row in 0..4 {
+                if row == column {
+                    continue;
+                };
+                let factor = left[row][column];
+                for value in 0..4 {
+                    left[row][value] -= factor * left[column][value];
+                    right[row][value] -= factor * right[column][value];
+                }
+            }
+        }
+        Some(Self { rows: right })
+    }
+
+    pub fn transform_point(&self, point: Vector3) -> Vector3 {
+        let m = &self.rows;
+        let transformed = |row: usize| {
+            m[row][0] * point.x + m[row][1] * point.y + m[row][2] * point.z + m[row][3]
+        };
+        let w = transformed(3);
+        let w = if w == 0.0 { 1.0 } else { w };
+        Vector3::new(transformed(0) / w, transformed(1) / w, transformed(2) / w)
+    }
+}
+
+impl Mul for Matrix4 {
+    type Output = Self;
+    fn mul(self, other: Self) -> Self {
+        let mut result = [[0.0; 4]; 4];
+        for (row, values) in result.iter_mut().enumerate() {
+            for (column, value) in values.iter_mut().enumerate() {
+                *value = (0..4)
+                    .map(|index| self.rows[row][index] * other.rows[index][column])
+                    .sum();
+            }
+        }
+        Self { rows: result }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EPSILON: f32 = 1e-4;
+
+    fn assert_vector_close(value: Vector3, expected: Vector3) {
+        assert!(
+            (value - expected).length() < EPSILON,
+            "{:?} is not {:?}",
+            value,
+            expected
+        );
+    }
+
+    /// a quaternion and its opposite are the same rotation
+    fn assert_rotation_close(value: Quaternion, expected: Quaternion) {
+        assert!(
+            value.dot(expected).abs() > 1.0 - EPSILON,
+            "{:?} is not {:?}",
+            value,
+            expected
+        );
+    }
+
+    fn assert_matrix_close(value: Matrix3x4, expected: Matrix3x4) {
+        for (row, expected_row) in value.rows.iter().zip(expected.rows.iter()) {
+            for (component, expected_component) in row.iter().zip(expected_row.iter()) {
+                assert!(
+                    (component - expected_component).abs() < EPSILON,
+                    "{:?} is not {:?}",
+                    value,
+                    expected
+                );
+            }
+        }
+    }
+
+    fn transforms() -> Vec<(Vector3, Vector3, Vector3)> {
+        vec![
+            (Vector3::ONE, Vector3::ZERO, Vector3::ZERO),
+            (
+                Vector3::new(2.0, 0.5, 3.0),
+                Vector3::new(0.3, -1.2, 2.5),
+                Vector3::new(10.0, -4.0, 0.25),
+            ),
+            (
+                Vector3::new(0.1, 0.1, 0.1),
+                Vector3::new(-2.0, 0.7, -0.4),
+                Vector3::new(0.0, 100.0, -50.0),
+            ),
+        ]
+    }
+
+    #[test]
+    fn decompose_srt() {
+        for (scale, rotation, translation) in transforms() {
+            let matrix = Matrix3x4::from_srt_euler(scale, rotation, translation);
+            let (decomposed_scale, decomposed_rotation, decomposed_translation) =
+                matrix.decompose();
+            assert_vector_close(decomposed_scale, scale);
+            assert_rotation_close(decomposed_rotation, Quaternion::from_euler(rotation));
+            assert_vector_close(decomposed_translation, translation);
+            assert_vector_close(matrix.get_euler(), rotation);
+        }
+    }
+
+    #[test]
+    fn inverse() {
+        for (scale, rotation, translation) in transforms() {
+            let matrix = Matrix3x4::from_srt_euler(scale, rotation, translation);
+            let inverse = matrix.inverse().unwrap();
+            assert_matrix_close(matrix * inverse, Matrix3x4::IDENTITY);
+            assert_matrix_close(inverse * matrix, Matrix3x4::IDENTITY);
+        }
+        assert!(Matrix3x4::from_scale(Vector3::new(1.0, 0.0, 1.0))
+            .inverse()
+            .is_none());
+    }
+
+    #[test]
+    fn euler_quaternion_matches_matrix() {
+        for (_, rotation, _) in transforms() {
+            let quaternion = Quaternion::from_euler(rotation);
+            let matrix = Matrix3x4::from_srt_euler(Vector3::ONE, rotation, Vector3::ZERO);
+            assert_matrix_close(Matrix3x4::from_rotation(quaternion), matrix);
+            let point = Vector3::new(1.0, -2.0, 3.0);
+            assert_vector_close(quaternion.rotate(point), matrix.transform_point(point));
+            assert_vector_close(quaternion.to_euler(), rotation);
+        }
+    }
 }
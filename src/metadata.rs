@@ -0,0 +1,214 @@
+use crate::deserialize::{
+    read_f32_le, read_i32_le, read_matrix3_f32, read_referenced_null_terminated_ascii_string,
+    read_u16_le, read_u32_le, read_vector3_f32,
+};
+use crate::{ReferenceDict, ReferenceDictError, Vector3};
+use std::io;
+use std::io::{Read, Seek, SeekFrom};
+
+#[derive(Debug)]
+pub enum MetaDataError {
+    IOError(io::Error, &'static str),
+    NullReference(&'static str),
+    ReferenceDictError(ReferenceDictError),
+    InvalidMetaDataType(u16),
+}
+
+fn ioe(err: io::Error, content: &'static str) -> MetaDataError {
+    MetaDataError::IOError(err, content)
+}
+
+/// read a null terminated UTF-16 string referenced by the next word
+fn read_referenced_unicode_string<F: Read + Seek>(file: &mut F) -> Result<String, MetaDataError> {
+    let offset = read_u32_le(file).map_err(|e| ioe(e, "unicode string offset"))?;
+    if offset == 0 {
+        return Err(MetaDataError::NullReference("unicode string"));
+    };
+    let position = file
+        .stream_position()
+        .map_err(|e| ioe(e, "unicode string offset"))?;
+    file.seek(SeekFrom::Start(offset as u64))
+        .map_err(|e| ioe(e, "unicode string"))?;
+    let mut units = Vec::new();
+    loop {
+        let unit = read_u16_le(file).map_err(|e| ioe(e, "unicode string"))?;
+        if unit == 0 {
+            break;
+        };
+        units.push(unit);
+    }
+    file.seek(SeekFrom::Start(position))
+        .map_err(|e| ioe(e, "after unicode string"))?;
+    Ok(String::from_utf16_lossy(&units))
+}
+
+/// An oriented box
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BoundingBox {
+    pub center: Vector3,
+    /// the rotation of the box, as a 3x3 matrix
+    pub orientation: [[f32; 3]; 3],
+    pub size: Vector3,
+}
+
+impl BoundingBox {
+    pub fn read<F: Read>(file: &mut F) -> Result<Self, io::Error> {
+        Ok(Self {
+            center: Vector3::from(read_vector3_f32(file)?),
+            orientation: read_matrix3_f32(file)?,
+            size: Vector3::from(read_vector3_f32(file)?),
+        })
+    }
+}
+
+/// Values attached to each vertex of a mesh
+#[derive(Debug, Clone, PartialEq)]
+pub struct VertexData {
+    pub attribute: u16,
+    pub values: Vec<f32>,
+}
+
+impl VertexData {
+    fn read<F: Read + Seek>(file: &mut F) -> Result<Self, MetaDataError> {
+        let attribute = read_u16_le(file).map_err(|e| ioe(e, "vertex data attribute"))?;
+        read_u16_le(file).map_err(|e| ioe(e, "vertex data padding"))?;
+        let values_offset = read_u32_le(file).map_err(|e| ioe(e, "vertex data offset"))?;
+        let value_count = read_u32_le(file).map_err(|e| ioe(e, "vertex data count"))?;
+        let position = file
+            .stream_position()
+            .map_err(|e| ioe(e, "vertex data offset"))?;
+        file.seek(SeekFrom::Start(values_offset as u64))
+            .map_err(|e| ioe(e, "vertex data"))?;
+        let mut values = Vec::with_capacity(value_count as usize);
+        for _ in 0..value_count {
+            values.push(read_f32_le(file).map_err(|e| ioe(e, "vertex data value"))?);
+        }
+        file.seek(SeekFrom::Start(position))
+            .map_err(|e| ioe(e, "after vertex data"))?;
+        Ok(Self { attribute, values })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum MetaDataContent {
+    Integer(Vec<i32>),
+    Float(Vec<f32>),
+    String(Vec<String>),
+    UnicodeString(Vec<String>),
+    BoundingBox(Vec<BoundingBox>),
+    VertexData(Vec<VertexData>),
+}
+
+/// A named list of values
+#[derive(Debug, Clone, PartialEq)]
+pub struct MetaDataValue {
+    pub name: String,
+    pub content: MetaDataContent,
+}
+
+impl MetaDataValue {
+    pub fn read<F: Read + Seek>(file: &mut F) -> Result<Self, MetaDataError> {
+        let name = read_referenced_null_terminated_ascii_string(file)
+            .map_err(|e| ioe(e, "meta data name"))?
+            .ok_or(MetaDataError::NullReference("meta data name"))?;
+        let value_type = read_u16_le(file).map_err(|e| ioe(e, "meta data type"))?;
+        read_u16_le(file).map_err(|e| ioe(e, "meta data padding"))?;
+        let values_offset = read_u32_le(file).map_err(|e| ioe(e, "meta data values offset"))?;
+        let value_count = read_u32_le(file).map_err(|e| ioe(e, "meta data value count"))?;
+
+        file.seek(SeekFrom::Start(values_offset as u64))
+            .map_err(|e| ioe(e, "meta data values"))?;
+        let count = value_count as usize;
+        let content = match value_type {
+            0 => MetaDataContent::Integer(
+                (0..count)
+                    .map(|_| read_i32_le(file).map_err(|e| ioe(e, "integer value")))
+                    .collect::<Result<_, _>>()?,
+            ),
+            1 => MetaDataContent::Float(
+                (0..count)
+                    .map(|_| read_f32_le(file).map_err(|e| ioe(e, "float value")))
+                    .collect::<Result<_, _>>()?,
+            ),
+            2 => MetaDataContent::String(
+                (0..count)
+                    .map(|_| {
+                        read_referenced_null_terminated_ascii_string(file)
+                            .map_err(|e| ioe(e, "string value"))?
+                            .ok_or(MetaDataError::NullReference("string value"))
+                    })
+                    .collect::<Result<_, _>>()?,
+            ),
+            3 => MetaDataContent::UnicodeString(
+                (0..count)
+                    .map(|_| read_referenced_unicode_string(file))
+                    .collect::<Result<_, _>>()?,
+            ),
+            4 => MetaDataContent::BoundingBox(
+                (0..count)
+                    .map(|_| BoundingBox::read(file).map_err(|e| ioe(e, "bounding box value")))
+                    .collect::<Result<_, _>>()?,
+            ),
+            5 => MetaDataContent::VertexData(
+                (0..count)
+                    .map(|_| VertexData::read(file))
+                    .collect::<Result<_, _>>()?,
+            ),
+            _ => return Err(MetaDataError::InvalidMetaDataType(value_type)),
+        };
+
+        Ok(Self { name, content })
+    }
+}
+
+/// The user data attached to a model, a bone, a material or other objects
+#[derive(Debug, Clone, PartialEq)]
+pub struct MetaData {
+    pub values: Vec<MetaDataValue>,
+}
+
+impl MetaData {
+    pub fn read<F: Read + Seek>(file: &mut F) -> Result<Self, MetaDataError> {
+        let dict =
+            ReferenceDict::read(file, "meta data").map_err(MetaDataError::ReferenceDictError)?;
+        let mut values = Vec::with_capacity(dict.pointer_table_entries as usize);
+        for entry in 0..dict.pointer_table_entries {
+            file.seek(SeekFrom::Start(
+                (dict.pointer_table_offset + entry * 4) as u64,
+            ))
+            .map_err(|e| ioe(e, "meta data pointer table"))?;
+            let value_offset = read_u32_le(file).map_err(|e| ioe(e, "meta data value offset"))?;
+            file.seek(SeekFrom::Start(value_offset as u64))
+                .map_err(|e| ioe(e, "meta data value"))?;
+            values.push(MetaDataValue::read(file)?);
+        }
+        Ok(Self { values })
+    }
+
+    /// read the meta data at the given offset, if it isn't null. The position in the file is kept.
+    pub fn read_at<F: Read + Seek>(
+        file: &mut F,
+        offset: u32,
+    ) -> Result<Option<Self>, MetaDataError> {
+        if offset == 0 {
+            return Ok(None);
+        };
+        let position = file
+            .stream_position()
+            .map_err(|e| ioe(e, "meta data offset"))?;
+        file.seek(SeekFrom::Start(offset as u64))
+            .map_err(|e| ioe(e, "meta data"))?;
+        let meta_data = Self::read(file)?;
+        file.seek(SeekFrom::Start(position))
+            .map_err(|e| ioe(e, "after meta data"))?;
+        Ok(Some(meta_data))
+    }
+
+    /// return the value with the given name
+    pub fn get(&self, name: &str) -> Option<&MetaDataContent> {
+        self.values
+            .iter()
+            .find(|value| value.name == name)
+            .map(|value| &value.content)
+    }
+}
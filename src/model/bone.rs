@@ -0,0 +1,174 @@
+use crate::deserialize::{
+    read_f32_le, read_i16_le, read_matrix3x4_f32, read_referenced_null_terminated_ascii_string,
+    read_u16_le, read_u32_le, read_vector,
+};
+use crate::{Matrix3x4, MetaData, MetaDataError, Vector3};
+use std::io;
+use std::io::{Read, Seek};
+
+#[derive(Debug)]
+pub enum BoneError {
+    IOError(io::Error, &'static str),
+    NullReference(&'static str),
+    InvalidBillboardMode(u8),
+    MetaDataError(MetaDataError),
+}
+
+fn ioe(err: io::Error, content: &'static str) -> BoneError {
+    BoneError::IOError(err, content)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BillboardMode {
+    Off,
+    World,
+    WorldViewpoint,
+    Screen,
+    ScreenViewpoint,
+    YAxial,
+    YAxialViewpoint,
+}
+
+impl BillboardMode {
+    pub fn new(value: u8) -> Option<Self> {
+        Some(match value {
+            0 => Self::Off,
+            2 => Self::World,
+            3 => Self::WorldViewpoint,
+            4 => Self::Screen,
+            5 => Self::ScreenViewpoint,
+            6 => Self::YAxial,
+            7 => Self::YAxialViewpoint,
+            _ => return None,
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Bone {
+    pub name: String,
+    pub flags: u32,
+    pub billboard_mode: BillboardMode,
+    pub is_segment_scale_compensate: bool,
+    /// index of the parent bone in the skeleton, or -1 for a root bone
+    pub parent_id: i16,
+    pub scale: Vector3,
+    /// rotation in radian, along the X, Y then Z axis
+    pub rotation: Vector3,
+    pub translation: Vector3,
+    pub inverse_transform: Matrix3x4,
+    pub meta_data_pointer_offset: u32,
+    pub meta_data: Option<MetaData>,
+}
+
+impl Bone {
+    pub fn read<F: Read + Seek>(file: &mut F) -> Result<Bone, BoneError> {
+        let flags = read_u32_le(file).map_err(|e| ioe(e, "flags"))?;
+        let billboard_mode_value = (flags >> 16 & 0xf) as u8;
+        let billboard_mode = BillboardMode::new(billboard_mode_value)
+            .ok_or(BoneError::InvalidBillboardMode(billboard_mode_value))?;
+        let is_segment_scale_compensate = flags & 0x0040_0000 != 0;
+
+        let parent_id = read_i16_le(file).map_err(|e| ioe(e, "parent id"))?;
+        read_u16_le(file).map_err(|e| ioe(e, "padding"))?;
+
+        let mut scale = [0.0; 3];
+        read_vector(file, read_f32_le, &mut scale).map_err(|e| ioe(e, "scale"))?;
+        let mut rotation = [0.0; 3];
+        read_vector(file, read_f32_le, &mut rotation).map_err(|e| ioe(e, "rotation"))?;
+        let mut translation = [0.0; 3];
+        read_vector(file, read_f32_le, &mut translation).map_err(|e| ioe(e, "translation"))?;
+
+        let inverse_transform =
+            Matrix3x4::from(read_matrix3x4_f32(file).map_err(|e| ioe(e, "inverse transform"))?);
+
+        let name = read_referenced_null_terminated_ascii_string(file)
+            .map_err(|e| ioe(e, "bone name"))?
+            .ok_or(BoneError::NullReference("bone name"))?;
+
+        let meta_data_pointer_offset =
+            read_u32_le(file).map_err(|e| ioe(e, "meta data pointer offset"))?;
+        let meta_data = MetaData::read_at(file, meta_data_pointer_offset)
+            .map_err(BoneError::MetaDataError)?;
+
+        Ok(Bone {
+            name,
+            flags,
+            billboard_mode,
+            is_segment_scale_compensate,
+            parent_id,
+            scale: Vector3::from(scale),
+            rotation: Vector3::from(rotation),
+            translation: Vector3::from(translation),
+            inverse_transform,
+            meta_data_pointer_offset,
+            meta_data,
+        })
+    }
+
+    /// the transform of the bone relative to its parent
+    pub fn get_local_transform(&self) -> Matrix3x4 {
+        Matrix3x4::from_srt_euler(self.scale, self.rotation, self.translation)
+    }
+}
+
+/// compute the transform of every bone of a skeleton relative to the model, in bind pose
+pub fn get_world_transforms(skeleton: &[Bone]) -> Vec<Matrix3x4> {
+    let local_transforms: Vec<Matrix3x4> = skeleton.iter().map(Bone::get_local_transform).collect();
+    compose_world_transforms(skeleton, &local_transforms)
+}
+
+/// compute the transform of every bone of a skeleton relative to the model, from the transform
+/// of each bone relative to its parent
+pub fn compose_world_transforms(
+    skeleton: &[Bone],
+    local_transforms: &[Matrix3x4],
+) -> Vec<Matrix3x4> {
+    let mut world_transforms: Vec<Option<Matrix3x4>> = vec![None; skeleton.len()];
+    for bone_id in 0..skeleton.len() {
+        compute_world_transform(
+            skeleton,
+            local_transforms,
+            bone_id,
+            &mut world_transforms,
+            0,
+        );
+    }
+    world_transforms
+        .into_iter()
+        .map(|transform| transform.unwrap_or_default())
+        .collect()
+}
+
+fn compute_world_transform(
+    skeleton: &[Bone],
+    local_transforms: &[Matrix3x4],
+    bone_id: usize,
+    world_transforms: &mut [Option<Matrix3x4>],
+    depth: usize,
+) -> Matrix3x4 {
+    if let Some(transform) = world_transforms[bone_id] {
+        return transform;
+    };
+    let bone = &skeleton[bone_id];
+    let local_transform = local_transforms[bone_id];
+    // a parent out of the skeleton or a loop in the hierarchy is treated as a root bone
+    let transform = match bone.parent_id {
+        parent_id
+            if parent_id >= 0
+                && (parent_id as usize) < skeleton.len()
+                && depth < skeleton.len() =>
+        {
+            compute_world_transform(
+                skeleton,
+                local_transforms,
+                parent_id as usize,
+                world_transforms,
+                depth + 1,
+            ) * local_transform
+        }
+        _ => local_transform,
+    };
+    world_transforms[bone_id] = Some(transform);
+    transform
+}
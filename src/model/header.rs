@@ -1,7 +1,8 @@
 use crate::deserialize::{
-    read_matrix4x3_f32_le, read_referenced_null_terminated_ascii_string, read_u16_le, read_u32_le,
+    read_matrix3x4_f32, read_referenced_null_terminated_ascii_string, read_u16_le, read_u32_le,
     read_u8,
 };
+use crate::Matrix3x4;
 use crate::{ReferenceDict, ReferenceDictError};
 use std::io;
 use std::io::{Read, Seek, SeekFrom};
@@ -29,7 +30,7 @@ pub struct ModelHeader {
     pub flags: u8,
     pub skeleton_scaling_type: u8,
     pub silhouette_material_entries: u16,
-    pub world_transform: [[f32; 3]; 4],
+    pub world_transform: Matrix3x4,
     pub materials: ReferenceDict,
     pub vertices: ReferenceDict,
     pub skeletons: ReferenceDict,
@@ -50,7 +51,8 @@ impl ModelHeader {
         let silhouette_material_entries =
             read_u16_le(file).map_err(|e| ioe(e, "silhouette material entries"))?;
 
-        let world_transform = read_matrix4x3_f32_le(file).map_err(|e| ioe(e, "world transform"))?;
+        let world_transform =
+            Matrix3x4::from(read_matrix3x4_f32(file).map_err(|e| ioe(e, "world transform"))?);
 
         let materials = ReferenceDict::read(file, "materials")?;
 
@@ -0,0 +1,62 @@
+/// An index buffer, stored with the smallest integer type able to address every vertex. The
+/// value 65535 is kept out of `U16` buffers, as glTF reserves it for the primitive restart.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Indices {
+    U16(Vec<u16>),
+    U32(Vec<u32>),
+}
+
+impl Default for Indices {
+    fn default() -> Self {
+        Self::U16(Vec::new())
+    }
+}
+
+impl Indices {
+    pub fn new(indices: Vec<u32>, vertex_count: usize) -> Self {
+        if vertex_count <= u16::MAX as usize {
+            Self::U16(indices.iter().map(|index| *index as u16).collect())
+        } else {
+            Self::U32(indices)
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        match self {
+            Self::U16(indices) => indices.len(),
+            Self::U32(indices) => indices.len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn get(&self, position: usize) -> Option<u32> {
+        match self {
+            Self::U16(indices) => indices.get(position).map(|index| *index as u32),
+            Self::U32(indices) => indices.get(position).copied(),
+        }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = u32> + '_ {
+        (0..self.len()).map(move |position| self.get(position).unwrap())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn u16_up_to_65535_vertices() {
+        let indices = Indices::new(vec![0, 65534], 65535);
+        assert_eq!(indices, Indices::U16(vec![0, 65534]));
+    }
+
+    #[test]
+    fn u32_from_65536_vertices() {
+        let indices = Indices::new(vec![0, 65535], 65536);
+        assert_eq!(indices, Indices::U32(vec![0, 65535]));
+    }
+}
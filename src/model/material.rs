@@ -0,0 +1,452 @@
+use crate::deserialize::{
+    read_f32_le, read_referenced_null_terminated_ascii_string, read_rgba, read_u16_le,
+    read_u32_le, read_u8, read_vector2_f32,
+};
+use crate::{AlphaTest, BlendFunction, DepthOperation, StencilTest};
+use crate::{MetaData, MetaDataError};
+use crate::{PICACommandReader, PICACommandReaderError};
+use std::io;
+use std::io::{Read, Seek, SeekFrom};
+
+#[derive(Debug)]
+pub enum MaterialError {
+    IOError(io::Error, &'static str),
+    PICACommandReaderError(PICACommandReaderError, &'static str),
+    NullReference(&'static str),
+    InvalidTextureProjection(u8),
+    InvalidTextureWrap(u8),
+    InvalidTextureMagFilter(u8),
+    InvalidTextureMinFilter(u8),
+    InvalidFragmentOperation(&'static str),
+    MetaDataError(MetaDataError),
+}
+
+fn ioe(err: io::Error, content: &'static str) -> MaterialError {
+    MaterialError::IOError(err, content)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TextureProjection {
+    UVMap,
+    CameraCubeMap,
+    CameraSphereMap,
+    ProjectionMap,
+    ShadowMap,
+    ShadowCubeMap,
+}
+
+impl TextureProjection {
+    pub fn new(value: u8) -> Option<Self> {
+        Some(match value {
+            0 => Self::UVMap,
+            1 => Self::CameraCubeMap,
+            2 => Self::CameraSphereMap,
+            3 => Self::ProjectionMap,
+            4 => Self::ShadowMap,
+            5 => Self::ShadowCubeMap,
+            _ => return None,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TextureWrap {
+    ClampToEdge,
+    ClampToBorder,
+    Repeat,
+    MirroredRepeat,
+}
+
+impl TextureWrap {
+    pub fn new(value: u8) -> Option<Self> {
+        Some(match value {
+            0 => Self::ClampToEdge,
+            1 => Self::ClampToBorder,
+            2 => Self::Repeat,
+            3 => Self::MirroredRepeat,
+            _ => return None,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TextureMagFilter {
+    Nearest,
+    Linear,
+}
+
+impl TextureMagFilter {
+    pub fn new(value: u8) -> Option<Self> {
+        Some(match value {
+            0 => Self::Nearest,
+            1 => Self::Linear,
+            _ => return None,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TextureMinFilter {
+    Nearest,
+    NearestMipmapNearest,
+    NearestMipmapLinear,
+    Linear,
+    LinearMipmapNearest,
+    LinearMipmapLinear,
+}
+
+impl TextureMinFilter {
+    pub fn new(value: u8) -> Option<Self> {
+        Some(match value {
+            0 => Self::Nearest,
+            1 => Self::NearestMipmapNearest,
+            2 => Self::NearestMipmapLinear,
+            3 => Self::Linear,
+            4 => Self::LinearMipmapNearest,
+            5 => Self::LinearMipmapLinear,
+            _ => return None,
+        })
+    }
+}
+
+/// How the texture coordinates of a texture unit are generated and transformed
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TextureCoordinator {
+    pub projection: TextureProjection,
+    pub reference_camera: u8,
+    pub scale: [f32; 2],
+    pub rotation: f32,
+    pub translation: [f32; 2],
+}
+
+impl TextureCoordinator {
+    pub fn read<F: Read>(file: &mut F) -> Result<Self, MaterialError> {
+        // the two first bytes are unused
+        read_u16_le(file).map_err(|e| ioe(e, "texture coordinator unknown data"))?;
+        let projection_value =
+            read_u8(file).map_err(|e| ioe(e, "texture coordinator projection"))?;
+        let projection = TextureProjection::new(projection_value)
+            .ok_or(MaterialError::InvalidTextureProjection(projection_value))?;
+        let reference_camera =
+            read_u8(file).map_err(|e| ioe(e, "texture coordinator reference camera"))?;
+        let scale = read_vector2_f32(file).map_err(|e| ioe(e, "texture coordinator scale"))?;
+        let rotation = read_f32_le(file).map_err(|e| ioe(e, "texture coordinator rotation"))?;
+        let translation =
+            read_vector2_f32(file).map_err(|e| ioe(e, "texture coordinator translation"))?;
+
+        Ok(TextureCoordinator {
+            projection,
+            reference_camera,
+            scale,
+            rotation,
+            translation,
+        })
+    }
+}
+
+/// The sampler setting of a texture unit
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TextureMapper {
+    pub wrap_u: TextureWrap,
+    pub wrap_v: TextureWrap,
+    pub mag_filter: TextureMagFilter,
+    pub min_filter: TextureMinFilter,
+    pub min_lod: u8,
+    pub lod_bias: f32,
+    pub border_color: [u8; 4],
+}
+
+impl TextureMapper {
+    pub fn read<F: Read>(file: &mut F) -> Result<Self, MaterialError> {
+        let wrap_and_mag_filter =
+            read_u32_le(file).map_err(|e| ioe(e, "texture mapper wrap and mag filter"))?;
+        let lod_and_min_filter =
+            read_u32_le(file).map_err(|e| ioe(e, "texture mapper lod and min filter"))?;
+        let lod_bias = read_f32_le(file).map_err(|e| ioe(e, "texture mapper lod bias"))?;
+        let border_color = read_rgba(file).map_err(|e| ioe(e, "texture mapper border color"))?;
+
+        let wrap_u_value = (wrap_and_mag_filter >> 8 & 0xff) as u8;
+        let wrap_v_value = (wrap_and_mag_filter >> 16 & 0xff) as u8;
+        let mag_filter_value = (wrap_and_mag_filter >> 24) as u8;
+        let min_filter_value = (lod_and_min_filter & 0xff) as u8;
+
+        Ok(TextureMapper {
+            wrap_u: TextureWrap::new(wrap_u_value)
+                .ok_or(MaterialError::InvalidTextureWrap(wrap_u_value))?,
+            wrap_v: TextureWrap::new(wrap_v_value)
+                .ok_or(MaterialError::InvalidTextureWrap(wrap_v_value))?,
+            mag_filter: TextureMagFilter::new(mag_filter_value)
+                .ok_or(MaterialError::InvalidTextureMagFilter(mag_filter_value))?,
+            min_filter: TextureMinFilter::new(min_filter_value)
+                .ok_or(MaterialError::InvalidTextureMinFilter(min_filter_value))?,
+            min_lod: (lod_and_min_filter >> 8 & 0xff) as u8,
+            lod_bias,
+            border_color,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MaterialColors {
+    pub emission: [u8; 4],
+    pub ambient: [u8; 4],
+    pub diffuse: [u8; 4],
+    pub specular0: [u8; 4],
+    pub specular1: [u8; 4],
+    pub constant: [[u8; 4]; 6],
+    pub blend: [u8; 4],
+    pub scale: f32,
+}
+
+/// Name of a fragment lighting look up table, and of the sampler it is stored in
+#[derive(Debug, Clone, PartialEq)]
+pub struct LUTReference {
+    pub table_name: Option<String>,
+    pub sampler_name: Option<String>,
+}
+
+impl LUTReference {
+    pub fn read<F: Read + Seek>(file: &mut F) -> Result<Self, io::Error> {
+        let table_name = read_referenced_null_terminated_ascii_string(file)?;
+        let sampler_name = read_referenced_null_terminated_ascii_string(file)?;
+        Ok(LUTReference {
+            table_name,
+            sampler_name,
+        })
+    }
+}
+
+/// The part of a material shared between the global material dict and the model materials
+#[derive(Debug)]
+pub struct MaterialParameters {
+    pub unique_id: u32,
+    pub flags: u16,
+    pub fragment_flags: u16,
+    pub texture_coordinators: Vec<TextureCoordinator>,
+    pub light_set_index: u16,
+    pub fog_index: u16,
+    pub colors: MaterialColors,
+    /// index of the constant color used by each texture combiner
+    pub constant_color_index: [u8; 6],
+    pub lut_input_absolute: u32,
+    pub lut_input_selection: u32,
+    pub lut_input_scale: u32,
+    pub polygon_offset_unit: f32,
+    pub lut_distribution0: LUTReference,
+    pub lut_distribution1: LUTReference,
+    pub lut_fresnel: LUTReference,
+    pub lut_reflectance_r: LUTReference,
+    pub lut_reflectance_g: LUTReference,
+    pub lut_reflectance_b: LUTReference,
+    pub shader_reference: Option<String>,
+    pub model_reference: Option<String>,
+    pub meta_data_pointer_offset: u32,
+    pub meta_data: Option<MetaData>,
+    pub blend_function: BlendFunction,
+    pub alpha_test: AlphaTest,
+    pub stencil_test: StencilTest,
+    pub depth_operation: DepthOperation,
+}
+
+impl MaterialParameters {
+    pub fn read<F: Read + Seek>(file: &mut F) -> Result<Self, MaterialError> {
+        let unique_id = read_u32_le(file).map_err(|e| ioe(e, "unique id"))?;
+        let flags = read_u16_le(file).map_err(|e| ioe(e, "flags"))?;
+        let fragment_flags = read_u16_le(file).map_err(|e| ioe(e, "fragment flags"))?;
+
+        file.seek(SeekFrom::Current(4))
+            .map_err(|e| ioe(e, "unknown data n°1"))?;
+
+        let mut texture_coordinators = Vec::new();
+        for _ in 0..3 {
+            texture_coordinators.push(TextureCoordinator::read(file)?);
+        }
+
+        let light_set_index = read_u16_le(file).map_err(|e| ioe(e, "light set index"))?;
+        let fog_index = read_u16_le(file).map_err(|e| ioe(e, "fog index"))?;
+
+        let emission = read_rgba(file).map_err(|e| ioe(e, "emission color"))?;
+        let ambient = read_rgba(file).map_err(|e| ioe(e, "ambient color"))?;
+        let diffuse = read_rgba(file).map_err(|e| ioe(e, "diffuse color"))?;
+        let specular0 = read_rgba(file).map_err(|e| ioe(e, "specular 0 color"))?;
+        let specular1 = read_rgba(file).map_err(|e| ioe(e, "specular 1 color"))?;
+        let mut constant = [[0; 4]; 6];
+        for color in &mut constant {
+            *color = read_rgba(file).map_err(|e| ioe(e, "constant color"))?;
+        }
+        let blend = read_rgba(file).map_err(|e| ioe(e, "blend color"))?;
+        let scale = read_f32_le(file).map_err(|e| ioe(e, "color scale"))?;
+
+        file.seek(SeekFrom::Current(0x18))
+            .map_err(|e| ioe(e, "unknown data n°2"))?;
+
+        let constant_colors = read_u32_le(file).map_err(|e| ioe(e, "constant colors"))?;
+        let mut constant_color_index = [0; 6];
+        for (stage, index) in constant_color_index.iter_mut().enumerate() {
+            *index = (constant_colors >> (stage * 4) & 0xf) as u8;
+        }
+
+        // the lut configuration is stored as three PICA commands, we only keep the parameter
+        let lut_input_absolute = read_u32_le(file).map_err(|e| ioe(e, "lut input absolute"))?;
+        read_u32_le(file).map_err(|e| ioe(e, "lut input absolute command header"))?;
+        let lut_input_selection = read_u32_le(file).map_err(|e| ioe(e, "lut input selection"))?;
+        read_u32_le(file).map_err(|e| ioe(e, "lut input selection command header"))?;
+        let lut_input_scale = read_u32_le(file).map_err(|e| ioe(e, "lut input scale"))?;
+        read_u32_le(file).map_err(|e| ioe(e, "lut input scale command header"))?;
+
+        file.seek(SeekFrom::Current(4))
+            .map_err(|e| ioe(e, "unknown data n°3"))?;
+
+        let polygon_offset_unit =
+            read_f32_le(file).map_err(|e| ioe(e, "polygon offset unit"))?;
+
+        let fragment_commands_offset =
+            read_u32_le(file).map_err(|e| ioe(e, "fragment commands offset"))?;
+        let fragment_commands_word_count =
+            read_u32_le(file).map_err(|e| ioe(e, "fragment commands word count"))?;
+
+        file.seek(SeekFrom::Current(4))
+            .map_err(|e| ioe(e, "unknown data n°4"))?;
+
+        let lut_distribution0 = LUTReference::read(file).map_err(|e| ioe(e, "lut distribution 0"))?;
+        let lut_distribution1 = LUTReference::read(file).map_err(|e| ioe(e, "lut distribution 1"))?;
+        let lut_fresnel = LUTReference::read(file).map_err(|e| ioe(e, "lut fresnel"))?;
+        let lut_reflectance_r = LUTReference::read(file).map_err(|e| ioe(e, "lut reflectance r"))?;
+        let lut_reflectance_g = LUTReference::read(file).map_err(|e| ioe(e, "lut reflectance g"))?;
+        let lut_reflectance_b = LUTReference::read(file).map_err(|e| ioe(e, "lut reflectance b"))?;
+
+        let shader_reference = read_referenced_null_terminated_ascii_string(file)
+            .map_err(|e| ioe(e, "shader reference"))?;
+        let model_reference = read_referenced_null_terminated_ascii_string(file)
+            .map_err(|e| ioe(e, "model reference"))?;
+
+        let meta_data_pointer_offset =
+            read_u32_le(file).map_err(|e| ioe(e, "meta data pointer offset"))?;
+        let meta_data = MetaData::read_at(file, meta_data_pointer_offset)
+            .map_err(MaterialError::MetaDataError)?;
+
+        file.seek(SeekFrom::Start(fragment_commands_offset as u64))
+            .map_err(|e| ioe(e, "fragment commands"))?;
+        let fragment_commands =
+            PICACommandReader::read(file, fragment_commands_word_count as u64)
+                .map_err(|e| MaterialError::PICACommandReaderError(e, "fragment commands"))?;
+
+        let blend_function = fragment_commands
+            .get_blend_function()
+            .ok_or(MaterialError::InvalidFragmentOperation("blend function"))?;
+        let alpha_test = fragment_commands
+            .get_alpha_test()
+            .ok_or(MaterialError::InvalidFragmentOperation("alpha test"))?;
+        let stencil_test = fragment_commands
+            .get_stencil_test()
+            .ok_or(MaterialError::InvalidFragmentOperation("stencil test"))?;
+        let depth_operation = fragment_commands
+            .get_depth_operation()
+            .ok_or(MaterialError::InvalidFragmentOperation("depth operation"))?;
+
+        Ok(MaterialParameters {
+            unique_id,
+            flags,
+            fragment_flags,
+            texture_coordinators,
+            light_set_index,
+            fog_index,
+            colors: MaterialColors {
+                emission,
+                ambient,
+                diffuse,
+                specular0,
+                specular1,
+                constant,
+                blend,
+                scale,
+            },
+            constant_color_index,
+            lut_input_absolute,
+            lut_input_selection,
+            lut_input_scale,
+            polygon_offset_unit,
+            lut_distribution0,
+            lut_distribution1,
+            lut_fresnel,
+            lut_reflectance_r,
+            lut_reflectance_g,
+            lut_reflectance_b,
+            shader_reference,
+            model_reference,
+            meta_data_pointer_offset,
+            meta_data,
+            blend_function,
+            alpha_test,
+            stencil_test,
+            depth_operation,
+        })
+    }
+}
+
+#[derive(Debug)]
+pub struct Material {
+    pub name: String,
+    pub texture0_name: Option<String>,
+    pub texture1_name: Option<String>,
+    pub texture2_name: Option<String>,
+    pub texture_mappers: Vec<TextureMapper>,
+    pub parameters: MaterialParameters,
+}
+
+impl Material {
+    /// read a material entry of a model. The file is left at the start of the next entry.
+    pub fn read<F: Read + Seek>(file: &mut F) -> Result<Self, MaterialError> {
+        let parameters_offset =
+            read_u32_le(file).map_err(|e| ioe(e, "material parameters offset"))?;
+
+        // unknown data, followed by the texture commands, already present in the textures
+        file.seek(SeekFrom::Current(0x14))
+            .map_err(|e| ioe(e, "unknown data and texture commands"))?;
+
+        let texture_mapper_offset =
+            read_u32_le(file).map_err(|e| ioe(e, "texture mapper offset"))?;
+
+        let texture0_name = read_referenced_null_terminated_ascii_string(file)
+            .map_err(|e| ioe(e, "texture 0 name"))?;
+        let texture1_name = read_referenced_null_terminated_ascii_string(file)
+            .map_err(|e| ioe(e, "texture 1 name"))?;
+        let texture2_name = read_referenced_null_terminated_ascii_string(file)
+            .map_err(|e| ioe(e, "texture 2 name"))?;
+        let name = read_referenced_null_terminated_ascii_string(file)
+            .map_err(|e| ioe(e, "material name"))?
+            .ok_or(MaterialError::NullReference("material name"))?;
+
+        let next_entry = file
+            .stream_position()
+            .map_err(|e| ioe(e, "seeking to the current location"))?;
+
+        if parameters_offset == 0 {
+            return Err(MaterialError::NullReference("material parameters"));
+        };
+        file.seek(SeekFrom::Start(parameters_offset as u64))
+            .map_err(|e| ioe(e, "material parameters"))?;
+        let parameters = MaterialParameters::read(file)?;
+
+        let mut texture_mappers = Vec::new();
+        if texture_mapper_offset != 0 {
+            file.seek(SeekFrom::Start(texture_mapper_offset as u64))
+                .map_err(|e| ioe(e, "texture mapper"))?;
+            for _ in 0..3 {
+                texture_mappers.push(TextureMapper::read(file)?);
+            }
+        };
+
+        file.seek(SeekFrom::Start(next_entry))
+            .map_err(|e| ioe(e, "next material entry"))?;
+
+        Ok(Material {
+            name,
+            texture0_name,
+            texture1_name,
+            texture2_name,
+            texture_mappers,
+            parameters,
+        })
+    }
+}
@@ -12,3 +12,18 @@ pub use object::{Object, ObjectError};
 
 mod vertex;
 pub use vertex::Vertex;
+
+mod indices;
+pub use indices::Indices;
+
+mod submesh;
+pub use submesh::SubMesh;
+
+mod material;
+pub use material::{
+    LUTReference, Material, MaterialColors, MaterialError, MaterialParameters, TextureCoordinator,
+    TextureMagFilter, TextureMapper, TextureMinFilter, TextureProjection, TextureWrap,
+};
+
+mod bone;
+pub use bone::{compose_world_transforms, get_world_transforms, BillboardMode, Bone, BoneError};
@@ -1,9 +1,12 @@
 use crate::deserialize::{
-    read_referenced_null_terminated_ascii_string, read_vec_inline, ReadVecError,
+    read_referenced_null_terminated_ascii_string, read_u32_le, read_vec_inline, ReadVecError,
 };
+use crate::model::{get_world_transforms, Bone, BoneError};
+use crate::model::{Material, MaterialError};
 use crate::model::{ModelHeader, ModelHeaderError};
 use crate::model::{Object, ObjectError};
 use crate::model::{ObjectEntry, ObjectEntryError};
+use crate::{Matrix3x4, MetaData, MetaDataError};
 use std::io;
 use std::io::{Read, Seek, SeekFrom};
 
@@ -12,20 +15,24 @@ pub enum ModelError {
     ModelHeaderError(ModelHeaderError),
     SeekError(io::Error, &'static str),
     ReadStringError(io::Error, &'static str),
+    IOError(io::Error, &'static str),
     NullReference(&'static str),
     ReadObjectEntryError(ReadVecError<ObjectEntryError>),
     ObjectError(ObjectError),
+    ReadMaterialError(ReadVecError<MaterialError>),
+    ReadBoneError(ReadVecError<BoneError>),
+    MetaDataError(MetaDataError),
 }
 
 #[derive(Debug)]
 pub struct Model {
-    //name: String,
+    pub name: String,
     //layer_id: u32,
     pub mesh: Vec<Object>,
-    //skeleton: Vec<Bone>,
-    //material: Vec<Material>,
-    //metadata: Vec<MetaData>,
-    //transform:
+    pub skeleton: Vec<Bone>,
+    pub material: Vec<Material>,
+    pub meta_data: Option<MetaData>,
+    pub world_transform: Matrix3x4,
     //min_vector
     //max_vector
     //vertices_count: i32,
@@ -51,6 +58,45 @@ impl Model {
             );
         }
 
+        // object node visibility, with a bit per node
+        let mut node_visibility = Vec::with_capacity(header.object_node_count as usize);
+        if header.object_node_visibility_offset != 0 {
+            file.seek(SeekFrom::Start(header.object_node_visibility_offset as u64))
+                .map_err(|e| ModelError::SeekError(e, "object node visibility"))?;
+            let mut visibility_word = 0;
+            for node_id in 0..header.object_node_count {
+                if node_id % 32 == 0 {
+                    visibility_word = read_u32_le(file)
+                        .map_err(|e| ModelError::IOError(e, "object node visibility"))?;
+                };
+                node_visibility.push(visibility_word >> (node_id % 32) & 1 != 0);
+            }
+        };
+
+        // materials
+        file.seek(SeekFrom::Start(header.materials.pointer_table_offset as u64))
+            .map_err(|e| ModelError::SeekError(e, "materials"))?;
+
+        let material = read_vec_inline(
+            file,
+            Material::read,
+            header.materials.pointer_table_entries as u64,
+        )
+        .map_err(ModelError::ReadMaterialError)?;
+
+        // skeleton
+        file.seek(SeekFrom::Start(header.skeletons.pointer_table_offset as u64))
+            .map_err(|e| ModelError::SeekError(e, "skeleton"))?;
+
+        let skeleton = read_vec_inline(
+            file,
+            Bone::read,
+            header.skeletons.pointer_table_entries as u64,
+        )
+        .map_err(ModelError::ReadBoneError)?;
+
+        let bone_transforms = get_world_transforms(&skeleton);
+
         // vertices header
         file.seek(SeekFrom::Start(header.vertices.pointer_table_offset as u64))
             .map_err(|e| ModelError::SeekError(e, "vertices header"))?;
@@ -60,27 +106,27 @@ impl Model {
             |f| ObjectEntry::read(f),
             header.vertices.pointer_table_entries as u64,
         )
-        .map_err(|e| ModelError::ReadObjectEntryError(e))?;
+        .map_err(ModelError::ReadObjectEntryError)?;
 
         // vertices
         let mut mesh = Vec::new();
         for obj in objects_entry.iter() {
             mesh.push(
-                Object::read(file, obj, &object_name).map_err(|e| ModelError::ObjectError(e))?,
+                Object::read(file, obj, &object_name, &node_visibility, &bone_transforms).map_err(ModelError::ObjectError)?,
             );
         };
 
-        if header.meta_data_pointer_offset != 0 {
-            file.seek(SeekFrom::Start(header.meta_data_pointer_offset as u64)).map_err(|e| ModelError::SeekError(e, "model meta data"))?;
-            debug!("TODO: in model.rs: read metadata");
-        };
-
-        debug!("TODO: in model.rs: something related to skeleton");
+        let meta_data = MetaData::read_at(file, header.meta_data_pointer_offset)
+            .map_err(ModelError::MetaDataError)?;
 
         Ok(Model {
-            //name,
+            name: header.model_name,
             //layer_id,
             mesh,
+            skeleton,
+            material,
+            meta_data,
+            world_transform: header.world_transform,
         })
     }
 }
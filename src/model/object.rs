@@ -1,13 +1,14 @@
 use crate::deserialize::{read_u16_le, read_u32_le, read_u8, read_i8, read_i16_le, read_f32_le};
 use crate::model::ObjectEntry;
-use crate::model::Vertex;
+use crate::model::{Indices, SubMesh, Vertex};
 use crate::IndexBufferFormat;
 use crate::SkinningMode;
+use crate::{Matrix3x4, Vector3};
 use crate::VSHAttribute;
 use crate::{AttributeFormat, AttributeFormatType, AttributeData};
-use crate::{PICACommandReader, PICACommandReaderError};
-use crate::math::vector3_tranform;
+use crate::{PICACommandReader, PICACommandReaderError, ShaderUniforms, ShaderUnit};
 use std::cmp::max;
+use std::collections::HashMap;
 use std::io;
 use std::io::{Read, Seek, SeekFrom};
 
@@ -17,33 +18,174 @@ pub enum ObjectError {
     PICACommandReaderError(PICACommandReaderError, &'static str),
     NotLongEnoughtError(&'static str),
     InvalidSkinning,
+    InvalidBoneIndex(u32),
 }
 
 fn ioe(err: io::Error, content: &'static str) -> ObjectError {
     ObjectError::IOError(err, content)
 }
 
-fn get_vector<F: Read>(file: &mut F, format: AttributeFormat) -> Result<[AttributeData; 4], ObjectError> {
-    let read_function: Box<dyn Fn(&mut F) -> Result<AttributeData, ObjectError>>;
-    match format.r#type {
-        AttributeFormatType::SignedByte => read_function = Box::new(|f| read_i8(f).map_err(|e| ioe(e, "i8 member of a vector")).map(|x| AttributeData::I8(x))),
-        AttributeFormatType::UnsignedByte => read_function = Box::new(|f| read_u8(f).map_err(|e| ioe(e, "u8 member of a vector")).map(|x| AttributeData::U8(x))),
-        AttributeFormatType::SignedShort => read_function = Box::new(|f| read_i16_le(f).map_err(|e| ioe(e, "i16 member of a vector")).map(|x| AttributeData::I16(x))),
-        AttributeFormatType::Single => read_function = Box::new(|f| read_f32_le(f).map_err(|e| ioe(e, "f32 member of a vector")).map(|x| AttributeData::F32(x))),
-    };
+fn read_attribute_data<F: Read>(file: &mut F, r#type: AttributeFormatType) -> Result<AttributeData, ObjectError> {
+    Ok(match r#type {
+        AttributeFormatType::SignedByte => AttributeData::I8(read_i8(file).map_err(|e| ioe(e, "i8 member of a vector"))?),
+        AttributeFormatType::UnsignedByte => AttributeData::U8(read_u8(file).map_err(|e| ioe(e, "u8 member of a vector"))?),
+        AttributeFormatType::SignedShort => AttributeData::I16(read_i16_le(file).map_err(|e| ioe(e, "i16 member of a vector"))?),
+        AttributeFormatType::Single => AttributeData::F32(read_f32_le(file).map_err(|e| ioe(e, "f32 member of a vector"))?),
+    })
+}
 
+fn get_vector<F: Read>(file: &mut F, format: AttributeFormat) -> Result<[AttributeData; 4], ObjectError> {
     let mut result = [AttributeData::None; 4];
 
-    for loop_count in 0..(max(format.attribute_length, 3)+1) as usize {
-        result[loop_count] = read_function(file)?;
+    for value in result.iter_mut().take(format.attribute_length as usize + 1) {
+        *value = read_attribute_data(file, format.r#type)?;
     };
 
     Ok(result)
 }
 
+/// convert an attribute to the value of an input register, where the missing components are
+/// filled with (0, 0, 0, 1)
+fn get_input_register(vector: &[AttributeData; 4], format: AttributeFormat) -> [f32; 4] {
+    let mut register = [0.0, 0.0, 0.0, 1.0];
+    let length = format.attribute_length as usize + 1;
+    for (value, data) in register.iter_mut().zip(vector).take(length) {
+        *value = data.to_f32();
+    };
+    register
+}
+
+/// The layout and decoding parameters of the vertex buffer of an object
+struct VertexBuffer {
+    offset: u32,
+    stride: u8,
+    /// the attributes in the order they are stored, with the input register they are loaded in
+    attributes: Vec<(VSHAttribute, AttributeFormat, u8)>,
+    position_offset: [f32; 4],
+    position_scale: f32,
+    normal_scale: f32,
+    tangent_scale: f32,
+    color_scale: f32,
+    texture0_scale: f32,
+    texture1_scale: f32,
+    texture2_scale: f32,
+    bone_weight_scale: f32,
+}
+
+impl VertexBuffer {
+    /// read a vertex, and the vertex shader input registers loaded from it
+    fn read_vertex<F: Read + Seek>(
+        &self,
+        file: &mut F,
+        index: u16,
+        skinning_mode: SkinningMode,
+        node_list: &[u16],
+        bone_transforms: &[Matrix3x4],
+    ) -> Result<(Vertex, [[f32; 4]; 16]), ObjectError> {
+        let vertex_offset = self.offset as u64 + (index as u64 * self.stride as u64);
+        file.seek(SeekFrom::Start(vertex_offset))
+            .map_err(|e| ioe(e, "vertex offset"))?;
+
+        let mut vertex = Vertex {
+            diffuse_color: 0xffffffff,
+            ..Vertex::default()
+        };
+        let mut inputs = [[0.0; 4]; 16];
+        for (att, format, register) in &self.attributes {
+            let mut format = *format;
+            if *att == VSHAttribute::BoneWeight {
+                format.r#type = AttributeFormatType::UnsignedByte;
+            };
+
+            let vector = get_vector(file, format)?;
+            if let Some(input) = inputs.get_mut(*register as usize) {
+                *input = get_input_register(&vector, format);
+            };
+
+            match att {
+                VSHAttribute::Position => {
+                    let x: f32 = (vector[0].to_f32() * self.position_scale) + self.position_offset[0];
+                    let y: f32 = (vector[1].to_f32() * self.position_scale) + self.position_offset[1];
+                    let z: f32 = (vector[2].to_f32() * self.position_scale) + self.position_offset[2];
+                    vertex.position = [x, y, z];
+                },
+                VSHAttribute::Normal => {
+                    vertex.normal = [vector[0].to_f32() * self.normal_scale, vector[1].to_f32() * self.normal_scale, vector[2].to_f32() * self.normal_scale]
+                },
+                VSHAttribute::Tangent => {
+                    vertex.tangent = [vector[0].to_f32() * self.tangent_scale, vector[1].to_f32() * self.tangent_scale, vector[2].to_f32() * self.tangent_scale]
+                },
+                VSHAttribute::Color => {
+                    let channel = |value: AttributeData| (value.to_f32() * self.color_scale * 255.0).clamp(0.0, 255.0) as u32;
+                    vertex.diffuse_color = channel(vector[2]) | (channel(vector[1]) << 8) | (channel(vector[0]) << 16) | (channel(vector[3]) << 24);
+                },
+                VSHAttribute::TextureCoordinate0 => {
+                    vertex.texture0 = [vector[0].to_f32() * self.texture0_scale, vector[1].to_f32() * self.texture0_scale]
+                },
+                VSHAttribute::TextureCoordinate1 => {
+                    vertex.texture1 = [vector[0].to_f32() * self.texture1_scale, vector[1].to_f32() * self.texture1_scale]
+                },
+                VSHAttribute::TextureCoordinate2 => {
+                    vertex.texture2 = [vector[0].to_f32() * self.texture2_scale, vector[1].to_f32() * self.texture2_scale]
+                },
+                VSHAttribute::BoneIndex => {
+                    // only smooth skinning can use more than one bone per vertex
+                    let bone_count = if skinning_mode == SkinningMode::SmoothSkinning { format.attribute_length as usize + 1 } else { 1 };
+                    for value in vector.iter().take(bone_count) {
+                        let node = node_list.get(value.to_f32() as usize).ok_or(ObjectError::InvalidBoneIndex(value.to_f32() as u32))?;
+                        vertex.node.push((*node).into());
+                    };
+                },
+                VSHAttribute::BoneWeight => {
+                    let weight_count = if skinning_mode == SkinningMode::SmoothSkinning { format.attribute_length as usize + 1 } else { 1 };
+                    for value in vector.iter().take(weight_count) {
+                        vertex.weight.push(value.to_f32() * self.bone_weight_scale);
+                    };
+                },
+                unused => debug!("TODO: in object.rs: a {:?} is not used", unused)
+            };
+        }
+
+        if vertex.node.is_empty() && node_list.len() <= 4 {
+            for n in node_list {
+                vertex.node.push((*n).into())
+            };
+            if vertex.weight.is_empty() {
+                vertex.weight.push(1.0);
+            };
+        };
+
+        if skinning_mode != SkinningMode::SmoothSkinning && !vertex.node.is_empty() {
+            if vertex.weight.is_empty() {
+                vertex.weight.push(1.0);
+            };
+            // rigidly skinned vertices are stored relative to their bone. A bone out of the
+            // skeleton is treated as the identity, like the parent of a root bone.
+            if let Some(bone_transform) = bone_transforms.get(vertex.node[0] as usize) {
+                vertex.position = bone_transform
+                    .transform_point(Vector3::from(vertex.position))
+                    .into();
+                vertex.normal = bone_transform
+                    .transform_normal(Vector3::from(vertex.normal))
+                    .into();
+                vertex.tangent = bone_transform
+                    .transform_vector(Vector3::from(vertex.tangent))
+                    .into();
+            };
+        }
+
+        Ok((vertex, inputs))
+    }
+}
+
 #[derive(Debug)]
 pub struct Object {
+    /// every vertex used by the object, with no duplicate inside a face group
     pub vertices: Vec<Vertex>,
+    /// the triangle list, indexing `vertices`
+    pub indices: Indices,
+    /// the face groups, in the order they are stored
+    pub sub_meshes: Vec<SubMesh>,
     pub material_id: u16,
     pub render_priority: u16,
     pub name: String,
@@ -55,6 +197,11 @@ pub struct Object {
     pub has_node: bool,
     pub has_weight: bool,
     pub tex_uv_count: i32,
+    /// the vertex shader uniforms uploaded by the commands of the object
+    pub shader_uniforms: ShaderUniforms,
+    /// the vertex shader input registers (v0 to v15) of each vertex, as loaded by the hardware
+    /// from the vertex buffer, before any scale. Registers not loaded are zero.
+    pub shader_inputs: Vec<[[f32; 4]; 16]>,
 }
 
 impl Object {
@@ -62,6 +209,8 @@ impl Object {
         file: &mut F,
         obj: &ObjectEntry,
         object_name: &[String],
+        node_visibility: &[bool],
+        bone_transforms: &[Matrix3x4],
     ) -> Result<Object, ObjectError> {
         let mut has_node = false;
         let mut has_weight = false;
@@ -78,8 +227,8 @@ impl Object {
             object_name[obj.node_id as usize].clone()
         };
 
-        //TODO: object visibility
-        let is_visible = true;
+        // a node without visibility bit is visible
+        let is_visible = *node_visibility.get(obj.node_id as usize).unwrap_or(&true);
 
         //vertices
         file.seek(SeekFrom::Start(
@@ -91,217 +240,167 @@ impl Object {
             PICACommandReader::read(file, obj.vsh_attributes_buffer_commands_word_count as u64)
                 .map_err(|e| ObjectError::PICACommandReaderError(e, "vsh commands"))?;
 
-        let mut vsh_attributes_uniform_reg6 = vsh_commands.float_uniform[6].clone();
-        let mut vsh_attributes_uniform_reg7 = vsh_commands.float_uniform[7].clone();
-
-        let mut position_offset = [0.0; 4];
-        for value in &mut position_offset {
-            *value = vsh_attributes_uniform_reg6
-                .pop()
-                .ok_or(ObjectError::NotLongEnoughtError(
-                    "vsh attributes uniform reg 6 for position offset",
-                ))?;
+        // the default shader gets the position offset in c6, the position, normal, tangent
+        // and color scales in c7, and the texture coordinate and bone weight scales in c8
+        if vsh_commands.float_uniform[6].len() < 4 {
+            return Err(ObjectError::NotLongEnoughtError("vsh attributes uniform reg 6"));
+        };
+        if vsh_commands.float_uniform[7].len() < 8 {
+            return Err(ObjectError::NotLongEnoughtError("vsh attributes uniform reg 7"));
+        };
+        let shader_uniforms = ShaderUniforms::from_commands(&vsh_commands);
+        let position_offset = shader_uniforms.float[6];
+        let [position_scale, normal_scale, tangent_scale, color_scale] = shader_uniforms.float[7];
+        let [texture0_scale, texture1_scale, texture2_scale, bone_weight_scale] =
+            shader_uniforms.float[8];
+
+        let vsh_total_attributes = vsh_commands.get_vsh_total_attributes(0);
+        let vsh_main_attributes_buffer_permutation =
+            vsh_commands.get_vsh_attributes_buffer_permutation_none();
+        let vsh_attributes_buffer_permutation =
+            vsh_commands.get_vsh_attributes_buffer_permutation(0);
+        let vsh_attributes_buffer_format = vsh_commands.get_vsh_attributes_buffer_format();
+        let input_permutation = vsh_commands.get_shader_setup(ShaderUnit::Vertex).input_permutation;
+
+        let mut attributes = Vec::new();
+        for attribute in 0..vsh_total_attributes {
+            let permutation = *vsh_attributes_buffer_permutation
+                .get(attribute as usize)
+                .ok_or(ObjectError::NotLongEnoughtError("vsh attributes buffer permutation"))?
+                as usize;
+            let vsh_attribute = *vsh_main_attributes_buffer_permutation
+                .get(permutation)
+                .ok_or(ObjectError::NotLongEnoughtError("vsh main attributes buffer permutation"))?;
+            let format = *vsh_attributes_buffer_format
+                .get(permutation)
+                .ok_or(ObjectError::NotLongEnoughtError("vsh attributes buffer format"))?;
+            match vsh_attribute {
+                VSHAttribute::Normal => has_normal = true,
+                VSHAttribute::Tangent => has_tangent = true,
+                VSHAttribute::Color => has_color = true,
+                VSHAttribute::TextureCoordinate0 => tex_uv_count = max(tex_uv_count, 1),
+                VSHAttribute::TextureCoordinate1 => tex_uv_count = max(tex_uv_count, 2),
+                VSHAttribute::TextureCoordinate2 => tex_uv_count = max(tex_uv_count, 3),
+                _ => (),
+            };
+            let register = *input_permutation
+                .get(permutation)
+                .ok_or(ObjectError::NotLongEnoughtError("vsh input permutation"))?;
+            attributes.push((vsh_attribute, format, register));
         }
 
-        let texture0_scale =
-            vsh_attributes_uniform_reg7
-                .pop()
-                .ok_or(ObjectError::NotLongEnoughtError(
-                    "vsh attributes uniform reg 7 for texture 0 scale",
-                ))?;
-        let texture1_scale =
-            vsh_attributes_uniform_reg7
-                .pop()
-                .ok_or(ObjectError::NotLongEnoughtError(
-                    "vsh attributes uniform reg 7 for texture 1 scale",
-                ))?;
-        let texture2_scale =
-            vsh_attributes_uniform_reg7
-                .pop()
-                .ok_or(ObjectError::NotLongEnoughtError(
-                    "vsh attributes uniform reg 7 for texture 2 scale",
-                ))?;
-        let bone_weight_scale =
-            vsh_attributes_uniform_reg7
-                .pop()
-                .ok_or(ObjectError::NotLongEnoughtError(
-                    "vsh attributes uniform reg 7 for bone weight scale",
-                ))?;
-        let position_scale =
-            vsh_attributes_uniform_reg7
-                .pop()
-                .ok_or(ObjectError::NotLongEnoughtError(
-                    "vsh attributes uniform reg 7 for position scale",
-                ))?;
-        let normal_scale =
-            vsh_attributes_uniform_reg7
-                .pop()
-                .ok_or(ObjectError::NotLongEnoughtError(
-                    "vsh attributes uniform reg 7 for normal scale",
-                ))?;
-        let tangent_scale =
-            vsh_attributes_uniform_reg7
-                .pop()
-                .ok_or(ObjectError::NotLongEnoughtError(
-                    "vsh attributes uniform reg 7 for tangent scale",
-                ))?;
-        let color_scale =
-            vsh_attributes_uniform_reg7
-                .pop()
-                .ok_or(ObjectError::NotLongEnoughtError(
-                    "vsh attributes uniform reg 7 for color scale",
-                ))?;
+        let vertex_buffer = VertexBuffer {
+            offset: vsh_commands.get_vsh_attributes_buffer_offset(0),
+            stride: vsh_commands.get_vsh_attributes_buffer_stride(0),
+            attributes,
+            position_offset,
+            position_scale,
+            normal_scale,
+            tangent_scale,
+            color_scale,
+            texture0_scale,
+            texture1_scale,
+            texture2_scale,
+            bone_weight_scale,
+        };
 
         // faces
         let faces_count = obj.faces_header_entries;
-        let has_faces = faces_count > 0;
-        let mut faces_table_offset = 0;
-
-        if !has_faces {
+        if faces_count == 0 {
             todo!("TODO: has faces");
         };
 
         let mut vertices = Vec::new();
-        
+        let mut shader_inputs = Vec::new();
+        let mut indices = Vec::new();
+        let mut sub_meshes = Vec::new();
+
         for f in 0..faces_count {
-            let mut skinning_mode: SkinningMode;
+            let base_offset = obj.faces_header_offset + f * 0x34;
+            file.seek(SeekFrom::Start(base_offset as u64))
+                .map_err(|e| ioe(e, "a faces data"))?;
+            let skinning_mode =
+                SkinningMode::new(read_u16_le(file).map_err(|e| ioe(e, "skinning mode"))?)
+                    .ok_or(ObjectError::InvalidSkinning)?;
+            let node_id_entries = read_u16_le(file).map_err(|e| ioe(e, "node id entries"))?;
             let mut node_list: Vec<u16> = Vec::new();
-            let idx_buffer_offset: u32;
-            let idx_buffer_format: IndexBufferFormat;
-            let idx_buffer_total_vertices: u32;
-
-            if has_faces {
-                let base_offset = obj.faces_header_offset + f * 0x34;
-                file.seek(SeekFrom::Start(base_offset as u64))
-                    .map_err(|e| ioe(e, "a faces data"))?;
-                skinning_mode =
-                    SkinningMode::new(read_u16_le(file).map_err(|e| ioe(e, "skinning mode"))?)
-                        .ok_or(ObjectError::InvalidSkinning)?;
-                let node_id_entries = read_u16_le(file).map_err(|e| ioe(e, "node id entries"))?;
-                for _ in 0..node_id_entries {
-                    node_list.push(read_u16_le(file).map_err(|e| ioe(e, "node entry"))?);
-                }
-                file.seek(SeekFrom::Start((base_offset + 0x2c) as u64))
-                    .map_err(|e| ioe(e, "some data"))?;
-                let face_header_offset =
-                    read_u32_le(file).map_err(|e| ioe(e, "face header offset"))?;
-                let face_header_word_count =
-                    read_u32_le(file).map_err(|e| ioe(e, "face header word count"))?;
-                file.seek(SeekFrom::Start(face_header_offset as u64))
-                    .map_err(|e| ioe(e, "face header offset"))?;
-
-                let idx_commands = PICACommandReader::read(file, face_header_word_count as u64)
-                    .map_err(|e| ObjectError::PICACommandReaderError(e, "idx commands"))?;
-                idx_buffer_offset = idx_commands.get_index_buffer_address();
-                idx_buffer_format = idx_commands.get_index_buffer_format();
-                idx_buffer_total_vertices = idx_commands.get_index_buffer_total_vertices();
-            } else {
-                todo!("TODO: has faces");
+            for _ in 0..node_id_entries {
+                node_list.push(read_u16_le(file).map_err(|e| ioe(e, "node entry"))?);
             }
-
-            let vsh_attributes_buffer_offset = vsh_commands.get_vsh_attributes_buffer_offset(0);
-            let vsh_attributes_buffer_stride = vsh_commands.get_vsh_attributes_buffer_stride(0);
-            let vsh_total_attributes = vsh_commands.get_vsh_total_attributes(0);
-            let vsh_main_attributes_buffer_permutation = vsh_commands.get_vsh_attributes_buffer_permutation_none();
-            let vsh_attributes_buffer_permutation = vsh_commands.get_vsh_attributes_buffer_permutation(0);
-            let vsh_attributes_buffer_format = vsh_commands.get_vsh_attributes_buffer_format();
-
-            for attribute in 0..vsh_total_attributes {
-                match vsh_main_attributes_buffer_permutation.get(
-                    *vsh_attributes_buffer_permutation.get(
-                        attribute as usize
-                    ).unwrap() as usize //TODO: remove those unwrap
-                ).unwrap() {
-                    VSHAttribute::Normal => has_normal = true,
-                    VSHAttribute::Tangent => has_tangent = true,
-                    VSHAttribute::Color => has_color = true,
-                    VSHAttribute::TextureCoordinate0 => {
-                        tex_uv_count = max(tex_uv_count, 1);
-                        break
-                    },
-                    VSHAttribute::TextureCoordinate1 => {
-                        tex_uv_count = max(tex_uv_count, 2);
-                        break
-                    },
-                    VSHAttribute::TextureCoordinate2 => {
-                        tex_uv_count = max(tex_uv_count, 3);
-                        break
-                    },
-                    _ => (),
-                }
-            };
+            file.seek(SeekFrom::Start((base_offset + 0x2c) as u64))
+                .map_err(|e| ioe(e, "some data"))?;
+            let face_header_offset = read_u32_le(file).map_err(|e| ioe(e, "face header offset"))?;
+            let face_header_word_count =
+                read_u32_le(file).map_err(|e| ioe(e, "face header word count"))?;
+            file.seek(SeekFrom::Start(face_header_offset as u64))
+                .map_err(|e| ioe(e, "face header offset"))?;
+
+            let idx_commands = PICACommandReader::read(file, face_header_word_count as u64)
+                .map_err(|e| ObjectError::PICACommandReaderError(e, "idx commands"))?;
+            let idx_buffer_offset = idx_commands.get_index_buffer_address();
+            let idx_buffer_format = idx_commands.get_index_buffer_format();
+            let idx_buffer_total_vertices = idx_commands.get_index_buffer_total_vertices();
 
             if !node_list.is_empty() {
                 has_node = true;
                 has_weight = true;
             };
 
-            file.seek(SeekFrom::Start(idx_buffer_offset as u64)).map_err(|e| ioe(e, "idx buffer offset"))?;
-
-            for face_index in 0..idx_buffer_total_vertices {
-                let index: u16 = match idx_buffer_format {
-                    IndexBufferFormat::U8 => read_u8(file).map(|v| v as u16),
-                    IndexBufferFormat::U16 => read_u16_le(file),
-                }.map_err(|e| ioe(e, "index"))?;
-
-                let file_position = file.seek(SeekFrom::Current(0)).map_err(|e| ioe(e, "seeking to the current location"))?;
-                let vertex_offset = vsh_attributes_buffer_offset as u64 + (index as u64 * vsh_attributes_buffer_stride as u64);
-                file.seek(SeekFrom::Start(vertex_offset)).map_err(|e| ioe(e, "vertex offset"))?;
-
-                let mut vertex = Vertex::default();
-                vertex.diffuse_color = 0xffffffff;
-                for attribute in 0..vsh_total_attributes {
-                    let att = vsh_main_attributes_buffer_permutation.get(*vsh_attributes_buffer_permutation.get(attribute as usize).unwrap() as usize).unwrap(); //TODO
-                    let mut format = vsh_attributes_buffer_format.get(*vsh_attributes_buffer_permutation.get(attribute as usize).unwrap() as usize).unwrap().clone(); //TODO
-                    if *att == VSHAttribute::BoneWeight {
-                        format.r#type = AttributeFormatType::UnsignedByte;
-                    };
-
-                    let vector = get_vector(file, format)?;
-
-                    match att {
-                        VSHAttribute::Position => {
-                            let x: f32 = (vector[0].to_f32() * position_scale) + position_offset[0];
-                            let y: f32 = (vector[1].to_f32() * position_scale) + position_offset[1];
-                            let z: f32 = (vector[2].to_f32() * position_scale) + position_offset[2];
-                            vertex.position = [x, y, z];
-                        },
-                        VSHAttribute::Normal => {
-                            vertex.normal = [vector[0].to_f32() * normal_scale, vector[1].to_f32() * normal_scale, vector[2].to_f32() * normal_scale]
-                        },
-                        VSHAttribute::Tangent => {
-                            vertex.tangent = [vector[0].to_f32() * tangent_scale, vector[1].to_f32() * tangent_scale, vector[2].to_f32() * tangent_scale]
-                        },
-                        unused => debug!("TODO: in object.rs: a {:?} is not used", unused)
-                    };
-                }
+            file.seek(SeekFrom::Start(idx_buffer_offset as u64))
+                .map_err(|e| ioe(e, "idx buffer offset"))?;
+            let mut raw_indices = Vec::with_capacity(idx_buffer_total_vertices as usize);
+            for _ in 0..idx_buffer_total_vertices {
+                raw_indices.push(
+                    match idx_buffer_format {
+                        IndexBufferFormat::U8 => read_u8(file).map(|v| v as u16),
+                        IndexBufferFormat::U16 => read_u16_le(file),
+                    }
+                    .map_err(|e| ioe(e, "index"))?,
+                );
+            }
 
-                if vertex.node.len() == 0 && node_list.len() <= 4 {
-                    for n in &node_list {
-                        vertex.node.push(n.clone().into())
-                    };
-                    if vertex.weight.is_empty() {
-                        vertex.weight.push(1.0);
-                    };
+            let first_index = indices.len();
+            let first_vertex = vertices.len();
+
+            // vertices are only shared inside a face group, as each has its own bone palette
+            let mut vertex_map: HashMap<u16, u32> = HashMap::new();
+            for raw_index in raw_indices {
+                let index = match vertex_map.get(&raw_index) {
+                    Some(index) => *index,
+                    None => {
+                        let index = vertices.len() as u32;
+                        let (vertex, inputs) = vertex_buffer.read_vertex(
+                            file,
+                            raw_index,
+                            skinning_mode,
+                            &node_list,
+                            bone_transforms,
+                        )?;
+                        vertices.push(vertex);
+                        shader_inputs.push(inputs);
+                        vertex_map.insert(raw_index, index);
+                        index
+                    }
                 };
+                indices.push(index);
+            }
 
-                if skinning_mode != SkinningMode::SmoothSkinning && vertex.node.len() > 0 {
-                    if vertex.weight.len() == 0 {
-                        vertex.weight.push(1.0);
-                    };
-                    //vertex.position = vector3_tranform(vertex.position, skeleton)
-                    debug!("TODO: some unimplemented tranform") //some transform
-                }
+            sub_meshes.push(SubMesh {
+                skinning_mode,
+                bone_palette: node_list,
+                indices: first_index..indices.len(),
+                vertices: first_vertex..vertices.len(),
+            });
+        }
 
-                vertices.push(vertex);
-                file.seek(SeekFrom::Start(file_position)).unwrap();
-            };
-        };
+        let indices = Indices::new(indices, vertices.len());
 
         debug!("TODO: in object.rs: bounding box");
 
         Ok(Object {
             vertices,
+            indices,
+            sub_meshes,
             material_id,
             render_priority,
             name,
@@ -313,6 +412,8 @@ impl Object {
             has_node,
             has_weight,
             tex_uv_count,
+            shader_uniforms,
+            shader_inputs,
         })
     }
 }
@@ -1,6 +1,7 @@
 use crate::deserialize::{read_f32_le, read_u16_le, read_u32_le, read_vector};
+use crate::Vector3;
 use std::io;
-use std::io::{Read, Seek, SeekFrom};
+use std::io::{Read, Seek};
 
 #[derive(Debug)]
 pub enum ObjectEntryError {
@@ -24,7 +25,7 @@ pub struct ObjectEntry {
     pub faces_header_entries: u32,
     pub vsh_extra_attributes_buffer_commands_offset: u32,
     pub vsh_extra_attributes_buffer_commands_word_counts: u32,
-    pub center_vector: [f32; 3],
+    pub center_vector: Vector3,
     pub flags_offset: u32,
     pub bounding_box_offset: u32,
 }
@@ -57,7 +58,7 @@ impl ObjectEntry {
 
         let mut center_vector = [0.0; 3];
 
-        read_vector(file, |f| read_f32_le(f), &mut center_vector)
+        read_vector(file, read_f32_le, &mut center_vector)
             .map_err(|e| ioe(e, "center vector"))?;
 
         let flags_offset = read_u32_le(file).map_err(|e| ioe(e, "flags offset"))?;
@@ -81,7 +82,7 @@ impl ObjectEntry {
             faces_header_entries,
             vsh_extra_attributes_buffer_commands_offset,
             vsh_extra_attributes_buffer_commands_word_counts,
-            center_vector,
+            center_vector: Vector3::from(center_vector),
             flags_offset,
             bounding_box_offset,
         })
@@ -0,0 +1,14 @@
+use crate::SkinningMode;
+use std::ops::Range;
+
+/// A face group of an object, drawn with its own bone palette
+#[derive(Debug, Clone, PartialEq)]
+pub struct SubMesh {
+    pub skinning_mode: SkinningMode,
+    /// the bones that the bone indices of the vertices refer to, by position in this list
+    pub bone_palette: Vec<u16>,
+    /// the range of `Object::indices` used by this face group
+    pub indices: Range<usize>,
+    /// the range of `Object::vertices` used by this face group
+    pub vertices: Range<usize>,
+}
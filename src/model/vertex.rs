@@ -8,5 +8,6 @@ pub struct Vertex {
     pub texture2: [f32; 2],
     pub node: Vec<i32>,
     pub weight: Vec<f32>,
+    /// color packed as 0xAARRGGBB
     pub diffuse_color: u32,
 }
@@ -1,23 +1,661 @@
+/// A register of the PICA200 GPU, written by a command. Registers that exist once per texture
+/// unit, texture combiner stage, light or attribute buffer take its index.
 #[derive(PartialEq, Clone, Copy, Debug)]
 pub enum PicaCommand {
+    // rasterizer
+    Finalize,
+    FaceCulling,
+    ViewportWidth,
+    ViewportInverseWidth,
+    ViewportHeight,
+    ViewportInverseHeight,
+    ClipEnable,
+    /// the coefficients of the clip plane, from 0 to 3
+    ClipData(u8),
+    DepthMapScale,
+    DepthMapOffset,
+    ShaderOutputTotal,
+    /// the semantic of the shader output registers, from 0 to 6
+    ShaderOutputMap(u8),
+    EarlyDepthFunction,
+    EarlyDepthTest1,
+    EarlyDepthClear,
+    ShaderOutputAttributeMode,
+    ScissorMode,
+    ScissorPosition,
+    ScissorDimension,
+    ViewportPosition,
+    EarlyDepthData,
+    DepthMapEnable,
+    RenderBufferDimension,
+    ShaderOutputAttributeClock,
+
+    // texture units
+    TextureUnitConfig,
+    /// the texture unit, from 0 to 2
+    TextureUnitBorderColor(u8),
+    TextureUnitDimension(u8),
+    TextureUnitParameters(u8),
+    TextureUnitLevelOfDetail(u8),
+    TextureUnitAddress(u8),
+    /// the address of the cube map faces other than the first, from 0 to 4
+    TextureUnit0CubeMapAddress(u8),
+    TextureUnit0Shadow,
+    TextureUnitType(u8),
+    LightingEnable0,
+    /// the configuration of the procedural texture, from 0 to 5
+    ProceduralTexture(u8),
+    ProceduralTextureLookUpTableIndex,
+    ProceduralTextureLookUpTableData,
+
+    // texture combiners
+    /// the texture combiner stage, from 0 to 5
+    TextureCombinerSource(u8),
+    TextureCombinerOperand(u8),
+    TextureCombinerCombiner(u8),
+    TextureCombinerColor(u8),
+    TextureCombinerScale(u8),
+    TextureCombinerBufferConfig,
+    FogColor,
+    GasAttenuation,
+    GasAccumulationMax,
+    FogLookUpTableIndex,
+    FogLookUpTableData,
+    TextureCombinerBufferColor,
+
+    // fragment operations
+    ColorOperation,
+    BlendFunction,
+    LogicOperation,
+    BlendColor,
+    AlphaTest,
+    StencilTest,
+    StencilOperation,
+    DepthColorMask,
+
+    // framebuffer
+    FramebufferInvalidate,
+    FramebufferFlush,
+    ColorBufferRead,
+    ColorBufferWrite,
+    DepthBufferRead,
+    DepthBufferWrite,
+    DepthBufferFormat,
+    ColorBufferFormat,
+    EarlyDepthTest2,
+    FramebufferBlock32,
+    DepthBufferLocation,
+    ColorBufferLocation,
+    FramebufferDimension,
+    GasLightXY,
+    GasLightZ,
+    GasLightZColor,
+    GasLookUpTableIndex,
+    GasLookUpTableData,
+    GasDeltaZDepth,
+    FragmentOperationShadow,
+
+    // lighting
+    /// the light, from 0 to 7
+    LightSpecular0(u8),
+    LightSpecular1(u8),
+    LightDiffuse(u8),
+    LightAmbient(u8),
+    LightPositionXY(u8),
+    LightPositionZ(u8),
+    LightSpotDirectionXY(u8),
+    LightSpotDirectionZ(u8),
+    LightConfig(u8),
+    LightAttenuationBias(u8),
+    LightAttenuationScale(u8),
+    LightingAmbient,
+    LightingLightCount,
+    LightingConfig0,
+    LightingConfig1,
+    FragmentShaderLookUpTableIndex,
+    LightingEnable1,
+    FragmentShaderLookUpTableData,
+    LightingLookUpTableInputAbsolute,
+    LightingLookUpTableInputSelect,
+    LightingLookUpTableInputScale,
+    LightingLightPermutation,
+
+    // geometry pipeline
+    AttributeBuffersLocation,
+    AttributeBuffersFormatLow,
+    AttributeBuffersFormatHigh,
+    /// the attribute buffer, from 0 to 11
+    AttributeBufferOffset(u8),
+    AttributeBufferConfig1(u8),
+    AttributeBufferConfig2(u8),
+    IndexBufferConfig,
+    VertexCount,
+    GeometryStageConfig,
+    VertexOffset,
+    PostVertexCacheCount,
+    DrawArrays,
+    DrawElements,
+    VertexFunction,
+    FixedAttributeIndex,
+    /// the packed value of the fixed attribute, from 0 to 2
+    FixedAttributeData(u8),
+    /// the command buffer channel, from 0 to 1
+    CommandBufferSize(u8),
+    CommandBufferAddress(u8),
+    CommandBufferJump0,
+    /// the jump to the command buffer channel 1, that ends the command buffers of BCH files
     BlockEnd,
+    VertexShaderInputCount,
+    CommandMode,
+    StartDrawFunction,
+    VertexShaderOutputTotal1,
+    VertexShaderOutputTotal2,
+    GeometryShaderMisc0,
+    GeometryStageConfig2,
+    GeometryShaderMisc1,
+    PrimitiveConfig,
+    RestartPrimitive,
+
+    // geometry shader
+    GeometryShaderBoolUniform,
+    /// the integer uniform, from 0 to 3
+    GeometryShaderIntegerUniform(u8),
+    GeometryShaderInputBufferConfig,
+    GeometryShaderEntryPoint,
+    GeometryShaderAttributesPermutationLow,
+    GeometryShaderAttributesPermutationHigh,
+    GeometryShaderOutputMapMask,
+    GeometryShaderCodeTransferEnd,
+    GeometryShaderFloatUniformConfig,
+    GeometryShaderFloatUniformData,
+    GeometryShaderCodeTransferIndex,
+    GeometryShaderCodeTransferData,
+    GeometryShaderOperandDescriptorIndex,
+    GeometryShaderOperandDescriptorData,
+
+    // vertex shader
+    VertexShaderBoolUniform,
+    /// the integer uniform, from 0 to 3
+    VertexShaderIntegerUniform(u8),
+    VertexShaderInputBufferConfig,
+    VertexShaderEntryPoint,
+    VertexShaderAttributesPermutationLow,
+    VertexShaderAttributesPermutationHigh,
+    VertexShaderOutputMapMask,
+    VertexShaderCodeTransferEnd,
     VertexShaderFloatUniformConfig,
     VertexShaderFloatUniformData,
-    FragmentShaderLookUpTableData,
+    VertexShaderCodeTransferIndex,
+    VertexShaderCodeTransferData,
+    VertexShaderOperandDescriptorIndex,
+    VertexShaderOperandDescriptorData,
+
     Unknown(u16),
 }
 
+const TEXTURE_UNIT_BASES: [u16; 3] = [0x81, 0x91, 0x99];
+const TEXTURE_COMBINER_BASES: [u16; 6] = [0xc0, 0xc8, 0xd0, 0xd8, 0xf0, 0xf8];
+
 impl PicaCommand {
     pub fn new_from_id(id: u16) -> PicaCommand {
         match id {
+            0x010 => PicaCommand::Finalize,
+            0x040 => PicaCommand::FaceCulling,
+            0x041 => PicaCommand::ViewportWidth,
+            0x042 => PicaCommand::ViewportInverseWidth,
+            0x043 => PicaCommand::ViewportHeight,
+            0x044 => PicaCommand::ViewportInverseHeight,
+            0x047 => PicaCommand::ClipEnable,
+            0x048..=0x04b => PicaCommand::ClipData((id - 0x048) as u8),
+            0x04d => PicaCommand::DepthMapScale,
+            0x04e => PicaCommand::DepthMapOffset,
+            0x04f => PicaCommand::ShaderOutputTotal,
+            0x050..=0x056 => PicaCommand::ShaderOutputMap((id - 0x050) as u8),
+            0x061 => PicaCommand::EarlyDepthFunction,
+            0x062 => PicaCommand::EarlyDepthTest1,
+            0x063 => PicaCommand::EarlyDepthClear,
+            0x064 => PicaCommand::ShaderOutputAttributeMode,
+            0x065 => PicaCommand::ScissorMode,
+            0x066 => PicaCommand::ScissorPosition,
+            0x067 => PicaCommand::ScissorDimension,
+            0x068 => PicaCommand::ViewportPosition,
+            0x06a => PicaCommand::EarlyDepthData,
+            0x06d => PicaCommand::DepthMapEnable,
+            0x06e => PicaCommand::RenderBufferDimension,
+            0x06f => PicaCommand::ShaderOutputAttributeClock,
+
+            0x080 => PicaCommand::TextureUnitConfig,
+            0x086..=0x08a => PicaCommand::TextureUnit0CubeMapAddress((id - 0x086) as u8),
+            0x08b => PicaCommand::TextureUnit0Shadow,
+            0x08e => PicaCommand::TextureUnitType(0),
+            0x08f => PicaCommand::LightingEnable0,
+            0x096 => PicaCommand::TextureUnitType(1),
+            0x09e => PicaCommand::TextureUnitType(2),
+            0x081..=0x085 | 0x091..=0x095 | 0x099..=0x09d => {
+                let unit = (if id < 0x91 { 0 } else if id < 0x99 { 1 } else { 2 }) as u8;
+                match id - TEXTURE_UNIT_BASES[unit as usize] {
+                    0 => PicaCommand::TextureUnitBorderColor(unit),
+                    1 => PicaCommand::TextureUnitDimension(unit),
+                    2 => PicaCommand::TextureUnitParameters(unit),
+                    3 => PicaCommand::TextureUnitLevelOfDetail(unit),
+                    _ => PicaCommand::TextureUnitAddress(unit),
+                }
+            }
+            0x0a8..=0x0ad => PicaCommand::ProceduralTexture((id - 0x0a8) as u8),
+            0x0af => PicaCommand::ProceduralTextureLookUpTableIndex,
+            0x0b0..=0x0b7 => PicaCommand::ProceduralTextureLookUpTableData,
+
+            0x0c0..=0x0c4 | 0x0c8..=0x0cc | 0x0d0..=0x0d4 | 0x0d8..=0x0dc | 0x0f0..=0x0f4
+            | 0x0f8..=0x0fc => {
+                let stage = match id {
+                    0x0c0..=0x0dc => (id - 0x0c0) / 8,
+                    _ => (id - 0x0f0) / 8 + 4,
+                } as u8;
+                match id - TEXTURE_COMBINER_BASES[stage as usize] {
+                    0 => PicaCommand::TextureCombinerSource(stage),
+                    1 => PicaCommand::TextureCombinerOperand(stage),
+                    2 => PicaCommand::TextureCombinerCombiner(stage),
+                    3 => PicaCommand::TextureCombinerColor(stage),
+                    _ => PicaCommand::TextureCombinerScale(stage),
+                }
+            }
+            0x0e0 => PicaCommand::TextureCombinerBufferConfig,
+            0x0e1 => PicaCommand::FogColor,
+            0x0e4 => PicaCommand::GasAttenuation,
+            0x0e5 => PicaCommand::GasAccumulationMax,
+            0x0e6 => PicaCommand::FogLookUpTableIndex,
+            0x0e8..=0x0ef => PicaCommand::FogLookUpTableData,
+            0x0fd => PicaCommand::TextureCombinerBufferColor,
+
+            0x100 => PicaCommand::ColorOperation,
+            0x101 => PicaCommand::BlendFunction,
+            0x102 => PicaCommand::LogicOperation,
+            0x103 => PicaCommand::BlendColor,
+            0x104 => PicaCommand::AlphaTest,
+            0x105 => PicaCommand::StencilTest,
+            0x106 => PicaCommand::StencilOperation,
+            0x107 => PicaCommand::DepthColorMask,
+
+            0x110 => PicaCommand::FramebufferInvalidate,
+            0x111 => PicaCommand::FramebufferFlush,
+            0x112 => PicaCommand::ColorBufferRead,
+            0x113 => PicaCommand::ColorBufferWrite,
+            0x114 => PicaCommand::DepthBufferRead,
+            0x115 => PicaCommand::DepthBufferWrite,
+            0x116 => PicaCommand::DepthBufferFormat,
+            0x117 => PicaCommand::ColorBufferFormat,
+            0x118 => PicaCommand::EarlyDepthTest2,
+            0x11b => PicaCommand::FramebufferBlock32,
+            0x11c => PicaCommand::DepthBufferLocation,
+            0x11d => PicaCommand::ColorBufferLocation,
+            0x11e => PicaCommand::FramebufferDimension,
+            0x120 => PicaCommand::GasLightXY,
+            0x121 => PicaCommand::GasLightZ,
+            0x122 => PicaCommand::GasLightZColor,
+            0x123 => PicaCommand::GasLookUpTableIndex,
+            0x124 => PicaCommand::GasLookUpTableData,
+            0x126 => PicaCommand::GasDeltaZDepth,
+            0x130 => PicaCommand::FragmentOperationShadow,
+
+            0x140..=0x1bf => {
+                let light = ((id - 0x140) / 0x10) as u8;
+                match (id - 0x140) % 0x10 {
+                    0x0 => PicaCommand::LightSpecular0(light),
+                    0x1 => PicaCommand::LightSpecular1(light),
+                    0x2 => PicaCommand::LightDiffuse(light),
+                    0x3 => PicaCommand::LightAmbient(light),
+                    0x4 => PicaCommand::LightPositionXY(light),
+                    0x5 => PicaCommand::LightPositionZ(light),
+                    0x6 => PicaCommand::LightSpotDirectionXY(light),
+                    0x7 => PicaCommand::LightSpotDirectionZ(light),
+                    0x9 => PicaCommand::LightConfig(light),
+                    0xa => PicaCommand::LightAttenuationBias(light),
+                    0xb => PicaCommand::LightAttenuationScale(light),
+                    _ => PicaCommand::Unknown(id),
+                }
+            }
+            0x1c0 => PicaCommand::LightingAmbient,
+            0x1c2 => PicaCommand::LightingLightCount,
+            0x1c3 => PicaCommand::LightingConfig0,
+            0x1c4 => PicaCommand::LightingConfig1,
+            0x1c5 => PicaCommand::FragmentShaderLookUpTableIndex,
+            0x1c6 => PicaCommand::LightingEnable1,
+            0x1c8..=0x1cf => PicaCommand::FragmentShaderLookUpTableData,
+            0x1d0 => PicaCommand::LightingLookUpTableInputAbsolute,
+            0x1d1 => PicaCommand::LightingLookUpTableInputSelect,
+            0x1d2 => PicaCommand::LightingLookUpTableInputScale,
+            0x1d9 => PicaCommand::LightingLightPermutation,
+
+            0x200 => PicaCommand::AttributeBuffersLocation,
+            0x201 => PicaCommand::AttributeBuffersFormatLow,
+            0x202 => PicaCommand::AttributeBuffersFormatHigh,
+            0x203..=0x226 => {
+                let buffer = ((id - 0x203) / 3) as u8;
+                match (id - 0x203) % 3 {
+                    0 => PicaCommand::AttributeBufferOffset(buffer),
+                    1 => PicaCommand::AttributeBufferConfig1(buffer),
+                    _ => PicaCommand::AttributeBufferConfig2(buffer),
+                }
+            }
+            0x227 => PicaCommand::IndexBufferConfig,
+            0x228 => PicaCommand::VertexCount,
+            0x229 => PicaCommand::GeometryStageConfig,
+            0x22a => PicaCommand::VertexOffset,
+            0x22d => PicaCommand::PostVertexCacheCount,
+            0x22e => PicaCommand::DrawArrays,
+            0x22f => PicaCommand::DrawElements,
+            0x231 => PicaCommand::VertexFunction,
+            0x232 => PicaCommand::FixedAttributeIndex,
+            0x233..=0x235 => PicaCommand::FixedAttributeData((id - 0x233) as u8),
+            0x238..=0x239 => PicaCommand::CommandBufferSize((id - 0x238) as u8),
+            0x23a..=0x23b => PicaCommand::CommandBufferAddress((id - 0x23a) as u8),
+            0x23c => PicaCommand::CommandBufferJump0,
             0x23d => PicaCommand::BlockEnd,
+            0x242 => PicaCommand::VertexShaderInputCount,
+            0x244 => PicaCommand::CommandMode,
+            0x245 => PicaCommand::StartDrawFunction,
+            0x24a => PicaCommand::VertexShaderOutputTotal1,
+            0x251 => PicaCommand::VertexShaderOutputTotal2,
+            0x252 => PicaCommand::GeometryShaderMisc0,
+            0x253 => PicaCommand::GeometryStageConfig2,
+            0x254 => PicaCommand::GeometryShaderMisc1,
+            0x25e => PicaCommand::PrimitiveConfig,
+            0x25f => PicaCommand::RestartPrimitive,
+
+            0x280 => PicaCommand::GeometryShaderBoolUniform,
+            0x281..=0x284 => PicaCommand::GeometryShaderIntegerUniform((id - 0x281) as u8),
+            0x289 => PicaCommand::GeometryShaderInputBufferConfig,
+            0x28a => PicaCommand::GeometryShaderEntryPoint,
+            0x28b => PicaCommand::GeometryShaderAttributesPermutationLow,
+            0x28c => PicaCommand::GeometryShaderAttributesPermutationHigh,
+            0x28d => PicaCommand::GeometryShaderOutputMapMask,
+            0x28f => PicaCommand::GeometryShaderCodeTransferEnd,
+            0x290 => PicaCommand::GeometryShaderFloatUniformConfig,
+            0x291..=0x298 => PicaCommand::GeometryShaderFloatUniformData,
+            0x29b => PicaCommand::GeometryShaderCodeTransferIndex,
+            0x29c..=0x2a3 => PicaCommand::GeometryShaderCodeTransferData,
+            0x2a5 => PicaCommand::GeometryShaderOperandDescriptorIndex,
+            0x2a6..=0x2ad => PicaCommand::GeometryShaderOperandDescriptorData,
+
+            0x2b0 => PicaCommand::VertexShaderBoolUniform,
+            0x2b1..=0x2b4 => PicaCommand::VertexShaderIntegerUniform((id - 0x2b1) as u8),
+            0x2b9 => PicaCommand::VertexShaderInputBufferConfig,
+            0x2ba => PicaCommand::VertexShaderEntryPoint,
+            0x2bb => PicaCommand::VertexShaderAttributesPermutationLow,
+            0x2bc => PicaCommand::VertexShaderAttributesPermutationHigh,
+            0x2bd => PicaCommand::VertexShaderOutputMapMask,
+            0x2bf => PicaCommand::VertexShaderCodeTransferEnd,
             0x2c0 => PicaCommand::VertexShaderFloatUniformConfig,
-            0x2c1 => PicaCommand::VertexShaderFloatUniformData,
-            0x1c8 => PicaCommand::FragmentShaderLookUpTableData,
+            0x2c1..=0x2c8 => PicaCommand::VertexShaderFloatUniformData,
+            0x2cb => PicaCommand::VertexShaderCodeTransferIndex,
+            0x2cc..=0x2d3 => PicaCommand::VertexShaderCodeTransferData,
+            0x2d5 => PicaCommand::VertexShaderOperandDescriptorIndex,
+            0x2d6..=0x2dd => PicaCommand::VertexShaderOperandDescriptorData,
 
             unk => PicaCommand::Unknown(unk),
         }
     }
+
+    /// return the id of the register. Registers that are written through several consecutive
+    /// ids, like look up table data, return the first one. Panic if the index of the register is
+    /// out of range.
+    pub fn get_id(&self) -> u16 {
+        match self {
+            PicaCommand::Finalize => 0x010,
+            PicaCommand::FaceCulling => 0x040,
+            PicaCommand::ViewportWidth => 0x041,
+            PicaCommand::ViewportInverseWidth => 0x042,
+            PicaCommand::ViewportHeight => 0x043,
+            PicaCommand::ViewportInverseHeight => 0x044,
+            PicaCommand::ClipEnable => 0x047,
+            PicaCommand::ClipData(index) => {
+                assert!(*index < 4);
+                0x048 + *index as u16
+            }
+            PicaCommand::DepthMapScale => 0x04d,
+            PicaCommand::DepthMapOffset => 0x04e,
+            PicaCommand::ShaderOutputTotal => 0x04f,
+            PicaCommand::ShaderOutputMap(index) => {
+                assert!(*index < 7);
+                0x050 + *index as u16
+            }
+            PicaCommand::EarlyDepthFunction => 0x061,
+            PicaCommand::EarlyDepthTest1 => 0x062,
+            PicaCommand::EarlyDepthClear => 0x063,
+            PicaCommand::ShaderOutputAttributeMode => 0x064,
+            PicaCommand::ScissorMode => 0x065,
+            PicaCommand::ScissorPosition => 0x066,
+            PicaCommand::ScissorDimension => 0x067,
+            PicaCommand::ViewportPosition => 0x068,
+            PicaCommand::EarlyDepthData => 0x06a,
+            PicaCommand::DepthMapEnable => 0x06d,
+            PicaCommand::RenderBufferDimension => 0x06e,
+            PicaCommand::ShaderOutputAttributeClock => 0x06f,
+
+            PicaCommand::TextureUnitConfig => 0x080,
+            PicaCommand::TextureUnitBorderColor(unit) => TEXTURE_UNIT_BASES[*unit as usize],
+            PicaCommand::TextureUnitDimension(unit) => TEXTURE_UNIT_BASES[*unit as usize] + 1,
+            PicaCommand::TextureUnitParameters(unit) => TEXTURE_UNIT_BASES[*unit as usize] + 2,
+            PicaCommand::TextureUnitLevelOfDetail(unit) => TEXTURE_UNIT_BASES[*unit as usize] + 3,
+            PicaCommand::TextureUnitAddress(unit) => TEXTURE_UNIT_BASES[*unit as usize] + 4,
+            PicaCommand::TextureUnit0CubeMapAddress(face) => {
+                assert!(*face < 5);
+                0x086 + *face as u16
+            }
+            PicaCommand::TextureUnit0Shadow => 0x08b,
+            PicaCommand::TextureUnitType(unit) => [0x08e, 0x096, 0x09e][*unit as usize],
+            PicaCommand::LightingEnable0 => 0x08f,
+            PicaCommand::ProceduralTexture(index) => {
+                assert!(*index < 6);
+                0x0a8 + *index as u16
+            }
+            PicaCommand::ProceduralTextureLookUpTableIndex => 0x0af,
+            PicaCommand::ProceduralTextureLookUpTableData => 0x0b0,
+
+            PicaCommand::TextureCombinerSource(stage) => TEXTURE_COMBINER_BASES[*stage as usize],
+            PicaCommand::TextureCombinerOperand(stage) => {
+                TEXTURE_COMBINER_BASES[*stage as usize] + 1
+            }
+            PicaCommand::TextureCombinerCombiner(stage) => {
+                TEXTURE_COMBINER_BASES[*stage as usize] + 2
+            }
+            PicaCommand::TextureCombinerColor(stage) => TEXTURE_COMBINER_BASES[*stage as usize] + 3,
+            PicaCommand::TextureCombinerScale(stage) => TEXTURE_COMBINER_BASES[*stage as usize] + 4,
+            PicaCommand::TextureCombinerBufferConfig => 0x0e0,
+            PicaCommand::FogColor => 0x0e1,
+            PicaCommand::GasAttenuation => 0x0e4,
+            PicaCommand::GasAccumulationMax => 0x0e5,
+            PicaCommand::FogLookUpTableIndex => 0x0e6,
+            PicaCommand::FogLookUpTableData => 0x0e8,
+            PicaCommand::TextureCombinerBufferColor => 0x0fd,
+
+            PicaCommand::ColorOperation => 0x100,
+            PicaCommand::BlendFunction => 0x101,
+            PicaCommand::LogicOperation => 0x102,
+            PicaCommand::BlendColor => 0x103,
+            PicaCommand::AlphaTest => 0x104,
+            PicaCommand::StencilTest => 0x105,
+            PicaCommand::StencilOperation => 0x106,
+            PicaCommand::DepthColorMask => 0x107,
+
+            PicaCommand::FramebufferInvalidate => 0x110,
+            PicaCommand::FramebufferFlush => 0x111,
+            PicaCommand::ColorBufferRead => 0x112,
+            PicaCommand::ColorBufferWrite => 0x113,
+            PicaCommand::DepthBufferRead => 0x114,
+            PicaCommand::DepthBufferWrite => 0x115,
+            PicaCommand::DepthBufferFormat => 0x116,
+            PicaCommand::ColorBufferFormat => 0x117,
+            PicaCommand::EarlyDepthTest2 => 0x118,
+            PicaCommand::FramebufferBlock32 => 0x11b,
+            PicaCommand::DepthBufferLocation => 0x11c,
+            PicaCommand::ColorBufferLocation => 0x11d,
+            PicaCommand::FramebufferDimension => 0x11e,
+            PicaCommand::GasLightXY => 0x120,
+            PicaCommand::GasLightZ => 0x121,
+            PicaCommand::GasLightZColor => 0x122,
+            PicaCommand::GasLookUpTableIndex => 0x123,
+            PicaCommand::GasLookUpTableData => 0x124,
+            PicaCommand::GasDeltaZDepth => 0x126,
+            PicaCommand::FragmentOperationShadow => 0x130,
+
+            PicaCommand::LightSpecular0(light) => get_light_id(*light, 0x0),
+            PicaCommand::LightSpecular1(light) => get_light_id(*light, 0x1),
+            PicaCommand::LightDiffuse(light) => get_light_id(*light, 0x2),
+            PicaCommand::LightAmbient(light) => get_light_id(*light, 0x3),
+            PicaCommand::LightPositionXY(light) => get_light_id(*light, 0x4),
+            PicaCommand::LightPositionZ(light) => get_light_id(*light, 0x5),
+            PicaCommand::LightSpotDirectionXY(light) => get_light_id(*light, 0x6),
+            PicaCommand::LightSpotDirectionZ(light) => get_light_id(*light, 0x7),
+            PicaCommand::LightConfig(light) => get_light_id(*light, 0x9),
+            PicaCommand::LightAttenuationBias(light) => get_light_id(*light, 0xa),
+            PicaCommand::LightAttenuationScale(light) => get_light_id(*light, 0xb),
+            PicaCommand::LightingAmbient => 0x1c0,
+            PicaCommand::LightingLightCount => 0x1c2,
+            PicaCommand::LightingConfig0 => 0x1c3,
+            PicaCommand::LightingConfig1 => 0x1c4,
+            PicaCommand::FragmentShaderLookUpTableIndex => 0x1c5,
+            PicaCommand::LightingEnable1 => 0x1c6,
+            PicaCommand::FragmentShaderLookUpTableData => 0x1c8,
+            PicaCommand::LightingLookUpTableInputAbsolute => 0x1d0,
+            PicaCommand::LightingLookUpTableInputSelect => 0x1d1,
+            PicaCommand::LightingLookUpTableInputScale => 0x1d2,
+            PicaCommand::LightingLightPermutation => 0x1d9,
+
+            PicaCommand::AttributeBuffersLocation => 0x200,
+            PicaCommand::AttributeBuffersFormatLow => 0x201,
+            PicaCommand::AttributeBuffersFormatHigh => 0x202,
+            PicaCommand::AttributeBufferOffset(buffer) => get_attribute_buffer_id(*buffer, 0),
+            PicaCommand::AttributeBufferConfig1(buffer) => get_attribute_buffer_id(*buffer, 1),
+            PicaCommand::AttributeBufferConfig2(buffer) => get_attribute_buffer_id(*buffer, 2),
+            PicaCommand::IndexBufferConfig => 0x227,
+            PicaCommand::VertexCount => 0x228,
+            PicaCommand::GeometryStageConfig => 0x229,
+            PicaCommand::VertexOffset => 0x22a,
+            PicaCommand::PostVertexCacheCount => 0x22d,
+            PicaCommand::DrawArrays => 0x22e,
+            PicaCommand::DrawElements => 0x22f,
+            PicaCommand::VertexFunction => 0x231,
+            PicaCommand::FixedAttributeIndex => 0x232,
+            PicaCommand::FixedAttributeData(index) => {
+                assert!(*index < 3);
+                0x233 + *index as u16
+            }
+            PicaCommand::CommandBufferSize(channel) => {
+                assert!(*channel < 2);
+                0x238 + *channel as u16
+            }
+            PicaCommand::CommandBufferAddress(channel) => {
+                assert!(*channel < 2);
+                0x23a + *channel as u16
+            }
+            PicaCommand::CommandBufferJump0 => 0x23c,
+            PicaCommand::BlockEnd => 0x23d,
+            PicaCommand::VertexShaderInputCount => 0x242,
+            PicaCommand::CommandMode => 0x244,
+            PicaCommand::StartDrawFunction => 0x245,
+            PicaCommand::VertexShaderOutputTotal1 => 0x24a,
+            PicaCommand::VertexShaderOutputTotal2 => 0x251,
+            PicaCommand::GeometryShaderMisc0 => 0x252,
+            PicaCommand::GeometryStageConfig2 => 0x253,
+            PicaCommand::GeometryShaderMisc1 => 0x254,
+            PicaCommand::PrimitiveConfig => 0x25e,
+            PicaCommand::RestartPrimitive => 0x25f,
+
+            PicaCommand::GeometryShaderBoolUniform => 0x280,
+            PicaCommand::GeometryShaderIntegerUniform(index) => {
+                assert!(*index < 4);
+                0x281 + *index as u16
+            }
+            PicaCommand::GeometryShaderInputBufferConfig => 0x289,
+            PicaCommand::GeometryShaderEntryPoint => 0x28a,
+            PicaCommand::GeometryShaderAttributesPermutationLow => 0x28b,
+            PicaCommand::GeometryShaderAttributesPermutationHigh => 0x28c,
+            PicaCommand::GeometryShaderOutputMapMask => 0x28d,
+            PicaCommand::GeometryShaderCodeTransferEnd => 0x28f,
+            PicaCommand::GeometryShaderFloatUniformConfig => 0x290,
+            PicaCommand::GeometryShaderFloatUniformData => 0x291,
+            PicaCommand::GeometryShaderCodeTransferIndex => 0x29b,
+            PicaCommand::GeometryShaderCodeTransferData => 0x29c,
+            PicaCommand::GeometryShaderOperandDescriptorIndex => 0x2a5,
+            PicaCommand::GeometryShaderOperandDescriptorData => 0x2a6,
+
+            PicaCommand::VertexShaderBoolUniform => 0x2b0,
+            PicaCommand::VertexShaderIntegerUniform(index) => {
+                assert!(*index < 4);
+                0x2b1 + *index as u16
+            }
+            PicaCommand::VertexShaderInputBufferConfig => 0x2b9,
+            PicaCommand::VertexShaderEntryPoint => 0x2ba,
+            PicaCommand::VertexShaderAttributesPermutationLow => 0x2bb,
+            PicaCommand::VertexShaderAttributesPermutationHigh => 0x2bc,
+            PicaCommand::VertexShaderOutputMapMask => 0x2bd,
+            PicaCommand::VertexShaderCodeTransferEnd => 0x2bf,
+            PicaCommand::VertexShaderFloatUniformConfig => 0x2c0,
+            PicaCommand::VertexShaderFloatUniformData => 0x2c1,
+            PicaCommand::VertexShaderCodeTransferIndex => 0x2cb,
+            PicaCommand::VertexShaderCodeTransferData => 0x2cc,
+            PicaCommand::VertexShaderOperandDescriptorIndex => 0x2d5,
+            PicaCommand::VertexShaderOperandDescriptorData => 0x2d6,
+
+            PicaCommand::Unknown(id) => *id,
+        }
+    }
+}
+
+fn get_light_id(light: u8, offset: u16) -> u16 {
+    assert!(light < 8);
+    0x140 + light as u16 * 0x10 + offset
+}
+
+fn get_attribute_buffer_id(buffer: u8, offset: u16) -> u16 {
+    assert!(buffer < 12);
+    0x203 + buffer as u16 * 3 + offset
+}
+
+/// The fragment lighting look up table written by the look up table data commands
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub enum LookUpTableSelect {
+    Distribution0,
+    Distribution1,
+    Fresnel,
+    ReflectanceB,
+    ReflectanceG,
+    ReflectanceR,
+    /// the spotlight attenuation of a light, from 0 to 7
+    Spotlight(u8),
+    /// the distance attenuation of a light, from 0 to 7
+    DistanceAttenuation(u8),
+}
+
+impl LookUpTableSelect {
+    pub fn new(value: u8) -> Option<LookUpTableSelect> {
+        Some(match value {
+            0 => Self::Distribution0,
+            1 => Self::Distribution1,
+            3 => Self::Fresnel,
+            4 => Self::ReflectanceB,
+            5 => Self::ReflectanceG,
+            6 => Self::ReflectanceR,
+            8..=15 => Self::Spotlight(value - 8),
+            16..=23 => Self::DistanceAttenuation(value - 16),
+            _ => return None,
+        })
+    }
+
+    /// return the value of the select field of the look up table index command
+    pub fn get_value(&self) -> u8 {
+        match self {
+            Self::Distribution0 => 0,
+            Self::Distribution1 => 1,
+            Self::Fresnel => 3,
+            Self::ReflectanceB => 4,
+            Self::ReflectanceG => 5,
+            Self::ReflectanceR => 6,
+            Self::Spotlight(light) => 8 + light,
+            Self::DistanceAttenuation(light) => 16 + light,
+        }
+    }
 }
 
 #[derive(PartialEq, Clone, Copy, Debug)]
@@ -130,3 +768,214 @@ pub struct AttributeFormat {
     pub r#type: AttributeFormatType,
     pub attribute_length: u32,
 }
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TestFunction {
+    Never,
+    Always,
+    Equal,
+    NotEqual,
+    Less,
+    LessEqual,
+    Greater,
+    GreaterEqual,
+}
+
+impl TestFunction {
+    pub fn new(nb: u8) -> Option<TestFunction> {
+        Some(match nb {
+            0 => Self::Never,
+            1 => Self::Always,
+            2 => Self::Equal,
+            3 => Self::NotEqual,
+            4 => Self::Less,
+            5 => Self::LessEqual,
+            6 => Self::Greater,
+            7 => Self::GreaterEqual,
+            _ => return None,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BlendEquation {
+    Add,
+    Subtract,
+    ReverseSubtract,
+    Min,
+    Max,
+}
+
+impl BlendEquation {
+    pub fn new(nb: u8) -> Option<BlendEquation> {
+        Some(match nb {
+            0 => Self::Add,
+            1 => Self::Subtract,
+            2 => Self::ReverseSubtract,
+            3 => Self::Min,
+            4 => Self::Max,
+            _ => return None,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BlendFactor {
+    Zero,
+    One,
+    SourceColor,
+    OneMinusSourceColor,
+    DestinationColor,
+    OneMinusDestinationColor,
+    SourceAlpha,
+    OneMinusSourceAlpha,
+    DestinationAlpha,
+    OneMinusDestinationAlpha,
+    ConstantColor,
+    OneMinusConstantColor,
+    ConstantAlpha,
+    OneMinusConstantAlpha,
+    SourceAlphaSaturate,
+}
+
+impl BlendFactor {
+    pub fn new(nb: u8) -> Option<BlendFactor> {
+        Some(match nb {
+            0 => Self::Zero,
+            1 => Self::One,
+            2 => Self::SourceColor,
+            3 => Self::OneMinusSourceColor,
+            4 => Self::DestinationColor,
+            5 => Self::OneMinusDestinationColor,
+            6 => Self::SourceAlpha,
+            7 => Self::OneMinusSourceAlpha,
+            8 => Self::DestinationAlpha,
+            9 => Self::OneMinusDestinationAlpha,
+            10 => Self::ConstantColor,
+            11 => Self::OneMinusConstantColor,
+            12 => Self::ConstantAlpha,
+            13 => Self::OneMinusConstantAlpha,
+            14 => Self::SourceAlphaSaturate,
+            _ => return None,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum StencilOperation {
+    Keep,
+    Zero,
+    Replace,
+    Increment,
+    Decrement,
+    Invert,
+    IncrementWrap,
+    DecrementWrap,
+}
+
+impl StencilOperation {
+    pub fn new(nb: u8) -> Option<StencilOperation> {
+        Some(match nb {
+            0 => Self::Keep,
+            1 => Self::Zero,
+            2 => Self::Replace,
+            3 => Self::Increment,
+            4 => Self::Decrement,
+            5 => Self::Invert,
+            6 => Self::IncrementWrap,
+            7 => Self::DecrementWrap,
+            _ => return None,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BlendFunction {
+    pub blend_enabled: bool,
+    pub color_equation: BlendEquation,
+    pub alpha_equation: BlendEquation,
+    pub color_source: BlendFactor,
+    pub color_destination: BlendFactor,
+    pub alpha_source: BlendFactor,
+    pub alpha_destination: BlendFactor,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AlphaTest {
+    pub enabled: bool,
+    pub function: TestFunction,
+    pub reference: u8,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StencilTest {
+    pub enabled: bool,
+    pub function: TestFunction,
+    pub buffer_mask: u8,
+    pub reference: u8,
+    pub mask: u8,
+    pub fail_operation: StencilOperation,
+    pub z_fail_operation: StencilOperation,
+    pub z_pass_operation: StencilOperation,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DepthOperation {
+    pub test_enabled: bool,
+    pub function: TestFunction,
+    pub write_red: bool,
+    pub write_green: bool,
+    pub write_blue: bool,
+    pub write_alpha: bool,
+    pub write_depth: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TextureFormat {
+    Rgba8,
+    Rgb8,
+    Rgba5551,
+    Rgb565,
+    Rgba4,
+    La8,
+    HiLo8,
+    L8,
+    A8,
+    La4,
+    L4,
+    A4,
+    Etc1,
+    Etc1A4,
+}
+
+impl TextureFormat {
+    pub fn new(nb: u8) -> Option<TextureFormat> {
+        Some(match nb {
+            0 => Self::Rgba8,
+            1 => Self::Rgb8,
+            2 => Self::Rgba5551,
+            3 => Self::Rgb565,
+            4 => Self::Rgba4,
+            5 => Self::La8,
+            6 => Self::HiLo8,
+            7 => Self::L8,
+            8 => Self::A8,
+            9 => Self::La4,
+            10 => Self::L4,
+            11 => Self::A4,
+            12 => Self::Etc1,
+            13 => Self::Etc1A4,
+            _ => return None,
+        })
+    }
+
+    pub fn get_bits_per_pixel(&self) -> u32 {
+        match self {
+            Self::Rgba8 => 32,
+            Self::Rgb8 => 24,
+            Self::Rgba5551 | Self::Rgb565 | Self::Rgba4 | Self::La8 | Self::HiLo8 => 16,
+            Self::L8 | Self::A8 | Self::La4 | Self::Etc1A4 => 8,
+            Self::L4 | Self::A4 | Self::Etc1 => 4,
+        }
+    }
+}
@@ -1,5 +1,19 @@
 use crate::deserialize::read_u32_le;
 use crate::{PicaCommand, IndexBufferFormat, VSHAttribute, AttributeFormat, AttributeFormatType};
+use crate::LookUpTableSelect;
+use crate::model::{TextureMagFilter, TextureMinFilter, TextureWrap};
+use crate::shader::float24_to_f32;
+use crate::{pica_fixed_to_f32, pica_float_to_f32, rgba8_to_array};
+use crate::{BumpMode, FragmentLightConfig, FresnelSelector, LightingConfig};
+use crate::{LookUpTableInput, LookUpTableInputType};
+use crate::{ColorBufferFormat, DepthBufferFormat, Framebuffer};
+use crate::{ColorOperation, CullMode, DepthMap, FragmentOperationMode, LogicOperation};
+use crate::{FogMode, TextureCombiner, TextureCombinerAlphaOperand, TextureCombinerBufferConfig};
+use crate::{TextureCombinerColorOperand, TextureCombinerMode, TextureCombinerSource};
+use crate::{PrimitiveMode, Scissor, ScissorMode, ShaderOutputSemantic, ShaderSetup, ShaderUnit, Viewport};
+use crate::{TextureLevelOfDetail, TextureType, TextureUnit, TextureUnitConfig, TextureUnitParameters};
+use crate::{AlphaTest, BlendEquation, BlendFactor, BlendFunction, DepthOperation};
+use crate::{StencilOperation, StencilTest, TestFunction, TextureFormat};
 use std::fmt;
 use std::io;
 use std::io::{Read, Seek, SeekFrom};
@@ -12,10 +26,47 @@ pub enum PICACommandReaderError {
 
 pub struct PICACommandReader {
     pub commands: [u32; 0x10000],
-    pub lookup_table: [f32; 256],
+    /// the fragment lighting look up tables, indexed by the value of their `LookUpTableSelect`.
+    /// Tables that aren't written are `None`.
+    pub lookup_tables: [Option<[f32; 256]>; 24],
+    /// the values written to the vertex shader float uniforms, by first register, in the order
+    /// they are uploaded (w, z, y then x for each register). Float24 values are converted.
     pub float_uniform: Vec<Vec<f32>>,
 }
 
+/// store a look up table entry, and move to the next one
+fn write_lookup_table(
+    lookup_tables: &mut [Option<[f32; 256]>],
+    lut_select: usize,
+    lut_index: &mut usize,
+    value: u32,
+) {
+    if let Some(table) = lookup_tables.get_mut(lut_select) {
+        // only the 12 lowest bits are used, as a fixed point value between 0 and 1
+        table.get_or_insert([0.0; 256])[*lut_index] = (value & 0xfff) as f32 / 0xfff as f32;
+    };
+    *lut_index = (*lut_index + 1) & 0xff;
+}
+
+/// convert the words written to the float uniform data register, and remove them from `words`.
+/// In float24 mode, a vector is packed in three words and an incomplete vector is kept for the
+/// next command.
+fn decode_float_uniform(values: &mut Vec<f32>, words: &mut Vec<u32>, float24: bool) {
+    if !float24 {
+        values.extend(words.drain(..).map(f32::from_bits));
+        return;
+    };
+    let complete = words.len() - words.len() % 3;
+    for vector in words[..complete].chunks_exact(3) {
+        // the components are packed in the w, z, y, x order, like in f32 mode
+        values.push(float24_to_f32(vector[0] >> 8));
+        values.push(float24_to_f32((vector[0] & 0xff) << 16 | vector[1] >> 16));
+        values.push(float24_to_f32((vector[1] & 0xffff) << 8 | vector[2] >> 24));
+        values.push(float24_to_f32(vector[2] & 0xffffff));
+    }
+    words.drain(..complete);
+}
+
 impl fmt::Debug for PICACommandReader {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("PICACommandReader").finish()
@@ -30,8 +81,10 @@ impl PICACommandReader {
         let mut commands = [0; 0x10000];
         let mut word_read: u64 = 0;
         let mut current_uniform = None;
-        let mut uniform: Vec<f32> = Vec::new();
-        let mut lookup_table = [0.0; 256];
+        let mut uniform: Vec<u32> = Vec::new();
+        let mut uniform_float24 = false;
+        let mut lookup_tables = [None; 24];
+        let mut lut_select = 0;
         let mut lut_index = 0;
         let mut float_uniform = vec![Vec::new(); 96];
 
@@ -53,15 +106,21 @@ impl PICACommandReader {
             match command {
                 PicaCommand::BlockEnd => break,
                 PicaCommand::VertexShaderFloatUniformConfig => {
-                    current_uniform = Some(parameter & 0x7fffffff)
-                }
-                PicaCommand::VertexShaderFloatUniformData => {
-                    uniform.push(f32::from_le_bytes(commands[id as usize].to_le_bytes()))
+                    current_uniform = Some(parameter & 0x7fffffff);
+                    uniform_float24 = parameter >> 31 == 0;
+                    uniform.clear();
                 }
-                PicaCommand::FragmentShaderLookUpTableData => {
-                    //lookup_table[lut_index++] = commands[id as usize]; //TODO: lut_index++
-                    todo!("fragment shader");
+                PicaCommand::VertexShaderFloatUniformData => uniform.push(commands[id as usize]),
+                PicaCommand::FragmentShaderLookUpTableIndex => {
+                    lut_index = (commands[id as usize] & 0xff) as usize;
+                    lut_select = ((commands[id as usize] >> 8) & 0x1f) as usize;
                 }
+                PicaCommand::FragmentShaderLookUpTableData => write_lookup_table(
+                    &mut lookup_tables,
+                    lut_select,
+                    &mut lut_index,
+                    commands[id as usize],
+                ),
                 _ => (),
             }
 
@@ -75,13 +134,16 @@ impl PICACommandReader {
                         & (0xfffffff0 | mask));
                 word_read += 1;
 
-                if (id > 0x2c0) && (id < (0x2c1 + 8)) {
-                    uniform.push(f32::from_le_bytes(commands[id as usize].to_le_bytes()))
-                } else if PicaCommand::new_from_id(id) == PicaCommand::FragmentShaderLookUpTableData
-                {
-                    //lookUpTable[lutIndex++] = commands[id]
-                    todo!("look up fragment 2")
-                };
+                match PicaCommand::new_from_id(id) {
+                    PicaCommand::VertexShaderFloatUniformData => uniform.push(commands[id as usize]),
+                    PicaCommand::FragmentShaderLookUpTableData => write_lookup_table(
+                        &mut lookup_tables,
+                        lut_select,
+                        &mut lut_index,
+                        commands[id as usize],
+                    ),
+                    _ => (),
+                }
             }
 
             if !uniform.is_empty() {
@@ -89,13 +151,15 @@ impl PICACommandReader {
                     None => return Err(PICACommandReaderError::UndefinedCurrentUniform),
                     Some(current_uniform) => {
                         let current_uniform = current_uniform as usize;
-                        float_uniform[current_uniform].append(&mut uniform);
+                        decode_float_uniform(
+                            &mut float_uniform[current_uniform],
+                            &mut uniform,
+                            uniform_float24,
+                        );
                     }
                 }
             };
 
-            lut_index = 0;
-
             while (file
                 .seek(SeekFrom::Current(0))
                 .map_err(|e| PICACommandReaderError::IOError(e, "telling position for padding"))?
@@ -108,17 +172,29 @@ impl PICACommandReader {
 
         Ok(PICACommandReader {
             commands,
-            lookup_table,
+            lookup_tables,
             float_uniform,
         })
     }
 
+    /// return the fragment lighting look up table written by the commands, if any
+    pub fn get_lookup_table(&self, select: LookUpTableSelect) -> Option<&[f32; 256]> {
+        self.lookup_tables
+            .get(select.get_value() as usize)
+            .and_then(|table| table.as_ref())
+    }
+
+    /// return the value last written in the register
+    pub fn get(&self, command: PicaCommand) -> u32 {
+        self.commands[command.get_id() as usize]
+    }
+
     pub fn get_index_buffer_address(&self) -> u32{
-        self.commands[0x227] & 0x7fffffff
+        self.get(PicaCommand::IndexBufferConfig) & 0x7fffffff
     }
 
     pub fn get_index_buffer_format(&self) -> IndexBufferFormat {
-        if self.commands[0x227] >> 31 == 0 {
+        if self.get(PicaCommand::IndexBufferConfig) >> 31 == 0 {
             IndexBufferFormat::U8
         } else {
             IndexBufferFormat::U16
@@ -126,24 +202,24 @@ impl PICACommandReader {
     }
 
     pub fn get_index_buffer_total_vertices(&self) -> u32 {
-        self.commands[0x228]
+        self.get(PicaCommand::VertexCount)
     }
 
     pub fn get_vsh_attributes_buffer_offset(&self, nb: usize) -> u32 {
-        self.commands[0x203 + nb * 3]
+        self.get(PicaCommand::AttributeBufferOffset(nb as u8))
     }
 
     pub fn get_vsh_attributes_buffer_stride(&self, nb: usize) -> u8 {
-        (self.commands[0x205 + nb * 3] >> 16 & 0xFF) as u8
+        (self.get(PicaCommand::AttributeBufferConfig2(nb as u8)) >> 16 & 0xFF) as u8
     }
 
     pub fn get_vsh_total_attributes(&self, nb: usize) -> u32 {
-        self.commands[0x205 + nb *3] >> 28
+        self.get(PicaCommand::AttributeBufferConfig2(nb as u8)) >> 28
     }
 
     pub fn get_vsh_attributes_buffer_permutation_none(&self) -> Vec<VSHAttribute> {
-        let mut permutation: u64 = self.commands[0x2bb] as u64;
-        permutation |= (self.commands[0x2bc] as u64) << 32;
+        let mut permutation: u64 = self.get(PicaCommand::VertexShaderAttributesPermutationLow) as u64;
+        permutation |= (self.get(PicaCommand::VertexShaderAttributesPermutationHigh) as u64) << 32;
 
         let mut attributes = Vec::new();
         for attribute in 0..16 { //TODO: 16 is 23 in the original
@@ -154,8 +230,8 @@ impl PICACommandReader {
     }
 
     pub fn get_vsh_attributes_buffer_permutation(&self, nb: usize) -> Vec<u8> {
-        let mut permutation: u64 = self.commands[0x204 + nb * 3] as u64;
-        permutation |= ((self.commands[0x205 + nb * 3] & 0xffff) as u64) << 32;
+        let mut permutation: u64 = self.get(PicaCommand::AttributeBufferConfig1(nb as u8)) as u64;
+        permutation |= ((self.get(PicaCommand::AttributeBufferConfig2(nb as u8)) & 0xffff) as u64) << 32;
 
         let mut attributes = Vec::new();
         for attribute in 0..16 { //TODO: 16 is 23 in the original
@@ -166,8 +242,8 @@ impl PICACommandReader {
     }
 
     pub fn get_vsh_attributes_buffer_format(&self) -> Vec<AttributeFormat>{
-        let mut permutation: u64 = self.commands[0x201] as u64;
-        permutation |= (self.commands[0x202] as u64) << 32;
+        let mut permutation: u64 = self.get(PicaCommand::AttributeBuffersFormatLow) as u64;
+        permutation |= (self.get(PicaCommand::AttributeBuffersFormatHigh) as u64) << 32;
 
         let mut formats = Vec::new();
         for attribute in 0..16 {  //TODO: 16 is 23 in the original
@@ -182,4 +258,509 @@ impl PICACommandReader {
 
 
     }
+
+    pub fn get_texture_unit0_address(&self) -> u32 {
+        self.get(PicaCommand::TextureUnitAddress(0))
+    }
+
+    /// return the width and the height of the texture bound to the texture unit 0
+    pub fn get_texture_unit0_size(&self) -> (u32, u32) {
+        let dimension = self.get(PicaCommand::TextureUnitDimension(0));
+        (dimension >> 16, dimension & 0xffff)
+    }
+
+    pub fn get_texture_unit0_format(&self) -> Option<TextureFormat> {
+        TextureFormat::new((self.get(PicaCommand::TextureUnitType(0)) & 0xf) as u8)
+    }
+
+    pub fn get_blend_function(&self) -> Option<BlendFunction> {
+        let blend_function = self.get(PicaCommand::BlendFunction);
+        Some(BlendFunction {
+            blend_enabled: (self.get(PicaCommand::ColorOperation) >> 8) & 1 == 1,
+            color_equation: BlendEquation::new((blend_function & 0x7) as u8)?,
+            alpha_equation: BlendEquation::new((blend_function >> 8 & 0x7) as u8)?,
+            color_source: BlendFactor::new((blend_function >> 16 & 0xf) as u8)?,
+            color_destination: BlendFactor::new((blend_function >> 20 & 0xf) as u8)?,
+            alpha_source: BlendFactor::new((blend_function >> 24 & 0xf) as u8)?,
+            alpha_destination: BlendFactor::new((blend_function >> 28) as u8)?,
+        })
+    }
+
+    pub fn get_alpha_test(&self) -> Option<AlphaTest> {
+        let alpha_test = self.get(PicaCommand::AlphaTest);
+        Some(AlphaTest {
+            enabled: alpha_test & 1 == 1,
+            function: TestFunction::new((alpha_test >> 4 & 0x7) as u8)?,
+            reference: (alpha_test >> 8 & 0xff) as u8,
+        })
+    }
+
+    pub fn get_stencil_test(&self) -> Option<StencilTest> {
+        let stencil_test = self.get(PicaCommand::StencilTest);
+        let stencil_operation = self.get(PicaCommand::StencilOperation);
+        Some(StencilTest {
+            enabled: stencil_test & 1 == 1,
+            function: TestFunction::new((stencil_test >> 4 & 0x7) as u8)?,
+            buffer_mask: (stencil_test >> 8 & 0xff) as u8,
+            reference: (stencil_test >> 16 & 0xff) as u8,
+            mask: (stencil_test >> 24) as u8,
+            fail_operation: StencilOperation::new((stencil_operation & 0x7) as u8)?,
+            z_fail_operation: StencilOperation::new((stencil_operation >> 4 & 0x7) as u8)?,
+            z_pass_operation: StencilOperation::new((stencil_operation >> 8 & 0x7) as u8)?,
+        })
+    }
+
+    pub fn get_depth_operation(&self) -> Option<DepthOperation> {
+        let depth_operation = self.get(PicaCommand::DepthColorMask);
+        Some(DepthOperation {
+            test_enabled: depth_operation & 1 == 1,
+            function: TestFunction::new((depth_operation >> 4 & 0x7) as u8)?,
+            write_red: depth_operation >> 8 & 1 == 1,
+            write_green: depth_operation >> 9 & 1 == 1,
+            write_blue: depth_operation >> 10 & 1 == 1,
+            write_alpha: depth_operation >> 11 & 1 == 1,
+            write_depth: depth_operation >> 12 & 1 == 1,
+        })
+    }
+
+    pub fn get_cull_mode(&self) -> Option<CullMode> {
+        CullMode::new((self.get(PicaCommand::FaceCulling) & 0x3) as u8)
+    }
+
+    pub fn get_viewport(&self) -> Viewport {
+        let position = self.get(PicaCommand::ViewportPosition);
+        Viewport {
+            // 10 bit signed values
+            x: ((position << 22) as i32 >> 22) as i16,
+            y: ((position << 6) as i32 >> 22) as i16,
+            // the registers store half of the size
+            width: float24_to_f32(self.get(PicaCommand::ViewportWidth) & 0xffffff) * 2.0,
+            height: float24_to_f32(self.get(PicaCommand::ViewportHeight) & 0xffffff) * 2.0,
+        }
+    }
+
+    pub fn get_depth_map(&self) -> DepthMap {
+        DepthMap {
+            enabled: self.get(PicaCommand::DepthMapEnable) & 1 == 1,
+            scale: float24_to_f32(self.get(PicaCommand::DepthMapScale) & 0xffffff),
+            offset: float24_to_f32(self.get(PicaCommand::DepthMapOffset) & 0xffffff),
+        }
+    }
+
+    pub fn get_scissor(&self) -> Option<Scissor> {
+        let position = self.get(PicaCommand::ScissorPosition);
+        let dimension = self.get(PicaCommand::ScissorDimension);
+        Some(Scissor {
+            mode: ScissorMode::new((self.get(PicaCommand::ScissorMode) & 0x3) as u8)?,
+            left: (position & 0x3ff) as u16,
+            bottom: (position >> 16 & 0x3ff) as u16,
+            right: (dimension & 0x3ff) as u16,
+            top: (dimension >> 16 & 0x3ff) as u16,
+        })
+    }
+
+    pub fn get_shader_output_count(&self) -> u8 {
+        (self.get(PicaCommand::ShaderOutputTotal) & 0x7) as u8
+    }
+
+    /// return the semantic of each component of a vertex shader output register, from 0 to 6
+    pub fn get_shader_output_map(&self, register: u8) -> [Option<ShaderOutputSemantic>; 4] {
+        let map = self.get(PicaCommand::ShaderOutputMap(register));
+        let mut semantics = [None; 4];
+        for (component, semantic) in semantics.iter_mut().enumerate() {
+            *semantic = ShaderOutputSemantic::new((map >> (component * 8) & 0x1f) as u8);
+        }
+        semantics
+    }
+
+    pub fn get_texture_unit_config(&self) -> TextureUnitConfig {
+        let config = self.get(PicaCommand::TextureUnitConfig);
+        TextureUnitConfig {
+            enabled: [config & 1 == 1, config >> 1 & 1 == 1, config >> 2 & 1 == 1],
+            procedural_texture_enabled: config >> 10 & 1 == 1,
+            procedural_texture_coordinate: (config >> 8 & 0x3) as u8,
+            texture2_use_coordinate1: config >> 13 & 1 == 1,
+        }
+    }
+
+    /// return the texture bound to a texture unit, from 0 to 2
+    pub fn get_texture_unit(&self, unit: u8) -> Option<TextureUnit> {
+        let dimension = self.get(PicaCommand::TextureUnitDimension(unit));
+        let parameters = self.get(PicaCommand::TextureUnitParameters(unit));
+        let level_of_detail = self.get(PicaCommand::TextureUnitLevelOfDetail(unit));
+        let min_filter = match (parameters >> 2 & 1, parameters >> 24 & 1) {
+            (0, 0) => TextureMinFilter::NearestMipmapNearest,
+            (0, _) => TextureMinFilter::NearestMipmapLinear,
+            (_, 0) => TextureMinFilter::LinearMipmapNearest,
+            (_, _) => TextureMinFilter::LinearMipmapLinear,
+        };
+        Some(TextureUnit {
+            address: self.get(PicaCommand::TextureUnitAddress(unit)),
+            width: (dimension >> 16 & 0x7ff) as u16,
+            height: (dimension & 0x7ff) as u16,
+            format: TextureFormat::new((self.get(PicaCommand::TextureUnitType(unit)) & 0xf) as u8)?,
+            border_color: rgba8_to_array(self.get(PicaCommand::TextureUnitBorderColor(unit))),
+            parameters: TextureUnitParameters {
+                mag_filter: TextureMagFilter::new((parameters >> 1 & 1) as u8)?,
+                min_filter,
+                wrap_u: TextureWrap::new((parameters >> 12 & 0x7) as u8)?,
+                wrap_v: TextureWrap::new((parameters >> 8 & 0x7) as u8)?,
+                shadow: parameters >> 20 & 1 == 1,
+                r#type: TextureType::new((parameters >> 28 & 0x7) as u8)?,
+            },
+            level_of_detail: TextureLevelOfDetail {
+                bias: pica_fixed_to_f32(level_of_detail & 0x1fff, 13, 8),
+                max_level: (level_of_detail >> 16 & 0xf) as u8,
+                min_level: (level_of_detail >> 24 & 0xf) as u8,
+            },
+        })
+    }
+
+    /// return a stage of the texture combiner, from 0 to 5
+    pub fn get_texture_combiner(&self, stage: u8) -> Option<TextureCombiner> {
+        let source = self.get(PicaCommand::TextureCombinerSource(stage));
+        let operand = self.get(PicaCommand::TextureCombinerOperand(stage));
+        let combiner = self.get(PicaCommand::TextureCombinerCombiner(stage));
+        let scale = self.get(PicaCommand::TextureCombinerScale(stage));
+        let mut combiner_stage = TextureCombiner {
+            color_sources: [TextureCombinerSource::Previous; 3],
+            alpha_sources: [TextureCombinerSource::Previous; 3],
+            color_operands: [TextureCombinerColorOperand::Color; 3],
+            alpha_operands: [TextureCombinerAlphaOperand::Alpha; 3],
+            color_mode: TextureCombinerMode::new((combiner & 0xf) as u8)?,
+            alpha_mode: TextureCombinerMode::new((combiner >> 16 & 0xf) as u8)?,
+            constant_color: rgba8_to_array(self.get(PicaCommand::TextureCombinerColor(stage))),
+            color_scale: 1 << (scale & 0x3),
+            alpha_scale: 1 << (scale >> 16 & 0x3),
+        };
+        for i in 0..3 {
+            combiner_stage.color_sources[i] =
+                TextureCombinerSource::new((source >> (i * 4) & 0xf) as u8)?;
+            combiner_stage.alpha_sources[i] =
+                TextureCombinerSource::new((source >> (16 + i * 4) & 0xf) as u8)?;
+            combiner_stage.color_operands[i] =
+                TextureCombinerColorOperand::new((operand >> (i * 4) & 0xf) as u8)?;
+            combiner_stage.alpha_operands[i] =
+                TextureCombinerAlphaOperand::new((operand >> (12 + i * 4) & 0x7) as u8)?;
+        }
+        Some(combiner_stage)
+    }
+
+    pub fn get_texture_combiner_buffer_config(&self) -> Option<TextureCombinerBufferConfig> {
+        let config = self.get(PicaCommand::TextureCombinerBufferConfig);
+        let mut update_color = [false; 4];
+        let mut update_alpha = [false; 4];
+        for stage in 0..4 {
+            update_color[stage] = config >> (8 + stage) & 1 == 1;
+            update_alpha[stage] = config >> (12 + stage) & 1 == 1;
+        }
+        Some(TextureCombinerBufferConfig {
+            fog_mode: FogMode::new((config & 0x7) as u8)?,
+            update_color,
+            update_alpha,
+            buffer_color: rgba8_to_array(self.get(PicaCommand::TextureCombinerBufferColor)),
+        })
+    }
+
+    pub fn get_color_operation(&self) -> Option<ColorOperation> {
+        let color_operation = self.get(PicaCommand::ColorOperation);
+        Some(ColorOperation {
+            mode: FragmentOperationMode::new((color_operation & 0x3) as u8)?,
+            blend_enabled: color_operation >> 8 & 1 == 1,
+        })
+    }
+
+    pub fn get_logic_operation(&self) -> Option<LogicOperation> {
+        LogicOperation::new((self.get(PicaCommand::LogicOperation) & 0xf) as u8)
+    }
+
+    pub fn get_blend_color(&self) -> [u8; 4] {
+        rgba8_to_array(self.get(PicaCommand::BlendColor))
+    }
+
+    pub fn get_framebuffer(&self) -> Option<Framebuffer> {
+        let dimension = self.get(PicaCommand::FramebufferDimension);
+        Some(Framebuffer {
+            color_buffer_address: self.get(PicaCommand::ColorBufferLocation),
+            depth_buffer_address: self.get(PicaCommand::DepthBufferLocation),
+            color_format: ColorBufferFormat::new(
+                (self.get(PicaCommand::ColorBufferFormat) >> 16 & 0x7) as u8,
+            )?,
+            depth_format: DepthBufferFormat::new(
+                (self.get(PicaCommand::DepthBufferFormat) & 0x3) as u8,
+            )?,
+            width: (dimension & 0x7ff) as u16,
+            height: (dimension >> 12 & 0x3ff) as u16 + 1,
+            color_read: self.get(PicaCommand::ColorBufferRead) & 0xf != 0,
+            color_write: self.get(PicaCommand::ColorBufferWrite) & 0xf != 0,
+            depth_read: self.get(PicaCommand::DepthBufferRead) & 0x3 != 0,
+            depth_write: self.get(PicaCommand::DepthBufferWrite) & 0x3 != 0,
+        })
+    }
+
+    /// return a light of the fragment lighting, from 0 to 7
+    pub fn get_light(&self, light: u8) -> FragmentLightConfig {
+        let color = |command| {
+            let value = self.get(command);
+            [
+                (value >> 20 & 0x3ff) as f32 / 255.0,
+                (value >> 10 & 0x3ff) as f32 / 255.0,
+                (value & 0x3ff) as f32 / 255.0,
+            ]
+        };
+        let position_xy = self.get(PicaCommand::LightPositionXY(light));
+        let position_z = self.get(PicaCommand::LightPositionZ(light));
+        let spot_xy = self.get(PicaCommand::LightSpotDirectionXY(light));
+        let spot_z = self.get(PicaCommand::LightSpotDirectionZ(light));
+        let config = self.get(PicaCommand::LightConfig(light));
+        FragmentLightConfig {
+            specular0: color(PicaCommand::LightSpecular0(light)),
+            specular1: color(PicaCommand::LightSpecular1(light)),
+            diffuse: color(PicaCommand::LightDiffuse(light)),
+            ambient: color(PicaCommand::LightAmbient(light)),
+            position: [
+                pica_float_to_f32(position_xy & 0xffff, 5, 10),
+                pica_float_to_f32(position_xy >> 16, 5, 10),
+                pica_float_to_f32(position_z & 0xffff, 5, 10),
+            ],
+            spot_direction: [
+                pica_fixed_to_f32(spot_xy & 0x1fff, 13, 11),
+                pica_fixed_to_f32(spot_xy >> 16 & 0x1fff, 13, 11),
+                pica_fixed_to_f32(spot_z & 0x1fff, 13, 11),
+            ],
+            directional: config & 1 == 1,
+            two_side_diffuse: config >> 1 & 1 == 1,
+            geometric_factor: [config >> 2 & 1 == 1, config >> 3 & 1 == 1],
+            attenuation_bias: pica_float_to_f32(
+                self.get(PicaCommand::LightAttenuationBias(light)) & 0xfffff,
+                7,
+                12,
+            ),
+            attenuation_scale: pica_float_to_f32(
+                self.get(PicaCommand::LightAttenuationScale(light)) & 0xfffff,
+                7,
+                12,
+            ),
+        }
+    }
+
+    pub fn get_lighting_config(&self) -> Option<LightingConfig> {
+        let ambient = self.get(PicaCommand::LightingAmbient);
+        let config0 = self.get(PicaCommand::LightingConfig0);
+        let config1 = self.get(PicaCommand::LightingConfig1);
+        let permutation = self.get(PicaCommand::LightingLightPermutation);
+        let mut config = LightingConfig {
+            enabled: self.get(PicaCommand::LightingEnable0) & 1 == 1,
+            ambient: [
+                (ambient >> 20 & 0x3ff) as f32 / 255.0,
+                (ambient >> 10 & 0x3ff) as f32 / 255.0,
+                (ambient & 0x3ff) as f32 / 255.0,
+            ],
+            light_count: (self.get(PicaCommand::LightingLightCount) & 0x7) as u8 + 1,
+            light_permutation: [0; 8],
+            shadow_enabled: config0 & 1 == 1,
+            fresnel_selector: FresnelSelector::new((config0 >> 2 & 0x3) as u8)?,
+            layer_config: (config0 >> 4 & 0xf) as u8,
+            bump_selector: (config0 >> 22 & 0x3) as u8,
+            bump_mode: BumpMode::new((config0 >> 28 & 0x3) as u8)?,
+            clamp_highlights: config0 >> 27 & 1 == 1,
+            bump_renormalize: config0 >> 30 & 1 == 0,
+            shadow_disabled: [false; 8],
+            spotlight_disabled: [false; 8],
+            distance_attenuation_disabled: [false; 8],
+            // the look up tables are enabled when their bit is cleared
+            distribution0_enabled: config1 >> 16 & 1 == 0,
+            distribution1_enabled: config1 >> 17 & 1 == 0,
+            fresnel_enabled: config1 >> 19 & 1 == 0,
+            reflectance_red_enabled: config1 >> 20 & 1 == 0,
+            reflectance_green_enabled: config1 >> 21 & 1 == 0,
+            reflectance_blue_enabled: config1 >> 22 & 1 == 0,
+        };
+        for light in 0..8 {
+            config.light_permutation[light] = (permutation >> (light * 4) & 0x7) as u8;
+            config.shadow_disabled[light] = config1 >> light & 1 == 1;
+            config.spotlight_disabled[light] = config1 >> (8 + light) & 1 == 1;
+            config.distance_attenuation_disabled[light] = config1 >> (24 + light) & 1 == 1;
+        }
+        Some(config)
+    }
+
+    /// return how a fragment lighting look up table is indexed. Return `None` for the distance
+    /// attenuation tables, that are indexed by the distance to the light.
+    pub fn get_lookup_table_input(&self, select: LookUpTableSelect) -> Option<LookUpTableInput> {
+        let shift = match select {
+            LookUpTableSelect::Distribution0 => 0,
+            LookUpTableSelect::Distribution1 => 4,
+            LookUpTableSelect::Spotlight(_) => 8,
+            LookUpTableSelect::Fresnel => 12,
+            LookUpTableSelect::ReflectanceB => 16,
+            LookUpTableSelect::ReflectanceG => 20,
+            LookUpTableSelect::ReflectanceR => 24,
+            LookUpTableSelect::DistanceAttenuation(_) => return None,
+        };
+        let absolute = self.get(PicaCommand::LightingLookUpTableInputAbsolute);
+        let input = self.get(PicaCommand::LightingLookUpTableInputSelect);
+        let scale = match self.get(PicaCommand::LightingLookUpTableInputScale) >> shift & 0x7 {
+            0 => 1.0,
+            1 => 2.0,
+            2 => 4.0,
+            3 => 8.0,
+            6 => 0.25,
+            7 => 0.5,
+            _ => return None,
+        };
+        Some(LookUpTableInput {
+            // the bit is set to disable the absolute value
+            absolute: absolute >> (shift + 1) & 1 == 0,
+            input: LookUpTableInputType::new((input >> shift & 0x7) as u8)?,
+            scale,
+        })
+    }
+
+    /// return the base address of the attribute buffers, as written in the command
+    pub fn get_attribute_buffers_location(&self) -> u32 {
+        self.get(PicaCommand::AttributeBuffersLocation)
+    }
+
+    pub fn get_vertex_offset(&self) -> u32 {
+        self.get(PicaCommand::VertexOffset)
+    }
+
+    pub fn get_geometry_shader_enabled(&self) -> bool {
+        self.get(PicaCommand::GeometryStageConfig) & 0x3 == 2
+    }
+
+    pub fn get_primitive_mode(&self) -> Option<PrimitiveMode> {
+        PrimitiveMode::new((self.get(PicaCommand::PrimitiveConfig) >> 8 & 0x3) as u8)
+    }
+
+    pub fn get_shader_setup(&self, unit: ShaderUnit) -> ShaderSetup {
+        let (bool_uniform, input, entry_point, permutation_low, permutation_high, output_mask) =
+            match unit {
+                ShaderUnit::Vertex => (
+                    PicaCommand::VertexShaderBoolUniform,
+                    PicaCommand::VertexShaderInputBufferConfig,
+                    PicaCommand::VertexShaderEntryPoint,
+                    PicaCommand::VertexShaderAttributesPermutationLow,
+                    PicaCommand::VertexShaderAttributesPermutationHigh,
+                    PicaCommand::VertexShaderOutputMapMask,
+                ),
+                ShaderUnit::Geometry => (
+                    PicaCommand::GeometryShaderBoolUniform,
+                    PicaCommand::GeometryShaderInputBufferConfig,
+                    PicaCommand::GeometryShaderEntryPoint,
+                    PicaCommand::GeometryShaderAttributesPermutationLow,
+                    PicaCommand::GeometryShaderAttributesPermutationHigh,
+                    PicaCommand::GeometryShaderOutputMapMask,
+                ),
+            };
+        let bool_uniform = self.get(bool_uniform);
+        let permutation =
+            self.get(permutation_low) as u64 | (self.get(permutation_high) as u64) << 32;
+        let mut setup = ShaderSetup {
+            bool_uniforms: [false; 16],
+            integer_uniforms: [[0; 4]; 4],
+            input_count: (self.get(input) & 0xf) as u8 + 1,
+            entry_point: (self.get(entry_point) & 0xffff) as u16,
+            input_permutation: [0; 16],
+            output_mask: (self.get(output_mask) & 0xffff) as u16,
+        };
+        for (id, value) in setup.bool_uniforms.iter_mut().enumerate() {
+            *value = bool_uniform >> id & 1 == 1;
+        }
+        for (id, integer) in setup.integer_uniforms.iter_mut().enumerate() {
+            let command = match unit {
+                ShaderUnit::Vertex => PicaCommand::VertexShaderIntegerUniform(id as u8),
+                ShaderUnit::Geometry => PicaCommand::GeometryShaderIntegerUniform(id as u8),
+            };
+            *integer = self.get(command).to_le_bytes();
+        }
+        for (attribute, register) in setup.input_permutation.iter_mut().enumerate() {
+            *register = (permutation >> (attribute * 4) & 0xf) as u8;
+        }
+        setup
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    /// build a command writing `parameters` from the register `id`, padded to 8 bytes
+    fn command(id: u16, parameters: &[u32], consecutive: bool) -> Vec<u32> {
+        let header = id as u32
+            | 0xf << 16
+            | ((parameters.len() - 1) as u32) << 20
+            | (consecutive as u32) << 31;
+        let mut words = vec![parameters[0], header];
+        words.extend_from_slice(&parameters[1..]);
+        if words.len() % 2 == 1 {
+            words.push(0);
+        };
+        words
+    }
+
+    /// read the commands, followed by the end of the block
+    fn read_commands(commands: &[Vec<u32>]) -> PICACommandReader {
+        let mut words: Vec<u32> = commands.concat();
+        words.extend(command(0x23d, &[1], false));
+        let data: Vec<u8> = words.iter().flat_map(|word| word.to_le_bytes()).collect();
+        PICACommandReader::read(&mut Cursor::new(data), words.len() as u64).unwrap()
+    }
+
+    #[test]
+    fn lookup_table_wrap_and_select() {
+        let reader = read_commands(&[
+            // start at the entry 254 of the Fresnel table
+            command(0x1c5, &[3 << 8 | 254], false),
+            command(0x1c8, &[0, 0xfff, 0x800, 0x1fff], false),
+            // switch to the entry 10 of the ReflectanceG table
+            command(0x1c5, &[5 << 8 | 10], false),
+            command(0x1c8, &[0x123], false),
+        ]);
+
+        let fresnel = reader.get_lookup_table(LookUpTableSelect::Fresnel).unwrap();
+        assert_eq!(fresnel[254], 0.0);
+        assert_eq!(fresnel[255], 1.0);
+        assert_eq!(fresnel[0], 0x800 as f32 / 4095.0);
+        // the bits above the 12th are ignored
+        assert_eq!(fresnel[1], 1.0);
+        assert_eq!(fresnel[2], 0.0);
+
+        let reflectance_g = reader
+            .get_lookup_table(LookUpTableSelect::ReflectanceG)
+            .unwrap();
+        assert_eq!(reflectance_g[10], 0x123 as f32 / 4095.0);
+        assert_eq!(reflectance_g[11], 0.0);
+
+        assert!(reader
+            .get_lookup_table(LookUpTableSelect::Distribution0)
+            .is_none());
+        assert!(reader
+            .get_lookup_table(LookUpTableSelect::Spotlight(0))
+            .is_none());
+    }
+
+    #[test]
+    fn float24_uniform() {
+        // x = 1, y = 2, z = -0.5, w = 0, split over two commands
+        let reader = read_commands(&[
+            command(0x2c0, &[5], false),
+            command(0x2c1, &[0x0000_00be, 0x0000_4000], false),
+            command(0x2c1, &[0x003f_0000], false),
+        ]);
+        assert_eq!(reader.float_uniform[5], vec![0.0, -0.5, 2.0, 1.0]);
+    }
+
+    #[test]
+    fn float32_uniform() {
+        let values = [4.0f32, 3.0, 2.0, 1.0];
+        let words: Vec<u32> = values.iter().map(|value| value.to_bits()).collect();
+        let reader = read_commands(&[
+            command(0x2c0, &[0x8000_0002], false),
+            command(0x2c1, &words, false),
+        ]);
+        assert_eq!(reader.float_uniform[2], values.to_vec());
+    }
 }
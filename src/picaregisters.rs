@@ -0,0 +1,648 @@
+//! Typed values of the PICA200 GPU registers, as returned by the getters of `PICACommandReader`
+
+use crate::model::{TextureMagFilter, TextureMinFilter, TextureWrap};
+use crate::TextureFormat;
+
+/// convert a float of the PICA200 with the given number of exponent and mantissa bits (and a
+/// sign bit) to a f32. Denormals are flushed to zero.
+pub fn pica_float_to_f32(value: u32, exponent_bits: u32, mantissa_bits: u32) -> f32 {
+    let sign = (value >> (exponent_bits + mantissa_bits)) & 1;
+    let exponent_max = (1 << exponent_bits) - 1;
+    let exponent = (value >> mantissa_bits) & exponent_max;
+    let mantissa = (value & ((1 << mantissa_bits) - 1)) << (23 - mantissa_bits);
+    let bits = if exponent == 0 {
+        sign << 31
+    } else if exponent == exponent_max {
+        (sign << 31) | (0xff << 23) | mantissa
+    } else {
+        let bias = (exponent_max >> 1) as i32;
+        (sign << 31) | (((exponent as i32 - bias + 127) as u32) << 23) | mantissa
+    };
+    f32::from_bits(bits)
+}
+
+/// convert a signed fixed point number of `bits` bits, with `fraction_bits` of them after the
+/// point, to a f32
+pub fn pica_fixed_to_f32(value: u32, bits: u32, fraction_bits: u32) -> f32 {
+    let shift = 32 - bits;
+    (((value << shift) as i32) >> shift) as f32 / (1 << fraction_bits) as f32
+}
+
+/// return the red, green, blue and alpha components of a color stored with 8 bits per component
+pub fn rgba8_to_array(value: u32) -> [u8; 4] {
+    value.to_le_bytes()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CullMode {
+    KeepAll,
+    KeepClockwise,
+    KeepCounterClockwise,
+}
+
+impl CullMode {
+    pub fn new(value: u8) -> Option<Self> {
+        Some(match value {
+            0 => Self::KeepAll,
+            1 => Self::KeepClockwise,
+            2 => Self::KeepCounterClockwise,
+            _ => return None,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Viewport {
+    pub x: i16,
+    pub y: i16,
+    pub width: f32,
+    pub height: f32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DepthMap {
+    pub enabled: bool,
+    pub scale: f32,
+    pub offset: f32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ScissorMode {
+    Disabled,
+    Exclude,
+    Include,
+}
+
+impl ScissorMode {
+    pub fn new(value: u8) -> Option<Self> {
+        Some(match value {
+            0 => Self::Disabled,
+            1 => Self::Exclude,
+            3 => Self::Include,
+            _ => return None,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Scissor {
+    pub mode: ScissorMode,
+    pub left: u16,
+    pub bottom: u16,
+    pub right: u16,
+    pub top: u16,
+}
+
+/// The meaning of a component of a vertex shader output register. The `u8` is the component of
+/// the value, 0 being x (or red, or u)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ShaderOutputSemantic {
+    Position(u8),
+    Quaternion(u8),
+    Color(u8),
+    TextureCoordinate0(u8),
+    TextureCoordinate1(u8),
+    TextureCoordinate0W,
+    View(u8),
+    TextureCoordinate2(u8),
+    Unused,
+}
+
+impl ShaderOutputSemantic {
+    pub fn new(value: u8) -> Option<Self> {
+        Some(match value {
+            0x00..=0x03 => Self::Position(value),
+            0x04..=0x07 => Self::Quaternion(value - 0x04),
+            0x08..=0x0b => Self::Color(value - 0x08),
+            0x0c..=0x0d => Self::TextureCoordinate0(value - 0x0c),
+            0x0e..=0x0f => Self::TextureCoordinate1(value - 0x0e),
+            0x10 => Self::TextureCoordinate0W,
+            0x12..=0x14 => Self::View(value - 0x12),
+            0x16..=0x17 => Self::TextureCoordinate2(value - 0x16),
+            0x1f => Self::Unused,
+            _ => return None,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TextureUnitConfig {
+    pub enabled: [bool; 3],
+    pub procedural_texture_enabled: bool,
+    /// the texture coordinate used by the procedural texture, from 0 to 2
+    pub procedural_texture_coordinate: u8,
+    /// true if the texture unit 2 uses the texture coordinate 1 instead of 2
+    pub texture2_use_coordinate1: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TextureType {
+    Texture2D,
+    CubeMap,
+    Shadow2D,
+    Projection,
+    ShadowCube,
+    Disabled,
+}
+
+impl TextureType {
+    pub fn new(value: u8) -> Option<Self> {
+        Some(match value {
+            0 => Self::Texture2D,
+            1 => Self::CubeMap,
+            2 => Self::Shadow2D,
+            3 => Self::Projection,
+            4 => Self::ShadowCube,
+            5 => Self::Disabled,
+            _ => return None,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TextureUnitParameters {
+    pub mag_filter: TextureMagFilter,
+    /// the minification filter. The mipmap part is only used when the texture has more than one
+    /// level.
+    pub min_filter: TextureMinFilter,
+    pub wrap_u: TextureWrap,
+    pub wrap_v: TextureWrap,
+    pub shadow: bool,
+    pub r#type: TextureType,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TextureLevelOfDetail {
+    pub bias: f32,
+    pub max_level: u8,
+    pub min_level: u8,
+}
+
+/// The texture bound to a texture unit, and how it is sampled
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TextureUnit {
+    /// the address of the texture, as written in the command. In BCH files, commands are
+    /// relocated so it is an offset in the file.
+    pub address: u32,
+    pub width: u16,
+    pub height: u16,
+    pub format: TextureFormat,
+    pub border_color: [u8; 4],
+    pub parameters: TextureUnitParameters,
+    pub level_of_detail: TextureLevelOfDetail,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TextureCombinerSource {
+    PrimaryColor,
+    FragmentPrimaryColor,
+    FragmentSecondaryColor,
+    Texture0,
+    Texture1,
+    Texture2,
+    Texture3,
+    PreviousBuffer,
+    Constant,
+    Previous,
+}
+
+impl TextureCombinerSource {
+    pub fn new(value: u8) -> Option<Self> {
+        Some(match value {
+            0 => Self::PrimaryColor,
+            1 => Self::FragmentPrimaryColor,
+            2 => Self::FragmentSecondaryColor,
+            3 => Self::Texture0,
+            4 => Self::Texture1,
+            5 => Self::Texture2,
+            6 => Self::Texture3,
+            13 => Self::PreviousBuffer,
+            14 => Self::Constant,
+            15 => Self::Previous,
+            _ => return None,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TextureCombinerColorOperand {
+    Color,
+    OneMinusColor,
+    Alpha,
+    OneMinusAlpha,
+    Red,
+    OneMinusRed,
+    Green,
+    OneMinusGreen,
+    Blue,
+    OneMinusBlue,
+}
+
+impl TextureCombinerColorOperand {
+    pub fn new(value: u8) -> Option<Self> {
+        Some(match value {
+            0 => Self::Color,
+            1 => Self::OneMinusColor,
+            2 => Self::Alpha,
+            3 => Self::OneMinusAlpha,
+            4 => Self::Red,
+            5 => Self::OneMinusRed,
+            8 => Self::Green,
+            9 => Self::OneMinusGreen,
+            12 => Self::Blue,
+            13 => Self::OneMinusBlue,
+            _ => return None,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TextureCombinerAlphaOperand {
+    Alpha,
+    OneMinusAlpha,
+    Red,
+    OneMinusRed,
+    Green,
+    OneMinusGreen,
+    Blue,
+    OneMinusBlue,
+}
+
+impl TextureCombinerAlphaOperand {
+    pub fn new(value: u8) -> Option<Self> {
+        Some(match value {
+            0 => Self::Alpha,
+            1 => Self::OneMinusAlpha,
+            2 => Self::Red,
+            3 => Self::OneMinusRed,
+            4 => Self::Green,
+            5 => Self::OneMinusGreen,
+            6 => Self::Blue,
+            7 => Self::OneMinusBlue,
+            _ => return None,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TextureCombinerMode {
+    Replace,
+    Modulate,
+    Add,
+    AddSigned,
+    Interpolate,
+    Subtract,
+    Dot3Rgb,
+    Dot3Rgba,
+    MultiplyAdd,
+    AddMultiply,
+}
+
+impl TextureCombinerMode {
+    pub fn new(value: u8) -> Option<Self> {
+        Some(match value {
+            0 => Self::Replace,
+            1 => Self::Modulate,
+            2 => Self::Add,
+            3 => Self::AddSigned,
+            4 => Self::Interpolate,
+            5 => Self::Subtract,
+            6 => Self::Dot3Rgb,
+            7 => Self::Dot3Rgba,
+            8 => Self::MultiplyAdd,
+            9 => Self::AddMultiply,
+            _ => return None,
+        })
+    }
+}
+
+/// A stage of the texture combiner (TEV)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TextureCombiner {
+    pub color_sources: [TextureCombinerSource; 3],
+    pub alpha_sources: [TextureCombinerSource; 3],
+    pub color_operands: [TextureCombinerColorOperand; 3],
+    pub alpha_operands: [TextureCombinerAlphaOperand; 3],
+    pub color_mode: TextureCombinerMode,
+    pub alpha_mode: TextureCombinerMode,
+    pub constant_color: [u8; 4],
+    /// 1, 2 or 4
+    pub color_scale: u8,
+    /// 1, 2 or 4
+    pub alpha_scale: u8,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FogMode {
+    Disabled,
+    Fog,
+    Gas,
+}
+
+impl FogMode {
+    pub fn new(value: u8) -> Option<Self> {
+        Some(match value {
+            0 => Self::Disabled,
+            5 => Self::Fog,
+            7 => Self::Gas,
+            _ => return None,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TextureCombinerBufferConfig {
+    pub fog_mode: FogMode,
+    /// true if the color of the combiner buffer is updated with the output of the stages 1 to 4
+    pub update_color: [bool; 4],
+    /// true if the alpha of the combiner buffer is updated with the output of the stages 1 to 4
+    pub update_alpha: [bool; 4],
+    /// the initial value of the combiner buffer
+    pub buffer_color: [u8; 4],
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FragmentOperationMode {
+    Default,
+    Gas,
+    Shadow,
+}
+
+impl FragmentOperationMode {
+    pub fn new(value: u8) -> Option<Self> {
+        Some(match value {
+            0 => Self::Default,
+            2 => Self::Gas,
+            3 => Self::Shadow,
+            _ => return None,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ColorOperation {
+    pub mode: FragmentOperationMode,
+    /// true if the blend function is used, false if the logic operation is used
+    pub blend_enabled: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LogicOperation {
+    Clear,
+    And,
+    AndReverse,
+    Copy,
+    Set,
+    CopyInverted,
+    NoOp,
+    Invert,
+    Nand,
+    Or,
+    Nor,
+    Xor,
+    Equivalent,
+    AndInverted,
+    OrReverse,
+    OrInverted,
+}
+
+impl LogicOperation {
+    pub fn new(value: u8) -> Option<Self> {
+        Some(match value {
+            0 => Self::Clear,
+            1 => Self::And,
+            2 => Self::AndReverse,
+            3 => Self::Copy,
+            4 => Self::Set,
+            5 => Self::CopyInverted,
+            6 => Self::NoOp,
+            7 => Self::Invert,
+            8 => Self::Nand,
+            9 => Self::Or,
+            10 => Self::Nor,
+            11 => Self::Xor,
+            12 => Self::Equivalent,
+            13 => Self::AndInverted,
+            14 => Self::OrReverse,
+            15 => Self::OrInverted,
+            _ => return None,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ColorBufferFormat {
+    Rgba8,
+    Rgb8,
+    Rgb5a1,
+    Rgb565,
+    Rgba4,
+}
+
+impl ColorBufferFormat {
+    pub fn new(value: u8) -> Option<Self> {
+        Some(match value {
+            0 => Self::Rgba8,
+            1 => Self::Rgb8,
+            2 => Self::Rgb5a1,
+            3 => Self::Rgb565,
+            4 => Self::Rgba4,
+            _ => return None,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DepthBufferFormat {
+    D16,
+    D24,
+    D24S8,
+}
+
+impl DepthBufferFormat {
+    pub fn new(value: u8) -> Option<Self> {
+        Some(match value {
+            0 => Self::D16,
+            2 => Self::D24,
+            3 => Self::D24S8,
+            _ => return None,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Framebuffer {
+    /// the addresses of the buffers, as written in the commands
+    pub color_buffer_address: u32,
+    pub depth_buffer_address: u32,
+    pub color_format: ColorBufferFormat,
+    pub depth_format: DepthBufferFormat,
+    pub width: u16,
+    pub height: u16,
+    pub color_read: bool,
+    pub color_write: bool,
+    pub depth_read: bool,
+    pub depth_write: bool,
+}
+
+/// A light of the fragment lighting. Colors are between 0 and 1.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FragmentLightConfig {
+    pub specular0: [f32; 3],
+    pub specular1: [f32; 3],
+    pub diffuse: [f32; 3],
+    pub ambient: [f32; 3],
+    /// the position of the light, or its direction if `directional` is true
+    pub position: [f32; 3],
+    pub spot_direction: [f32; 3],
+    pub directional: bool,
+    pub two_side_diffuse: bool,
+    pub geometric_factor: [bool; 2],
+    pub attenuation_bias: f32,
+    pub attenuation_scale: f32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FresnelSelector {
+    None,
+    PrimaryAlpha,
+    SecondaryAlpha,
+    Both,
+}
+
+impl FresnelSelector {
+    pub fn new(value: u8) -> Option<Self> {
+        Some(match value {
+            0 => Self::None,
+            1 => Self::PrimaryAlpha,
+            2 => Self::SecondaryAlpha,
+            3 => Self::Both,
+            _ => return None,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BumpMode {
+    None,
+    NormalMap,
+    TangentMap,
+}
+
+impl BumpMode {
+    pub fn new(value: u8) -> Option<Self> {
+        Some(match value {
+            0 => Self::None,
+            1 => Self::NormalMap,
+            2 => Self::TangentMap,
+            _ => return None,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LightingConfig {
+    pub enabled: bool,
+    pub ambient: [f32; 3],
+    pub light_count: u8,
+    /// the light used for each slot, from 0 to 7
+    pub light_permutation: [u8; 8],
+    pub shadow_enabled: bool,
+    pub fresnel_selector: FresnelSelector,
+    /// the hardware layer configuration, that defines which look up tables are available
+    pub layer_config: u8,
+    /// the texture unit used for the bump map
+    pub bump_selector: u8,
+    pub bump_mode: BumpMode,
+    pub clamp_highlights: bool,
+    pub bump_renormalize: bool,
+    /// per light, and indexed by the light number
+    pub shadow_disabled: [bool; 8],
+    pub spotlight_disabled: [bool; 8],
+    pub distance_attenuation_disabled: [bool; 8],
+    pub distribution0_enabled: bool,
+    pub distribution1_enabled: bool,
+    pub fresnel_enabled: bool,
+    pub reflectance_red_enabled: bool,
+    pub reflectance_green_enabled: bool,
+    pub reflectance_blue_enabled: bool,
+}
+
+/// The value used to index a fragment lighting look up table
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LookUpTableInputType {
+    /// the normal dot the half vector
+    NormalHalf,
+    /// the view dot the half vector
+    ViewHalf,
+    /// the normal dot the view
+    NormalView,
+    /// the light dot the normal
+    LightNormal,
+    /// minus the light dot the spot direction
+    LightSpot,
+    /// the cosine of the angle between the tangent and the projected half vector
+    CosPhi,
+}
+
+impl LookUpTableInputType {
+    pub fn new(value: u8) -> Option<Self> {
+        Some(match value {
+            0 => Self::NormalHalf,
+            1 => Self::ViewHalf,
+            2 => Self::NormalView,
+            3 => Self::LightNormal,
+            4 => Self::LightSpot,
+            5 => Self::CosPhi,
+            _ => return None,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LookUpTableInput {
+    /// true if the absolute value of the input is used, false if it is used as a signed value
+    pub absolute: bool,
+    pub input: LookUpTableInputType,
+    pub scale: f32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PrimitiveMode {
+    Triangles,
+    TriangleStrip,
+    TriangleFan,
+    GeometryPrimitive,
+}
+
+impl PrimitiveMode {
+    pub fn new(value: u8) -> Option<Self> {
+        Some(match value {
+            0 => Self::Triangles,
+            1 => Self::TriangleStrip,
+            2 => Self::TriangleFan,
+            3 => Self::GeometryPrimitive,
+            _ => return None,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ShaderUnit {
+    Vertex,
+    Geometry,
+}
+
+/// The configuration of a shader unit, as set before running its program
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ShaderSetup {
+    pub bool_uniforms: [bool; 16],
+    pub integer_uniforms: [[u8; 4]; 4],
+    pub input_count: u8,
+    /// the offset, in instructions, of the first instruction to run
+    pub entry_point: u16,
+    /// the input register loaded from each attribute
+    pub input_permutation: [u8; 16],
+    /// one bit per output register written by the program
+    pub output_mask: u16,
+}
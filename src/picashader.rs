@@ -0,0 +1,808 @@
+use crate::shader::{ShaderBinary, ShaderProgram};
+use std::fmt::Write;
+
+fn bits(word: u32, start: u32, length: u32) -> u32 {
+    (word >> start) & ((1 << length) - 1)
+}
+
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub enum ShaderOpcode {
+    Add,
+    Dp3,
+    Dp4,
+    Dph,
+    Dst,
+    Ex2,
+    Lg2,
+    Litp,
+    Mul,
+    Sge,
+    Slt,
+    Flr,
+    Max,
+    Min,
+    Rcp,
+    Rsq,
+    Mova,
+    Mov,
+    Dphi,
+    Dsti,
+    Sgei,
+    Slti,
+    Break,
+    Nop,
+    End,
+    Breakc,
+    Call,
+    Callc,
+    Callu,
+    Ifu,
+    Ifc,
+    Loop,
+    Emit,
+    SetEmit,
+    Jmpc,
+    Jmpu,
+    Cmp,
+    Madi,
+    Mad,
+}
+
+impl ShaderOpcode {
+    /// create the opcode from the 6 upper bits of an instruction
+    pub fn new(value: u8) -> Option<Self> {
+        Some(match value {
+            0x00 => Self::Add,
+            0x01 => Self::Dp3,
+            0x02 => Self::Dp4,
+            0x03 => Self::Dph,
+            0x04 => Self::Dst,
+            0x05 => Self::Ex2,
+            0x06 => Self::Lg2,
+            0x07 => Self::Litp,
+            0x08 => Self::Mul,
+            0x09 => Self::Sge,
+            0x0a => Self::Slt,
+            0x0b => Self::Flr,
+            0x0c => Self::Max,
+            0x0d => Self::Min,
+            0x0e => Self::Rcp,
+            0x0f => Self::Rsq,
+            0x12 => Self::Mova,
+            0x13 => Self::Mov,
+            0x18 => Self::Dphi,
+            0x19 => Self::Dsti,
+            0x1a => Self::Sgei,
+            0x1b => Self::Slti,
+            0x20 => Self::Break,
+            0x21 => Self::Nop,
+            0x22 => Self::End,
+            0x23 => Self::Breakc,
+            0x24 => Self::Call,
+            0x25 => Self::Callc,
+            0x26 => Self::Callu,
+            0x27 => Self::Ifu,
+            0x28 => Self::Ifc,
+            0x29 => Self::Loop,
+            0x2a => Self::Emit,
+            0x2b => Self::SetEmit,
+            0x2c => Self::Jmpc,
+            0x2d => Self::Jmpu,
+            0x2e | 0x2f => Self::Cmp,
+            0x30..=0x37 => Self::Madi,
+            0x38..=0x3f => Self::Mad,
+            _ => return None,
+        })
+    }
+
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            Self::Add => "add",
+            Self::Dp3 => "dp3",
+            Self::Dp4 => "dp4",
+            Self::Dph => "dph",
+            Self::Dst => "dst",
+            Self::Ex2 => "ex2",
+            Self::Lg2 => "lg2",
+            Self::Litp => "litp",
+            Self::Mul => "mul",
+            Self::Sge => "sge",
+            Self::Slt => "slt",
+            Self::Flr => "flr",
+            Self::Max => "max",
+            Self::Min => "min",
+            Self::Rcp => "rcp",
+            Self::Rsq => "rsq",
+            Self::Mova => "mova",
+            Self::Mov => "mov",
+            Self::Dphi => "dphi",
+            Self::Dsti => "dsti",
+            Self::Sgei => "sgei",
+            Self::Slti => "slti",
+            Self::Break => "break",
+            Self::Nop => "nop",
+            Self::End => "end",
+            Self::Breakc => "breakc",
+            Self::Call => "call",
+            Self::Callc => "callc",
+            Self::Callu => "callu",
+            Self::Ifu => "ifu",
+            Self::Ifc => "ifc",
+            Self::Loop => "loop",
+            Self::Emit => "emit",
+            Self::SetEmit => "setemit",
+            Self::Jmpc => "jmpc",
+            Self::Jmpu => "jmpu",
+            Self::Cmp => "cmp",
+            Self::Madi => "madi",
+            Self::Mad => "mad",
+        }
+    }
+
+    /// return true if the operation only use its first source
+    pub fn is_unary(&self) -> bool {
+        matches!(
+            self,
+            Self::Ex2
+                | Self::Lg2
+                | Self::Litp
+                | Self::Flr
+                | Self::Rcp
+                | Self::Rsq
+                | Self::Mova
+                | Self::Mov
+        )
+    }
+}
+
+/// The address register component added to the index of a source register
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub enum RelativeAddress {
+    None,
+    AddressX,
+    AddressY,
+    LoopCounter,
+}
+
+impl RelativeAddress {
+    pub fn new(value: u8) -> Option<Self> {
+        Some(match value {
+            0 => Self::None,
+            1 => Self::AddressX,
+            2 => Self::AddressY,
+            3 => Self::LoopCounter,
+            _ => return None,
+        })
+    }
+}
+
+/// A source register: 0x00 to 0x0f are the inputs (v0 to v15), 0x10 to 0x1f the temporaries (r0
+/// to r15) and 0x20 to 0x7f the float uniforms (c0 to c95)
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub struct ShaderSource {
+    pub register: u8,
+    pub relative: RelativeAddress,
+}
+
+impl ShaderSource {
+    fn new(register: u32) -> Self {
+        Self {
+            register: register as u8,
+            relative: RelativeAddress::None,
+        }
+    }
+
+    fn new_relative(register: u32, relative: u32) -> Self {
+        Self {
+            register: register as u8,
+            // two bits are always a valid address
+            relative: RelativeAddress::new(relative as u8).unwrap(),
+        }
+    }
+
+    /// return the float uniform this read from, ignoring the relative address
+    pub fn get_float_uniform(&self) -> Option<u8> {
+        if self.register >= 0x20 {
+            Some(self.register - 0x20)
+        } else {
+            None
+        }
+    }
+
+    fn get_name(&self) -> String {
+        let name = match self.register {
+            0x00..=0x0f => format!("v{}", self.register),
+            0x10..=0x1f => format!("r{}", self.register - 0x10),
+            _ => format!("c{}", self.register - 0x20),
+        };
+        match self.relative {
+            RelativeAddress::None => name,
+            RelativeAddress::AddressX => format!("{}[a0.x]", name),
+            RelativeAddress::AddressY => format!("{}[a0.y]", name),
+            RelativeAddress::LoopCounter => format!("{}[aL]", name),
+        }
+    }
+}
+
+fn get_destination_name(register: u8) -> String {
+    match register {
+        0x00..=0x0f => format!("o{}", register),
+        _ => format!("r{}", register - 0x10),
+    }
+}
+
+const COMPONENTS: [char; 4] = ['x', 'y', 'z', 'w'];
+
+/// The swizzles, negations and destination mask of an instruction, stored in the operand
+/// descriptor table of the shader binary
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub struct OperandDescriptor(pub u32);
+
+impl OperandDescriptor {
+    /// return the written component of the destination, in the x, y, z, w order
+    pub fn get_destination_mask(&self) -> [bool; 4] {
+        let mut mask = [false; 4];
+        for (component, enabled) in mask.iter_mut().enumerate() {
+            *enabled = self.0 & (8 >> component) != 0;
+        }
+        mask
+    }
+
+    /// return true if the source (from 0 to 2) is negated
+    pub fn is_source_negated(&self, source: usize) -> bool {
+        self.0 & (1 << (4 + source * 9)) != 0
+    }
+
+    /// return the component read for each of the x, y, z, w component of the source (from 0 to
+    /// 2)
+    pub fn get_source_swizzle(&self, source: usize) -> [usize; 4] {
+        let swizzle = bits(self.0, 5 + source as u32 * 9, 8);
+        let mut result = [0; 4];
+        for (component, selector) in result.iter_mut().enumerate() {
+            *selector = bits(swizzle, 6 - component as u32 * 2, 2) as usize;
+        }
+        result
+    }
+
+    fn format_destination(&self, name: String) -> String {
+        let mask = self.get_destination_mask();
+        if mask == [true; 4] {
+            return name;
+        };
+        let components: String = COMPONENTS
+            .iter()
+            .zip(mask.iter())
+            .filter(|(_, enabled)| **enabled)
+            .map(|(component, _)| component)
+            .collect();
+        format!("{}.{}", name, components)
+    }
+
+    fn format_source(&self, source: usize, register: &ShaderSource) -> String {
+        let negate = if self.is_source_negated(source) {
+            "-"
+        } else {
+            ""
+        };
+        let swizzle = self.get_source_swizzle(source);
+        if swizzle == [0, 1, 2, 3] {
+            format!("{}{}", negate, register.get_name())
+        } else {
+            let components: String = swizzle
+                .iter()
+                .map(|selector| COMPONENTS[*selector])
+                .collect();
+            format!("{}{}.{}", negate, register.get_name(), components)
+        }
+    }
+}
+
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub enum CompareOperation {
+    Equal,
+    NotEqual,
+    LessThan,
+    LessEqual,
+    GreaterThan,
+    GreaterEqual,
+}
+
+impl CompareOperation {
+    pub fn new(value: u8) -> Option<Self> {
+        Some(match value {
+            0 => Self::Equal,
+            1 => Self::NotEqual,
+            2 => Self::LessThan,
+            3 => Self::LessEqual,
+            4 => Self::GreaterThan,
+            5 => Self::GreaterEqual,
+            _ => return None,
+        })
+    }
+
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            Self::Equal => "eq",
+            Self::NotEqual => "ne",
+            Self::LessThan => "lt",
+            Self::LessEqual => "le",
+            Self::GreaterThan => "gt",
+            Self::GreaterEqual => "ge",
+        }
+    }
+}
+
+/// How the two condition code flags set by `cmp` are combined
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub enum ConditionOperation {
+    Or,
+    And,
+    X,
+    Y,
+}
+
+impl ConditionOperation {
+    pub fn new(value: u8) -> Option<Self> {
+        Some(match value {
+            0 => Self::Or,
+            1 => Self::And,
+            2 => Self::X,
+            3 => Self::Y,
+            _ => return None,
+        })
+    }
+}
+
+/// What decide if a flow control instruction is taken
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub enum FlowCondition {
+    Always,
+    /// the condition code flags, compared with the reference values
+    Condition {
+        operation: ConditionOperation,
+        reference_x: bool,
+        reference_y: bool,
+    },
+    /// a bool uniform (b0 to b15)
+    BoolUniform(u8),
+    /// an integer uniform (i0 to i3), giving the loop count, start and step
+    IntegerUniform(u8),
+}
+
+impl FlowCondition {
+    fn get_name(&self) -> String {
+        match self {
+            Self::Always => String::new(),
+            Self::Condition {
+                operation,
+                reference_x,
+                reference_y,
+            } => {
+                let x = if *reference_x { "cc.x" } else { "!cc.x" };
+                let y = if *reference_y { "cc.y" } else { "!cc.y" };
+                match operation {
+                    ConditionOperation::Or => format!("{} || {}", x, y),
+                    ConditionOperation::And => format!("{} && {}", x, y),
+                    ConditionOperation::X => x.to_string(),
+                    ConditionOperation::Y => y.to_string(),
+                }
+            }
+            Self::BoolUniform(id) => format!("b{}", id),
+            Self::IntegerUniform(id) => format!("i{}", id),
+        }
+    }
+}
+
+/// A decoded PICA200 shader instruction
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub enum ShaderInstruction {
+    /// an operation on one or two sources. `destination` is ignored by `mova`, that write the
+    /// address register.
+    Arithmetic {
+        opcode: ShaderOpcode,
+        destination: u8,
+        source1: ShaderSource,
+        source2: ShaderSource,
+        descriptor: u8,
+    },
+    /// set the condition code flags by comparing the x and y components of the sources
+    Compare {
+        source1: ShaderSource,
+        source2: ShaderSource,
+        operation_x: CompareOperation,
+        operation_y: CompareOperation,
+        descriptor: u8,
+    },
+    /// compute `source1 * source2 + source3`
+    MultiplyAdd {
+        opcode: ShaderOpcode,
+        destination: u8,
+        source1: ShaderSource,
+        source2: ShaderSource,
+        source3: ShaderSource,
+        descriptor: u8,
+    },
+    /// a jump, call, conditional or loop. `destination` is an instruction offset, and `count` the
+    /// number of instructions to run from it for calls and for the else branch of conditionals.
+    Flow {
+        opcode: ShaderOpcode,
+        destination: u16,
+        count: u8,
+        condition: FlowCondition,
+    },
+    SetEmit {
+        vertex_id: u8,
+        primitive_emit: bool,
+        winding: bool,
+    },
+    /// `nop`, `end`, `break` and `emit`
+    Simple(ShaderOpcode),
+}
+
+impl ShaderInstruction {
+    pub fn decode(word: u32) -> Option<Self> {
+        let opcode = ShaderOpcode::new(bits(word, 26, 6) as u8)?;
+        Some(match opcode {
+            ShaderOpcode::Dphi | ShaderOpcode::Dsti | ShaderOpcode::Sgei | ShaderOpcode::Slti => {
+                Self::Arithmetic {
+                    opcode,
+                    destination: bits(word, 21, 5) as u8,
+                    source1: ShaderSource::new(bits(word, 14, 5)),
+                    source2: ShaderSource::new_relative(bits(word, 7, 7), bits(word, 19, 2)),
+                    descriptor: bits(word, 0, 7) as u8,
+                }
+            }
+            ShaderOpcode::Cmp => Self::Compare {
+                source1: ShaderSource::new_relative(bits(word, 12, 7), bits(word, 19, 2)),
+                source2: ShaderSource::new(bits(word, 7, 5)),
+                operation_x: CompareOperation::new(bits(word, 24, 3) as u8)?,
+                operation_y: CompareOperation::new(bits(word, 21, 3) as u8)?,
+                descriptor: bits(word, 0, 7) as u8,
+            },
+            ShaderOpcode::Mad => Self::MultiplyAdd {
+                opcode,
+                destination: bits(word, 24, 5) as u8,
+                source1: ShaderSource::new(bits(word, 17, 5)),
+                source2: ShaderSource::new_relative(bits(word, 10, 7), bits(word, 22, 2)),
+                source3: ShaderSource::new(bits(word, 5, 5)),
+                descriptor: bits(word, 0, 5) as u8,
+            },
+            ShaderOpcode::Madi => Self::MultiplyAdd {
+                opcode,
+                destination: bits(word, 24, 5) as u8,
+                source1: ShaderSource::new(bits(word, 17, 5)),
+                source2: ShaderSource::new(bits(word, 12, 5)),
+                source3: ShaderSource::new_relative(bits(word, 5, 7), bits(word, 22, 2)),
+                descriptor: bits(word, 0, 5) as u8,
+            },
+            ShaderOpcode::Breakc
+            | ShaderOpcode::Call
+            | ShaderOpcode::Callc
+            | ShaderOpcode::Ifc
+            | ShaderOpcode::Jmpc => Self::Flow {
+                opcode,
+                destination: bits(word, 10, 12) as u16,
+                count: bits(word, 0, 8) as u8,
+                condition: if opcode == ShaderOpcode::Call {
+                    FlowCondition::Always
+                } else {
+                    FlowCondition::Condition {
+                        operation: ConditionOperation::new(bits(word, 22, 2) as u8)?,
+                        reference_x: bits(word, 25, 1) != 0,
+                        reference_y: bits(word, 24, 1) != 0,
+                    }
+                },
+            },
+            ShaderOpcode::Callu | ShaderOpcode::Ifu | ShaderOpcode::Jmpu | ShaderOpcode::Loop => {
+                Self::Flow {
+                    opcode,
+                    destination: bits(word, 10, 12) as u16,
+                    count: bits(word, 0, 8) as u8,
+                    condition: if opcode == ShaderOpcode::Loop {
+                        FlowCondition::IntegerUniform(bits(word, 22, 2) as u8)
+                    } else {
+                        FlowCondition::BoolUniform(bits(word, 22, 4) as u8)
+                    },
+                }
+            }
+            ShaderOpcode::SetEmit => Self::SetEmit {
+                vertex_id: bits(word, 24, 2) as u8,
+                primitive_emit: bits(word, 23, 1) != 0,
+                winding: bits(word, 22, 1) != 0,
+            },
+            ShaderOpcode::Break | ShaderOpcode::Nop | ShaderOpcode::End | ShaderOpcode::Emit => {
+                Self::Simple(opcode)
+            }
+            _ => Self::Arithmetic {
+                opcode,
+                destination: bits(word, 21, 5) as u8,
+                source1: ShaderSource::new_relative(bits(word, 12, 7), bits(word, 19, 2)),
+                source2: ShaderSource::new(bits(word, 7, 5)),
+                descriptor: bits(word, 0, 7) as u8,
+            },
+        })
+    }
+
+    pub fn get_opcode(&self) -> ShaderOpcode {
+        match self {
+            Self::Arithmetic { opcode, .. } => *opcode,
+            Self::Compare { .. } => ShaderOpcode::Cmp,
+            Self::MultiplyAdd { opcode, .. } => *opcode,
+            Self::Flow { opcode, .. } => *opcode,
+            Self::SetEmit { .. } => ShaderOpcode::SetEmit,
+            Self::Simple(opcode) => *opcode,
+        }
+    }
+
+    /// return the sources read by the instruction
+    pub fn get_sources(&self) -> Vec<ShaderSource> {
+        match self {
+            Self::Arithmetic {
+                opcode,
+                source1,
+                source2,
+                ..
+            } => {
+                if opcode.is_unary() {
+                    vec![*source1]
+                } else {
+                    vec![*source1, *source2]
+                }
+            }
+            Self::Compare {
+                source1, source2, ..
+            } => vec![*source1, *source2],
+            Self::MultiplyAdd {
+                source1,
+                source2,
+                source3,
+                ..
+            } => vec![*source1, *source2, *source3],
+            _ => Vec::new(),
+        }
+    }
+
+    /// return the textual representation of the instruction. `operand_descriptors` is the
+    /// operand descriptor table of the shader binary.
+    pub fn disassemble(&self, operand_descriptors: &[u32]) -> String {
+        let get_descriptor = |descriptor: &u8| {
+            OperandDescriptor(
+                operand_descriptors
+                    .get(*descriptor as usize)
+                    .copied()
+                    .unwrap_or(0),
+            )
+        };
+        match self {
+            Self::Arithmetic {
+                opcode,
+                destination,
+                descriptor,
+                ..
+            } => {
+                let descriptor = get_descriptor(descriptor);
+                let destination_name = if *opcode == ShaderOpcode::Mova {
+                    "a0".to_string()
+                } else {
+                    get_destination_name(*destination)
+                };
+                let mut result = format!(
+                    "{} {}",
+                    opcode.get_name(),
+                    descriptor.format_destination(destination_name)
+                );
+                for (source, register) in self.get_sources().iter().enumerate() {
+                    result.push_str(", ");
+                    result.push_str(&descriptor.format_source(source, register));
+                }
+                result
+            }
+            Self::Compare {
+                source1,
+                source2,
+                operation_x,
+                operation_y,
+                descriptor,
+            } => {
+                let descriptor = get_descriptor(descriptor);
+                format!(
+                    "cmp {}, {}, {}, {}",
+                    descriptor.format_source(0, source1),
+                    operation_x.get_name(),
+                    operation_y.get_name(),
+                    descriptor.format_source(1, source2)
+                )
+            }
+            Self::MultiplyAdd {
+                opcode,
+                destination,
+                source1,
+                source2,
+                source3,
+                descriptor,
+            } => {
+                let descriptor = get_descriptor(descriptor);
+                format!(
+                    "{} {}, {}, {}, {}",
+                    opcode.get_name(),
+                    descriptor.format_destination(get_destination_name(*destination)),
+                    descriptor.format_source(0, source1),
+                    descriptor.format_source(1, source2),
+                    descriptor.format_source(2, source3)
+                )
+            }
+            Self::Flow {
+                opcode,
+                destination,
+                count,
+                condition,
+            } => {
+                let mut condition_name = condition.get_name();
+                // jmpu jump when the bool uniform is false if the lowest bit of count is set
+                if *opcode == ShaderOpcode::Jmpu && count & 1 != 0 {
+                    condition_name.insert(0, '!');
+                };
+                let mut result = opcode.get_name().to_string();
+                if !condition_name.is_empty() {
+                    result.push(' ');
+                    result.push_str(&condition_name);
+                    result.push(',');
+                };
+                write!(result, " 0x{:04x}", destination).unwrap();
+                match opcode {
+                    ShaderOpcode::Call
+                    | ShaderOpcode::Callc
+                    | ShaderOpcode::Callu
+                    | ShaderOpcode::Ifc
+                    | ShaderOpcode::Ifu => write!(result, ", {}", count).unwrap(),
+                    _ => (),
+                };
+                result
+            }
+            Self::SetEmit {
+                vertex_id,
+                primitive_emit,
+                winding,
+            } => {
+                let mut result = format!("setemit {}", vertex_id);
+                if *primitive_emit {
+                    result.push_str(", prim");
+                };
+                if *winding {
+                    result.push_str(", inv");
+                };
+                result
+            }
+            Self::Simple(opcode) => opcode.get_name().to_string(),
+        }
+    }
+}
+
+/// Return the disassembly of every instruction of the shader binary, one per line, with the
+/// labels of the program and the name of the float uniforms read by each instruction
+pub fn disassemble_shader(binary: &ShaderBinary, program: &ShaderProgram) -> String {
+    let mut result = String::new();
+    for (offset, word) in binary.instructions.iter().enumerate() {
+        let offset = offset as u32;
+        if offset == program.main_offset
+            && !program
+                .labels
+                .iter()
+                .any(|label| label.location == offset && label.name == "main")
+        {
+            result.push_str("main:\n");
+        };
+        for label in &program.labels {
+            if label.location == offset {
+                writeln!(result, "{}:", label.name).unwrap();
+            };
+        }
+
+        let instruction = match ShaderInstruction::decode(*word) {
+            Some(instruction) => instruction,
+            None => {
+                writeln!(result, "    {:04x}: unknown 0x{:08x}", offset, word).unwrap();
+                continue;
+            }
+        };
+        write!(
+            result,
+            "    {:04x}: {}",
+            offset,
+            instruction.disassemble(&binary.operand_descriptors)
+        )
+        .unwrap();
+
+        let uniform_names: Vec<&str> = instruction
+            .get_sources()
+            .iter()
+            .filter_map(|source| source.get_float_uniform())
+            .filter_map(|uniform| program.get_uniform(uniform as u16 + 0x10))
+            .map(|uniform| uniform.name.as_str())
+            .collect();
+        if !uniform_names.is_empty() {
+            write!(result, " ; {}", uniform_names.join(", ")).unwrap();
+        };
+        result.push('\n');
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// the swizzle reading x, y, z, w in order
+    const IDENTITY: u32 = 0x1b;
+
+    /// disassemble a single instruction word
+    fn disassemble(word: u32, operand_descriptors: &[u32]) -> String {
+        ShaderInstruction::decode(word)
+            .unwrap()
+            .disassemble(operand_descriptors)
+    }
+
+    #[test]
+    fn arithmetic_relative_source() {
+        // add o2, c3[aL], v1, with the opcode 0
+        let word = 0x02 << 21 | 3 << 19 | 0x23 << 12 | 0x01 << 7;
+        let descriptors = [0xf | IDENTITY << 5 | IDENTITY << 14];
+        assert_eq!(disassemble(word, &descriptors), "add o2, c3[aL], v1");
+    }
+
+    #[test]
+    fn mad_relative_source2() {
+        // mad r1.xyz, v0, -c4[a0.x].yyyy, r2, with v0 being the register 0
+        let word = 0b111 << 29 | 0x11 << 24 | 1 << 22 | 0x24 << 10 | 0x12 << 5 | 1;
+        let descriptors = [
+            0,
+            0xe | IDENTITY << 5 | 1 << 13 | 0x55 << 14 | IDENTITY << 23,
+        ];
+        assert_eq!(
+            disassemble(word, &descriptors),
+            "mad r1.xyz, v0, -c4[a0.x].yyyy, r2"
+        );
+    }
+
+    #[test]
+    fn compare() {
+        // cmp c2, lt, ge, r3
+        let word = 0b10111 << 27 | 2 << 24 | 5 << 21 | 0x22 << 12 | 0x13 << 7;
+        let descriptors = [IDENTITY << 5 | IDENTITY << 14];
+        assert_eq!(disassemble(word, &descriptors), "cmp c2, lt, ge, r3");
+    }
+
+    #[test]
+    fn conditional_bool_uniform() {
+        let word = 0x27 << 26 | 3 << 22 | 0x10 << 10 | 4;
+        assert_eq!(disassemble(word, &[]), "ifu b3, 0x0010, 4");
+    }
+
+    #[test]
+    fn conditional_condition_code() {
+        // ifc with cc.x == false and the x operation
+        let word = 0x28 << 26 | 0b0010 << 22 | 0x08 << 10 | 2;
+        assert_eq!(disassemble(word, &[]), "ifc !cc.x, 0x0008, 2");
+    }
+
+    #[test]
+    fn jump_inverted() {
+        let word = 0x2d << 26 | 5 << 22 | 0x20 << 10;
+        assert_eq!(disassemble(word, &[]), "jmpu b5, 0x0020");
+        assert_eq!(disassemble(word | 1, &[]), "jmpu !b5, 0x0020");
+    }
+
+    #[test]
+    fn move_to_address_register() {
+        // mova a0.xy, r0
+        let word = 0x12 << 26 | 0x10 << 12 | 2;
+        let descriptors = [0, 0, 0xc | IDENTITY << 5];
+        assert_eq!(disassemble(word, &descriptors), "mova a0.xy, r0");
+    }
+
+    #[test]
+    fn set_emit() {
+        let word = 0x2b << 26 | 2 << 24 | 1 << 23;
+        assert_eq!(disassemble(word, &[]), "setemit 2, prim");
+    }
+}
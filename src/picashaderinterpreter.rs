@@ -0,0 +1,725 @@
+use crate::shader::{ShaderBinary, ShaderConstantValue, ShaderOutputType, ShaderProgram};
+use crate::model::Object;
+use crate::ShaderUnit;
+use crate::{CompareOperation, ConditionOperation, FlowCondition, RelativeAddress};
+use crate::{OperandDescriptor, PICACommandReader, ShaderInstruction, ShaderOpcode, ShaderSource};
+
+/// the maximum number of instructions run for a vertex, to stop badly formed loops
+const MAX_INSTRUCTIONS: usize = 0x100000;
+
+#[derive(Debug)]
+pub enum ShaderInterpreterError {
+    /// the instruction word and its offset
+    UnknownInstruction(u32, u32),
+    ProgramCounterOutOfRange(u32),
+    TooManyInstructions,
+}
+
+/// The value of the uniform registers of a shader unit
+#[derive(Debug, Clone, PartialEq)]
+pub struct ShaderUniforms {
+    /// c0 to c95
+    pub float: [[f32; 4]; 96],
+    /// i0 to i3, holding the loop count, start and step in x, y and z
+    pub integer: [[u8; 4]; 4],
+    /// b0 to b15
+    pub bool: [bool; 16],
+}
+
+impl Default for ShaderUniforms {
+    fn default() -> Self {
+        Self {
+            float: [[0.0; 4]; 96],
+            integer: [[0; 4]; 4],
+            bool: [false; 16],
+        }
+    }
+}
+
+impl ShaderUniforms {
+    /// return the vertex shader uniforms set by the commands
+    pub fn from_commands(commands: &PICACommandReader) -> Self {
+        let mut uniforms = Self::default();
+        for (first_register, values) in commands.float_uniform.iter().enumerate() {
+            // each register is uploaded in the w, z, y, x order
+            for (offset, chunk) in values.chunks_exact(4).enumerate() {
+                if let Some(register) = uniforms.float.get_mut(first_register + offset) {
+                    *register = [chunk[3], chunk[2], chunk[1], chunk[0]];
+                };
+            }
+        }
+        let setup = commands.get_shader_setup(ShaderUnit::Vertex);
+        uniforms.integer = setup.integer_uniforms;
+        uniforms.bool = setup.bool_uniforms;
+        uniforms
+    }
+
+    /// set the constants of the program, that override the uploaded values
+    pub fn apply_constants(&mut self, program: &ShaderProgram) {
+        for constant in &program.constants {
+            let register = constant.register as usize;
+            match constant.value {
+                ShaderConstantValue::Bool(value) => {
+                    if let Some(uniform) = self.bool.get_mut(register) {
+                        *uniform = value;
+                    };
+                }
+                ShaderConstantValue::Integer(value) => {
+                    if let Some(uniform) = self.integer.get_mut(register) {
+                        *uniform = value;
+                    };
+                }
+                ShaderConstantValue::Float(value) => {
+                    if let Some(uniform) = self.float.get_mut(register) {
+                        *uniform = value;
+                    };
+                }
+            }
+        }
+    }
+}
+
+/// multiply like the PICA200, where zero multiplied by anything, including infinity, is zero
+fn multiply(a: f32, b: f32) -> f32 {
+    if a == 0.0 || b == 0.0 {
+        0.0
+    } else {
+        a * b
+    }
+}
+
+fn dot(a: &[f32], b: &[f32]) -> f32 {
+    a.iter().zip(b.iter()).map(|(a, b)| multiply(*a, *b)).sum()
+}
+
+fn compare(a: f32, b: f32, operation: CompareOperation) -> bool {
+    match operation {
+        CompareOperation::Equal => a == b,
+        CompareOperation::NotEqual => a != b,
+        CompareOperation::LessThan => a < b,
+        CompareOperation::LessEqual => a <= b,
+        CompareOperation::GreaterThan => a > b,
+        CompareOperation::GreaterEqual => a >= b,
+    }
+}
+
+/// An entry of the call stack, for calls, conditionals and loops
+struct CallFrame {
+    /// the offset following the last instruction of the block
+    end: u32,
+    return_offset: u32,
+    is_loop: bool,
+    repeat: u8,
+    loop_start: u32,
+    loop_step: i32,
+}
+
+impl CallFrame {
+    fn new(start: u32, count: u32, return_offset: u32) -> Self {
+        Self {
+            end: start + count,
+            return_offset,
+            is_loop: false,
+            repeat: 0,
+            loop_start: 0,
+            loop_step: 0,
+        }
+    }
+}
+
+/// The state of the shader unit while a vertex is processed
+struct ShaderState<'a> {
+    inputs: &'a [[f32; 4]; 16],
+    uniforms: &'a ShaderUniforms,
+    temporaries: [[f32; 4]; 16],
+    outputs: [[f32; 4]; 16],
+    /// a0.x, a0.y and aL
+    address: [i32; 3],
+    condition: [bool; 2],
+}
+
+impl<'a> ShaderState<'a> {
+    fn read_source(
+        &self,
+        source: &ShaderSource,
+        descriptor: &OperandDescriptor,
+        source_index: usize,
+    ) -> [f32; 4] {
+        let register = match source.register {
+            0x00..=0x0f => self.inputs[source.register as usize],
+            0x10..=0x1f => self.temporaries[source.register as usize - 0x10],
+            _ => {
+                let offset = match source.relative {
+                    RelativeAddress::None => 0,
+                    RelativeAddress::AddressX => self.address[0],
+                    RelativeAddress::AddressY => self.address[1],
+                    RelativeAddress::LoopCounter => self.address[2],
+                };
+                // an out of range uniform read zero
+                let index = source.register as i32 - 0x20 + offset;
+                self.uniforms
+                    .float
+                    .get(index as usize)
+                    .copied()
+                    .unwrap_or([0.0; 4])
+            }
+        };
+        let swizzle = descriptor.get_source_swizzle(source_index);
+        let negate = descriptor.is_source_negated(source_index);
+        let mut result = [0.0; 4];
+        for (value, selector) in result.iter_mut().zip(swizzle.iter()) {
+            *value = if negate {
+                -register[*selector]
+            } else {
+                register[*selector]
+            };
+        }
+        result
+    }
+
+    fn write_destination(
+        &mut self,
+        destination: u8,
+        descriptor: &OperandDescriptor,
+        value: [f32; 4],
+    ) {
+        let register = match destination {
+            0x00..=0x0f => &mut self.outputs[destination as usize],
+            _ => &mut self.temporaries[destination as usize - 0x10],
+        };
+        for (component, enabled) in descriptor.get_destination_mask().iter().enumerate() {
+            if *enabled {
+                register[component] = value[component];
+            };
+        }
+    }
+
+    fn check_condition(&self, condition: &FlowCondition) -> bool {
+        match condition {
+            FlowCondition::Always => true,
+            FlowCondition::Condition {
+                operation,
+                reference_x,
+                reference_y,
+            } => {
+                let x = self.condition[0] == *reference_x;
+                let y = self.condition[1] == *reference_y;
+                match operation {
+                    ConditionOperation::Or => x || y,
+                    ConditionOperation::And => x && y,
+                    ConditionOperation::X => x,
+                    ConditionOperation::Y => y,
+                }
+            }
+            FlowCondition::BoolUniform(id) => self.uniforms.bool[*id as usize],
+            FlowCondition::IntegerUniform(_) => true,
+        }
+    }
+}
+
+/// Run a vertex shader on the CPU. Computations are done with f32, while the hardware use 24 bit
+/// floats, so the results may slightly differ.
+pub struct ShaderInterpreter<'a> {
+    binary: &'a ShaderBinary,
+    program: &'a ShaderProgram,
+    uniforms: ShaderUniforms,
+}
+
+impl<'a> ShaderInterpreter<'a> {
+    /// create an interpreter for the program, with the given uniforms. The constants of the
+    /// program are applied to the uniforms.
+    pub fn new(
+        binary: &'a ShaderBinary,
+        program: &'a ShaderProgram,
+        mut uniforms: ShaderUniforms,
+    ) -> Self {
+        uniforms.apply_constants(program);
+        Self {
+            binary,
+            program,
+            uniforms,
+        }
+    }
+
+    pub fn get_uniforms(&self) -> &ShaderUniforms {
+        &self.uniforms
+    }
+
+    /// run the program for a vertex with the given input registers (v0 to v15), and return the
+    /// output registers (o0 to o15)
+    pub fn run(&self, inputs: &[[f32; 4]; 16]) -> Result<[[f32; 4]; 16], ShaderInterpreterError> {
+        let mut state = ShaderState {
+            inputs,
+            uniforms: &self.uniforms,
+            temporaries: [[0.0; 4]; 16],
+            outputs: [[0.0; 4]; 16],
+            address: [0; 3],
+            condition: [false; 2],
+        };
+        let mut call_stack: Vec<CallFrame> = Vec::new();
+        let mut program_counter = self.program.main_offset;
+
+        for _ in 0..MAX_INSTRUCTIONS {
+            // return from the blocks that are finished
+            while let Some(frame) = call_stack.last_mut() {
+                if frame.end != program_counter {
+                    break;
+                };
+                if frame.is_loop && frame.repeat > 0 {
+                    frame.repeat -= 1;
+                    state.address[2] += frame.loop_step;
+                    program_counter = frame.loop_start;
+                    break;
+                };
+                program_counter = frame.return_offset;
+                call_stack.pop();
+            }
+
+            let word = *self
+                .binary
+                .instructions
+                .get(program_counter as usize)
+                .ok_or(ShaderInterpreterError::ProgramCounterOutOfRange(
+                    program_counter,
+                ))?;
+            let instruction = ShaderInstruction::decode(word).ok_or(
+                ShaderInterpreterError::UnknownInstruction(word, program_counter),
+            )?;
+            let next = program_counter + 1;
+            program_counter = next;
+
+            match instruction {
+                ShaderInstruction::Arithmetic {
+                    opcode,
+                    destination,
+                    source1,
+                    source2,
+                    descriptor,
+                } => {
+                    let descriptor = self.get_descriptor(descriptor);
+                    let a = state.read_source(&source1, &descriptor, 0);
+                    let b = state.read_source(&source2, &descriptor, 1);
+                    let result = match opcode {
+                        ShaderOpcode::Add => [a[0] + b[0], a[1] + b[1], a[2] + b[2], a[3] + b[3]],
+                        ShaderOpcode::Mul => [
+                            multiply(a[0], b[0]),
+                            multiply(a[1], b[1]),
+                            multiply(a[2], b[2]),
+                            multiply(a[3], b[3]),
+                        ],
+                        ShaderOpcode::Dp3 => [dot(&a[0..3], &b[0..3]); 4],
+                        ShaderOpcode::Dp4 => [dot(&a, &b); 4],
+                        ShaderOpcode::Dph | ShaderOpcode::Dphi => {
+                            [dot(&a[0..3], &b[0..3]) + b[3]; 4]
+                        }
+                        ShaderOpcode::Dst | ShaderOpcode::Dsti => {
+                            [1.0, multiply(a[1], b[1]), a[2], b[3]]
+                        }
+                        ShaderOpcode::Ex2 => [a[0].exp2(); 4],
+                        ShaderOpcode::Lg2 => [a[0].log2(); 4],
+                        ShaderOpcode::Litp => {
+                            state.condition = [a[0] >= 0.0, a[3] >= 0.0];
+                            [
+                                a[0].max(0.0),
+                                a[1].clamp(-127.9961, 127.9961),
+                                a[2],
+                                a[3].max(0.0),
+                            ]
+                        }
+                        ShaderOpcode::Sge | ShaderOpcode::Sgei => {
+                            let mut result = [0.0; 4];
+                            for (component, value) in result.iter_mut().enumerate() {
+                                *value = if a[component] >= b[component] {
+                                    1.0
+                                } else {
+                                    0.0
+                                };
+                            }
+                            result
+                        }
+                        ShaderOpcode::Slt | ShaderOpcode::Slti => {
+                            let mut result = [0.0; 4];
+                            for (component, value) in result.iter_mut().enumerate() {
+                                *value = if a[component] < b[component] {
+                                    1.0
+                                } else {
+                                    0.0
+                                };
+                            }
+                            result
+                        }
+                        ShaderOpcode::Flr => {
+                            [a[0].floor(), a[1].floor(), a[2].floor(), a[3].floor()]
+                        }
+                        ShaderOpcode::Max => {
+                            let mut result = a;
+                            for (value, other) in result.iter_mut().zip(b.iter()) {
+                                *value = if *value > *other { *value } else { *other };
+                            }
+                            result
+                        }
+                        ShaderOpcode::Min => {
+                            let mut result = a;
+                            for (value, other) in result.iter_mut().zip(b.iter()) {
+                                *value = if *value < *other { *value } else { *other };
+                            }
+                            result
+                        }
+                        ShaderOpcode::Rcp => [1.0 / a[0]; 4],
+                        ShaderOpcode::Rsq => [1.0 / a[0].sqrt(); 4],
+                        ShaderOpcode::Mova => {
+                            let mask = descriptor.get_destination_mask();
+                            for component in 0..2 {
+                                if mask[component] {
+                                    state.address[component] = a[component] as i32;
+                                };
+                            }
+                            continue;
+                        }
+                        _ => a,
+                    };
+                    state.write_destination(destination, &descriptor, result);
+                }
+                ShaderInstruction::Compare {
+                    source1,
+                    source2,
+                    operation_x,
+                    operation_y,
+                    descriptor,
+                } => {
+                    let descriptor = self.get_descriptor(descriptor);
+                    let a = state.read_source(&source1, &descriptor, 0);
+                    let b = state.read_source(&source2, &descriptor, 1);
+                    state.condition = [
+                        compare(a[0], b[0], operation_x),
+                        compare(a[1], b[1], operation_y),
+                    ];
+                }
+                ShaderInstruction::MultiplyAdd {
+                    destination,
+                    source1,
+                    source2,
+                    source3,
+                    descriptor,
+                    ..
+                } => {
+                    let descriptor = self.get_descriptor(descriptor);
+                    let a = state.read_source(&source1, &descriptor, 0);
+                    let b = state.read_source(&source2, &descriptor, 1);
+                    let c = state.read_source(&source3, &descriptor, 2);
+                    let mut result = [0.0; 4];
+                    for (component, value) in result.iter_mut().enumerate() {
+                        *value = multiply(a[component], b[component]) + c[component];
+                    }
+                    state.write_destination(destination, &descriptor, result);
+                }
+                ShaderInstruction::Flow {
+                    opcode,
+                    destination,
+                    count,
+                    condition,
+                } => {
+                    let destination = destination as u32;
+                    let count = count as u32;
+                    let mut taken = state.check_condition(&condition);
+                    // jmpu jump when the bool uniform is false if the lowest bit of count is set
+                    if opcode == ShaderOpcode::Jmpu && count & 1 != 0 {
+                        taken = !taken;
+                    };
+                    match opcode {
+                        ShaderOpcode::Breakc if taken => {
+                            Self::break_loop(&mut call_stack, &mut program_counter)
+                        }
+                        ShaderOpcode::Call | ShaderOpcode::Callc | ShaderOpcode::Callu if taken => {
+                            call_stack.push(CallFrame::new(destination, count, next));
+                            program_counter = destination;
+                        }
+                        ShaderOpcode::Ifc | ShaderOpcode::Ifu => {
+                            // the else block start at destination and contain count instructions
+                            let end = destination + count;
+                            if taken {
+                                call_stack.push(CallFrame::new(
+                                    next,
+                                    destination.saturating_sub(next),
+                                    end,
+                                ));
+                            } else {
+                                call_stack.push(CallFrame::new(destination, count, end));
+                                program_counter = destination;
+                            };
+                        }
+                        ShaderOpcode::Loop => {
+                            let id = match condition {
+                                FlowCondition::IntegerUniform(id) => id as usize,
+                                _ => 0,
+                            };
+                            let [repeat, start, step, _] = state.uniforms.integer[id];
+                            state.address[2] = start as i32;
+                            call_stack.push(CallFrame {
+                                end: destination + 1,
+                                return_offset: destination + 1,
+                                is_loop: true,
+                                repeat,
+                                loop_start: next,
+                                loop_step: step as i8 as i32,
+                            });
+                        }
+                        ShaderOpcode::Jmpc | ShaderOpcode::Jmpu if taken => {
+                            program_counter = destination
+                        }
+                        _ => (),
+                    }
+                }
+                ShaderInstruction::Simple(ShaderOpcode::End) => return Ok(state.outputs),
+                ShaderInstruction::Simple(ShaderOpcode::Break) => {
+                    Self::break_loop(&mut call_stack, &mut program_counter)
+                }
+                // emit and setemit are only used by geometry shaders
+                ShaderInstruction::Simple(_) | ShaderInstruction::SetEmit { .. } => (),
+            }
+        }
+        Err(ShaderInterpreterError::TooManyInstructions)
+    }
+
+    /// run the program for every vertex of the object, with the input registers loaded from its
+    /// vertex buffer, and return the output registers of each vertex, in the order of
+    /// `object.vertices`. The interpreter is usually created with `object.shader_uniforms`.
+    pub fn run_object(
+        &self,
+        object: &Object,
+    ) -> Result<Vec<[[f32; 4]; 16]>, ShaderInterpreterError> {
+        object
+            .shader_inputs
+            .iter()
+            .map(|inputs| self.run(inputs))
+            .collect()
+    }
+
+    /// return the value written by the program for an output attribute, in the registers returned
+    /// by `run`
+    pub fn get_output(
+        &self,
+        outputs: &[[f32; 4]; 16],
+        output_type: ShaderOutputType,
+    ) -> Option<[f32; 4]> {
+        self.program
+            .outputs
+            .iter()
+            .find(|output| output.output_type == output_type)
+            .and_then(|output| outputs.get(output.register as usize))
+            .copied()
+    }
+
+    fn get_descriptor(&self, descriptor: u8) -> OperandDescriptor {
+        OperandDescriptor(
+            self.binary
+                .operand_descriptors
+                .get(descriptor as usize)
+                .copied()
+                .unwrap_or(0),
+        )
+    }
+
+    /// leave the innermost loop
+    fn break_loop(call_stack: &mut Vec<CallFrame>, program_counter: &mut u32) {
+        while let Some(frame) = call_stack.pop() {
+            if frame.is_loop {
+                *program_counter = frame.return_offset;
+                break;
+            };
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shader::ShaderProgramType;
+
+    /// write all the components, with the sources read as xyzw
+    const DESCRIPTOR_XYZW: u32 = 0xf | 0x1b << 5 | 0x1b << 14 | 0x1b << 23;
+    /// write the x and y components, with the sources read as xyzw
+    const DESCRIPTOR_XY: u32 = 0xc | 0x1b << 5 | 0x1b << 14 | 0x1b << 23;
+
+    const RELATIVE_X: u32 = 1;
+    const RELATIVE_Y: u32 = 2;
+    const RELATIVE_LOOP: u32 = 3;
+
+    fn arithmetic(opcode: u32, destination: u32, source1: u32, relative: u32, source2: u32) -> u32 {
+        opcode << 26 | destination << 21 | relative << 19 | source1 << 12 | source2 << 7
+    }
+
+    fn mov(destination: u32, source: u32, relative: u32) -> u32 {
+        arithmetic(0x13, destination, source, relative, 0)
+    }
+
+    fn add(destination: u32, source1: u32, source2: u32) -> u32 {
+        arithmetic(0x00, destination, source1, 0, source2)
+    }
+
+    fn cmp(source1: u32, source2: u32, operation_x: u32, operation_y: u32) -> u32 {
+        0x2e << 26 | operation_x << 24 | operation_y << 21 | source1 << 12 | source2 << 7
+    }
+
+    /// a flow instruction, with `condition` being the bits from 22 to 25
+    fn flow(opcode: u32, condition: u32, destination: u32, count: u32) -> u32 {
+        opcode << 26 | condition << 22 | destination << 10 | count
+    }
+
+    const END: u32 = 0x22 << 26;
+    /// the condition of flow instructions that is true when cc.x is set
+    const IF_X: u32 = 0b1010;
+
+    fn run(
+        instructions: &[u32],
+        uniforms: ShaderUniforms,
+        inputs: &[[f32; 4]; 16],
+    ) -> [[f32; 4]; 16] {
+        let binary = ShaderBinary {
+            instructions: instructions.to_vec(),
+            operand_descriptors: vec![DESCRIPTOR_XYZW, DESCRIPTOR_XY],
+            programs: Vec::new(),
+        };
+        let program = ShaderProgram {
+            program_type: ShaderProgramType::Vertex,
+            merge_output_maps: false,
+            main_offset: 0,
+            end_offset: instructions.len() as u32 - 1,
+            input_register_mask: 0,
+            output_register_mask: 0,
+            geometry_shader_type: 0,
+            fixed_vertex_start_register: 0,
+            fixed_vertex_count: 0,
+            variable_vertex_count: 0,
+            constants: Vec::new(),
+            labels: Vec::new(),
+            outputs: Vec::new(),
+            uniforms: Vec::new(),
+        };
+        ShaderInterpreter::new(&binary, &program, uniforms)
+            .run(inputs)
+            .unwrap()
+    }
+
+    /// uniforms where cN is filled with `values[N]`
+    fn float_uniforms(values: &[f32]) -> ShaderUniforms {
+        let mut uniforms = ShaderUniforms::default();
+        for (register, value) in uniforms.float.iter_mut().zip(values.iter()) {
+            *register = [*value; 4];
+        }
+        uniforms
+    }
+
+    #[test]
+    fn loop_runs_count_plus_one_times() {
+        // r0 += c1[aL]
+        let program = [
+            flow(0x29, 0, 1, 0),
+            arithmetic(0x00, 0x10, 0x21, RELATIVE_LOOP, 0x10),
+            mov(0, 0x10, 0),
+            END,
+        ];
+        let mut uniforms = float_uniforms(&[0.0, 1.0, 2.0, 4.0, 8.0, 16.0]);
+
+        // 4 iterations, aL going from 0 to 3, reading c1 to c4
+        uniforms.integer[0] = [3, 0, 1, 0];
+        let outputs = run(&program, uniforms.clone(), &[[0.0; 4]; 16]);
+        assert_eq!(outputs[0], [15.0; 4]);
+
+        // 2 iterations, aL being 1 then 3, reading c2 and c4
+        uniforms.integer[0] = [1, 1, 2, 0];
+        let outputs = run(&program, uniforms, &[[0.0; 4]; 16]);
+        assert_eq!(outputs[0], [10.0; 4]);
+    }
+
+    #[test]
+    fn ifu_else_block() {
+        let program = [
+            flow(0x27, 0, 3, 2),
+            mov(0, 0x20, 0),
+            mov(1, 0x20, 0),
+            mov(0, 0x21, 0),
+            mov(1, 0x21, 0),
+            mov(2, 0x22, 0),
+            END,
+        ];
+        let mut uniforms = float_uniforms(&[1.0, 2.0, 3.0]);
+
+        uniforms.bool[0] = true;
+        let outputs = run(&program, uniforms.clone(), &[[0.0; 4]; 16]);
+        assert_eq!(outputs[0..3], [[1.0; 4], [1.0; 4], [3.0; 4]]);
+
+        uniforms.bool[0] = false;
+        let outputs = run(&program, uniforms, &[[0.0; 4]; 16]);
+        assert_eq!(outputs[0..3], [[2.0; 4], [2.0; 4], [3.0; 4]]);
+    }
+
+    #[test]
+    fn ifc_else_block() {
+        // if c0.x < v0.x
+        let program = [
+            cmp(0x20, 0x00, 2, 0),
+            flow(0x28, IF_X, 3, 1),
+            mov(0, 0x21, 0),
+            mov(0, 0x22, 0),
+            mov(1, 0x22, 0),
+            END,
+        ];
+        let uniforms = float_uniforms(&[1.0, 2.0, 3.0]);
+        let mut inputs = [[0.0; 4]; 16];
+
+        inputs[0] = [5.0; 4];
+        let outputs = run(&program, uniforms.clone(), &inputs);
+        assert_eq!(outputs[0..2], [[2.0; 4], [3.0; 4]]);
+
+        inputs[0] = [0.0; 4];
+        let outputs = run(&program, uniforms, &inputs);
+        assert_eq!(outputs[0..2], [[3.0; 4], [3.0; 4]]);
+    }
+
+    #[test]
+    fn call_returns() {
+        let program = [
+            flow(0x24, 0, 3, 2),
+            mov(0, 0x10, 0),
+            END,
+            mov(0x10, 0x20, 0),
+            add(0x10, 0x20, 0x10),
+        ];
+        let outputs = run(&program, float_uniforms(&[1.5]), &[[0.0; 4]; 16]);
+        assert_eq!(outputs[0], [3.0; 4]);
+    }
+
+    #[test]
+    fn breakc_leaves_loop() {
+        // add c0 to r0 until c1.x <= r0.x, in a loop of 10 iterations
+        let program = [
+            flow(0x29, 0, 3, 0),
+            add(0x10, 0x20, 0x10),
+            cmp(0x21, 0x10, 3, 0),
+            flow(0x23, IF_X, 0, 0),
+            mov(0, 0x10, 0),
+            END,
+        ];
+        let mut uniforms = float_uniforms(&[1.0, 3.0]);
+        uniforms.integer[0] = [9, 0, 1, 0];
+        let outputs = run(&program, uniforms, &[[0.0; 4]; 16]);
+        assert_eq!(outputs[0], [3.0; 4]);
+    }
+
+    #[test]
+    fn mova_relative_read() {
+        let program = [
+            // mova a0.xy, c0, with the second operand descriptor
+            arithmetic(0x12, 0, 0x20, 0, 0) | 1,
+            mov(0, 0x21, RELATIVE_X),
+            mov(1, 0x21, RELATIVE_Y),
+            END,
+        ];
+        let mut uniforms = float_uniforms(&[0.0, 10.0, 20.0, 30.0]);
+        uniforms.float[0] = [2.0, 1.0, 0.0, 0.0];
+        let outputs = run(&program, uniforms, &[[0.0; 4]; 16]);
+        assert_eq!(outputs[0], [30.0; 4]);
+        assert_eq!(outputs[1], [20.0; 4]);
+    }
+}
@@ -0,0 +1,173 @@
+use crate::deserialize::{
+    read_f32_le, read_referenced_null_terminated_ascii_string, read_u16_le, read_u32_le, read_u8,
+    read_vector3_f32,
+};
+use crate::{Matrix3x4, Vector3};
+use std::io;
+use std::io::{Read, Seek, SeekFrom};
+
+#[derive(Debug)]
+pub enum CameraError {
+    IOError(io::Error, &'static str),
+    NullReference(&'static str),
+    InvalidViewType(u8),
+    InvalidProjectionType(u8),
+}
+
+fn ioe(err: io::Error, content: &'static str) -> CameraError {
+    CameraError::IOError(err, content)
+}
+
+/// How the orientation of a camera is given
+#[derive(Debug, Clone, PartialEq)]
+pub enum CameraView {
+    /// the camera look at a target, and is rotated around the view axis by `twist` radian
+    Aim { target: Vector3, twist: f32 },
+    /// the camera look at a target, with the given up vector
+    LookAt { target: Vector3, up_vector: Vector3 },
+    /// the camera is rotated by euler angles, in radian, along the X, Y then Z axis
+    Rotate { rotation: Vector3 },
+}
+
+impl CameraView {
+    fn read<F: Read>(file: &mut F, view_type: u8) -> Result<Self, CameraError> {
+        Ok(match view_type {
+            0 => Self::Aim {
+                target: Vector3::from(read_vector3_f32(file).map_err(|e| ioe(e, "aim target"))?),
+                twist: read_f32_le(file).map_err(|e| ioe(e, "aim twist"))?,
+            },
+            1 => Self::LookAt {
+                target: Vector3::from(
+                    read_vector3_f32(file).map_err(|e| ioe(e, "look at target"))?,
+                ),
+                up_vector: Vector3::from(
+                    read_vector3_f32(file).map_err(|e| ioe(e, "look at up vector"))?,
+                ),
+            },
+            2 => Self::Rotate {
+                rotation: Vector3::from(
+                    read_vector3_f32(file).map_err(|e| ioe(e, "view rotation"))?,
+                ),
+            },
+            _ => return Err(CameraError::InvalidViewType(view_type)),
+        })
+    }
+}
+
+/// How the view of a camera is projected on the screen
+#[derive(Debug, Clone, PartialEq)]
+pub enum CameraProjection {
+    /// `fov_y` is the vertical field of view, in radian
+    Perspective {
+        near: f32,
+        far: f32,
+        aspect_ratio: f32,
+        fov_y: f32,
+    },
+    /// `height` is the height of the visible area
+    Orthogonal {
+        near: f32,
+        far: f32,
+        aspect_ratio: f32,
+        height: f32,
+    },
+}
+
+impl CameraProjection {
+    fn read<F: Read>(file: &mut F, projection_type: u8) -> Result<Self, CameraError> {
+        if projection_type > 1 {
+            return Err(CameraError::InvalidProjectionType(projection_type));
+        };
+        let near = read_f32_le(file).map_err(|e| ioe(e, "near clip"))?;
+        let far = read_f32_le(file).map_err(|e| ioe(e, "far clip"))?;
+        let aspect_ratio = read_f32_le(file).map_err(|e| ioe(e, "aspect ratio"))?;
+        Ok(if projection_type == 0 {
+            Self::Perspective {
+                near,
+                far,
+                aspect_ratio,
+                fov_y: read_f32_le(file).map_err(|e| ioe(e, "field of view"))?,
+            }
+        } else {
+            Self::Orthogonal {
+                near,
+                far,
+                aspect_ratio,
+                height: read_f32_le(file).map_err(|e| ioe(e, "height"))?,
+            }
+        })
+    }
+
+    pub fn get_near(&self) -> f32 {
+        match self {
+            Self::Perspective { near, .. } | Self::Orthogonal { near, .. } => *near,
+        }
+    }
+
+    pub fn get_far(&self) -> f32 {
+        match self {
+            Self::Perspective { far, .. } | Self::Orthogonal { far, .. } => *far,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Camera {
+    pub name: String,
+    pub scale: Vector3,
+    /// rotation in radian, along the X, Y then Z axis
+    pub rotation: Vector3,
+    pub translation: Vector3,
+    pub flags: u16,
+    pub w_scale: f32,
+    pub view: CameraView,
+    pub projection: CameraProjection,
+    pub meta_data_pointer_offset: u32,
+}
+
+impl Camera {
+    pub fn read<F: Read + Seek>(file: &mut F) -> Result<Self, CameraError> {
+        let name = read_referenced_null_terminated_ascii_string(file)
+            .map_err(|e| ioe(e, "camera name"))?
+            .ok_or(CameraError::NullReference("camera name"))?;
+
+        let scale = Vector3::from(read_vector3_f32(file).map_err(|e| ioe(e, "scale"))?);
+        let rotation = Vector3::from(read_vector3_f32(file).map_err(|e| ioe(e, "rotation"))?);
+        let translation = Vector3::from(read_vector3_f32(file).map_err(|e| ioe(e, "translation"))?);
+
+        let view_type = read_u8(file).map_err(|e| ioe(e, "view type"))?;
+        let projection_type = read_u8(file).map_err(|e| ioe(e, "projection type"))?;
+        let flags = read_u16_le(file).map_err(|e| ioe(e, "flags"))?;
+        let w_scale = read_f32_le(file).map_err(|e| ioe(e, "w scale"))?;
+
+        let view_offset = read_u32_le(file).map_err(|e| ioe(e, "view offset"))?;
+        let projection_offset = read_u32_le(file).map_err(|e| ioe(e, "projection offset"))?;
+        let meta_data_pointer_offset =
+            read_u32_le(file).map_err(|e| ioe(e, "meta data pointer offset"))?;
+
+        file.seek(SeekFrom::Start(view_offset as u64))
+            .map_err(|e| ioe(e, "view"))?;
+        let view = CameraView::read(file, view_type)?;
+
+        file.seek(SeekFrom::Start(projection_offset as u64))
+            .map_err(|e| ioe(e, "projection"))?;
+        let projection = CameraProjection::read(file, projection_type)?;
+
+        Ok(Self {
+            name,
+            scale,
+            rotation,
+            translation,
+            flags,
+            w_scale,
+            view,
+            projection,
+            meta_data_pointer_offset,
+        })
+    }
+
+    /// the transform of the camera relative to the scene
+    pub fn get_transform(&self) -> Matrix3x4 {
+        Matrix3x4::from_srt_euler(self.scale, self.rotation, self.translation)
+    }
+}
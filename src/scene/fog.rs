@@ -0,0 +1,99 @@
+use crate::deserialize::{
+    read_f32_le, read_referenced_null_terminated_ascii_string, read_rgba, read_u16_le, read_u32_le,
+    read_u8, read_vector3_f32,
+};
+use crate::{Matrix3x4, Vector3};
+use std::io;
+use std::io::{Read, Seek};
+
+#[derive(Debug)]
+pub enum FogError {
+    IOError(io::Error, &'static str),
+    NullReference(&'static str),
+    InvalidFogType(u8),
+}
+
+fn ioe(err: io::Error, content: &'static str) -> FogError {
+    FogError::IOError(err, content)
+}
+
+/// How the density of the fog grow with the depth
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FogType {
+    Linear,
+    Exponent,
+    ExponentSquare,
+}
+
+impl FogType {
+    pub fn new(value: u8) -> Option<Self> {
+        Some(match value {
+            0 => Self::Linear,
+            1 => Self::Exponent,
+            2 => Self::ExponentSquare,
+            _ => return None,
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Fog {
+    pub name: String,
+    pub scale: Vector3,
+    /// rotation in radian, along the X, Y then Z axis
+    pub rotation: Vector3,
+    pub translation: Vector3,
+    pub fog_type: FogType,
+    pub flags: u16,
+    pub color: [u8; 4],
+    /// the depth where the fog start
+    pub min_depth: f32,
+    /// the depth where the fog is the thickest
+    pub max_depth: f32,
+    pub density: f32,
+    pub meta_data_pointer_offset: u32,
+}
+
+impl Fog {
+    pub fn read<F: Read + Seek>(file: &mut F) -> Result<Self, FogError> {
+        let name = read_referenced_null_terminated_ascii_string(file)
+            .map_err(|e| ioe(e, "fog name"))?
+            .ok_or(FogError::NullReference("fog name"))?;
+
+        let scale = Vector3::from(read_vector3_f32(file).map_err(|e| ioe(e, "scale"))?);
+        let rotation = Vector3::from(read_vector3_f32(file).map_err(|e| ioe(e, "rotation"))?);
+        let translation = Vector3::from(read_vector3_f32(file).map_err(|e| ioe(e, "translation"))?);
+
+        let fog_type_value = read_u8(file).map_err(|e| ioe(e, "fog type"))?;
+        let fog_type =
+            FogType::new(fog_type_value).ok_or(FogError::InvalidFogType(fog_type_value))?;
+        read_u8(file).map_err(|e| ioe(e, "padding"))?;
+        let flags = read_u16_le(file).map_err(|e| ioe(e, "flags"))?;
+
+        let color = read_rgba(file).map_err(|e| ioe(e, "color"))?;
+        let min_depth = read_f32_le(file).map_err(|e| ioe(e, "min depth"))?;
+        let max_depth = read_f32_le(file).map_err(|e| ioe(e, "max depth"))?;
+        let density = read_f32_le(file).map_err(|e| ioe(e, "density"))?;
+        let meta_data_pointer_offset =
+            read_u32_le(file).map_err(|e| ioe(e, "meta data pointer offset"))?;
+
+        Ok(Self {
+            name,
+            scale,
+            rotation,
+            translation,
+            fog_type,
+            flags,
+            color,
+            min_depth,
+            max_depth,
+            density,
+            meta_data_pointer_offset,
+        })
+    }
+
+    /// the transform of the fog relative to the scene
+    pub fn get_transform(&self) -> Matrix3x4 {
+        Matrix3x4::from_srt_euler(self.scale, self.rotation, self.translation)
+    }
+}
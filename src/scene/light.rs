@@ -0,0 +1,217 @@
+use crate::deserialize::{
+    read_f32_le, read_referenced_null_terminated_ascii_string, read_rgba, read_u16_le, read_u32_le,
+    read_u8, read_vector, read_vector3_f32,
+};
+use crate::model::LUTReference;
+use crate::{Matrix3x4, Vector3};
+use std::io;
+use std::io::{Read, Seek, SeekFrom};
+
+#[derive(Debug)]
+pub enum LightError {
+    IOError(io::Error, &'static str),
+    NullReference(&'static str),
+    InvalidLightType(u8),
+}
+
+fn ioe(err: io::Error, content: &'static str) -> LightError {
+    LightError::IOError(err, content)
+}
+
+fn read_color_f32<F: Read>(file: &mut F) -> Result<[f32; 4], io::Error> {
+    let mut color = [0.0; 4];
+    read_vector(file, read_f32_le, &mut color)?;
+    Ok(color)
+}
+
+/// The shape of the light emitted by a vertex or fragment light
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LightShape {
+    Directional,
+    Point,
+    Spot,
+}
+
+/// How a light is computed
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LightType {
+    Hemisphere,
+    Ambient,
+    /// computed for each vertex by the vertex shader
+    Vertex(LightShape),
+    /// computed for each pixel by the fragment lighting unit
+    Fragment(LightShape),
+}
+
+impl LightType {
+    pub fn new(value: u8) -> Option<Self> {
+        // the two lowest bits give the shape of vertex and fragment lights
+        let shape = match value & 0x3 {
+            0 => LightShape::Directional,
+            1 => LightShape::Point,
+            _ => LightShape::Spot,
+        };
+        Some(match value {
+            0 => Self::Hemisphere,
+            1 => Self::Ambient,
+            4..=6 => Self::Vertex(shape),
+            8..=10 => Self::Fragment(shape),
+            _ => return None,
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct HemisphereLight {
+    pub ground_color: [f32; 4],
+    pub sky_color: [f32; 4],
+    pub direction: Vector3,
+    /// the interpolation between the ground and the sky color
+    pub lerp_factor: f32,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct AmbientLight {
+    pub color: [f32; 4],
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct VertexLight {
+    pub ambient_color: [f32; 4],
+    pub diffuse_color: [f32; 4],
+    pub specular0_color: [f32; 4],
+    pub specular1_color: [f32; 4],
+    pub direction: Vector3,
+    pub attenuation_constant: f32,
+    pub attenuation_linear: f32,
+    pub attenuation_quadratic: f32,
+    pub spot_exponent: f32,
+    /// the angle of the spot cone, in radian
+    pub spot_cut_off_angle: f32,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FragmentLight {
+    pub ambient_color: [u8; 4],
+    pub diffuse_color: [u8; 4],
+    pub specular0_color: [u8; 4],
+    pub specular1_color: [u8; 4],
+    pub direction: Vector3,
+    /// the look up table giving the attenuation from the distance to the light
+    pub distance_lut: LUTReference,
+    pub attenuation_start: f32,
+    pub attenuation_end: f32,
+    /// the look up table giving the attenuation of a spot light from the angle to its direction
+    pub angle_lut: LUTReference,
+    pub angle_lut_input: u32,
+    pub angle_lut_scale: f32,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum LightContent {
+    Hemisphere(HemisphereLight),
+    Ambient(AmbientLight),
+    Vertex(VertexLight),
+    Fragment(FragmentLight),
+}
+
+impl LightContent {
+    fn read<F: Read + Seek>(file: &mut F, light_type: LightType) -> Result<Self, LightError> {
+        Ok(match light_type {
+            LightType::Hemisphere => Self::Hemisphere(HemisphereLight {
+                ground_color: read_color_f32(file).map_err(|e| ioe(e, "ground color"))?,
+                sky_color: read_color_f32(file).map_err(|e| ioe(e, "sky color"))?,
+                direction: Vector3::from(read_vector3_f32(file).map_err(|e| ioe(e, "direction"))?),
+                lerp_factor: read_f32_le(file).map_err(|e| ioe(e, "lerp factor"))?,
+            }),
+            LightType::Ambient => Self::Ambient(AmbientLight {
+                color: read_color_f32(file).map_err(|e| ioe(e, "ambient color"))?,
+            }),
+            LightType::Vertex(_) => Self::Vertex(VertexLight {
+                ambient_color: read_color_f32(file).map_err(|e| ioe(e, "ambient color"))?,
+                diffuse_color: read_color_f32(file).map_err(|e| ioe(e, "diffuse color"))?,
+                specular0_color: read_color_f32(file).map_err(|e| ioe(e, "specular 0 color"))?,
+                specular1_color: read_color_f32(file).map_err(|e| ioe(e, "specular 1 color"))?,
+                direction: Vector3::from(read_vector3_f32(file).map_err(|e| ioe(e, "direction"))?),
+                attenuation_constant: read_f32_le(file)
+                    .map_err(|e| ioe(e, "constant attenuation"))?,
+                attenuation_linear: read_f32_le(file).map_err(|e| ioe(e, "linear attenuation"))?,
+                attenuation_quadratic: read_f32_le(file)
+                    .map_err(|e| ioe(e, "quadratic attenuation"))?,
+                spot_exponent: read_f32_le(file).map_err(|e| ioe(e, "spot exponent"))?,
+                spot_cut_off_angle: read_f32_le(file).map_err(|e| ioe(e, "spot cut off angle"))?,
+            }),
+            LightType::Fragment(_) => Self::Fragment(FragmentLight {
+                ambient_color: read_rgba(file).map_err(|e| ioe(e, "ambient color"))?,
+                diffuse_color: read_rgba(file).map_err(|e| ioe(e, "diffuse color"))?,
+                specular0_color: read_rgba(file).map_err(|e| ioe(e, "specular 0 color"))?,
+                specular1_color: read_rgba(file).map_err(|e| ioe(e, "specular 1 color"))?,
+                direction: Vector3::from(read_vector3_f32(file).map_err(|e| ioe(e, "direction"))?),
+                distance_lut: LUTReference::read(file).map_err(|e| ioe(e, "distance lut"))?,
+                attenuation_start: read_f32_le(file).map_err(|e| ioe(e, "attenuation start"))?,
+                attenuation_end: read_f32_le(file).map_err(|e| ioe(e, "attenuation end"))?,
+                angle_lut: LUTReference::read(file).map_err(|e| ioe(e, "angle lut"))?,
+                angle_lut_input: read_u32_le(file).map_err(|e| ioe(e, "angle lut input"))?,
+                angle_lut_scale: read_f32_le(file).map_err(|e| ioe(e, "angle lut scale"))?,
+            }),
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Light {
+    pub name: String,
+    pub scale: Vector3,
+    /// rotation in radian, along the X, Y then Z axis
+    pub rotation: Vector3,
+    pub translation: Vector3,
+    pub light_type: LightType,
+    pub flags: u16,
+    pub is_enabled: bool,
+    pub content: LightContent,
+    pub meta_data_pointer_offset: u32,
+}
+
+impl Light {
+    pub fn read<F: Read + Seek>(file: &mut F) -> Result<Self, LightError> {
+        let name = read_referenced_null_terminated_ascii_string(file)
+            .map_err(|e| ioe(e, "light name"))?
+            .ok_or(LightError::NullReference("light name"))?;
+
+        let scale = Vector3::from(read_vector3_f32(file).map_err(|e| ioe(e, "scale"))?);
+        let rotation = Vector3::from(read_vector3_f32(file).map_err(|e| ioe(e, "rotation"))?);
+        let translation = Vector3::from(read_vector3_f32(file).map_err(|e| ioe(e, "translation"))?);
+
+        let light_type_value = read_u8(file).map_err(|e| ioe(e, "light type"))?;
+        let light_type = LightType::new(light_type_value)
+            .ok_or(LightError::InvalidLightType(light_type_value))?;
+        read_u8(file).map_err(|e| ioe(e, "padding"))?;
+        let flags = read_u16_le(file).map_err(|e| ioe(e, "flags"))?;
+        let is_enabled = flags & 1 != 0;
+
+        let content_offset = read_u32_le(file).map_err(|e| ioe(e, "content offset"))?;
+        let meta_data_pointer_offset =
+            read_u32_le(file).map_err(|e| ioe(e, "meta data pointer offset"))?;
+
+        file.seek(SeekFrom::Start(content_offset as u64))
+            .map_err(|e| ioe(e, "light content"))?;
+        let content = LightContent::read(file, light_type)?;
+
+        Ok(Self {
+            name,
+            scale,
+            rotation,
+            translation,
+            light_type,
+            flags,
+            is_enabled,
+            content,
+            meta_data_pointer_offset,
+        })
+    }
+
+    /// the transform of the light relative to the scene
+    pub fn get_transform(&self) -> Matrix3x4 {
+        Matrix3x4::from_srt_euler(self.scale, self.rotation, self.translation)
+    }
+}
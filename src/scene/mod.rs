@@ -0,0 +1,12 @@
+mod camera;
+pub use camera::{Camera, CameraError, CameraProjection, CameraView};
+
+mod fog;
+pub use fog::{Fog, FogError, FogType};
+
+mod light;
+pub use light::{AmbientLight, FragmentLight, HemisphereLight, VertexLight};
+pub use light::{Light, LightContent, LightError, LightShape, LightType};
+
+mod scenedata;
+pub use scenedata::{LightGroup, Scene, SceneError, SceneReference};
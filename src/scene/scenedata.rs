@@ -0,0 +1,108 @@
+use crate::deserialize::{read_i32_le, read_referenced_null_terminated_ascii_string, read_u32_le};
+use std::io;
+use std::io::{Read, Seek, SeekFrom};
+
+#[derive(Debug)]
+pub enum SceneError {
+    IOError(io::Error, &'static str),
+    NullReference(&'static str),
+}
+
+fn ioe(err: io::Error, content: &'static str) -> SceneError {
+    SceneError::IOError(err, content)
+}
+
+/// read a list stored as an offset followed by the number of entries, and put the cursor after it
+fn read_list<F, O, P>(file: &mut F, treat: P, content: &'static str) -> Result<Vec<O>, SceneError>
+where
+    F: Read + Seek,
+    P: Fn(&mut F) -> Result<O, SceneError>,
+{
+    let offset = read_u32_le(file).map_err(|e| ioe(e, content))?;
+    let count = read_u32_le(file).map_err(|e| ioe(e, content))?;
+    let position = file.stream_position().map_err(|e| ioe(e, content))?;
+    file.seek(SeekFrom::Start(offset as u64))
+        .map_err(|e| ioe(e, content))?;
+    let mut result = Vec::with_capacity(count as usize);
+    for _ in 0..count {
+        result.push(treat(file)?);
+    }
+    file.seek(SeekFrom::Start(position))
+        .map_err(|e| ioe(e, content))?;
+    Ok(result)
+}
+
+fn read_name<F: Read + Seek>(file: &mut F) -> Result<String, SceneError> {
+    read_referenced_null_terminated_ascii_string(file)
+        .map_err(|e| ioe(e, "name"))?
+        .ok_or(SceneError::NullReference("name"))
+}
+
+/// A camera or a fog used by a scene, referenced by name
+#[derive(Debug, Clone, PartialEq)]
+pub struct SceneReference {
+    /// the slot the object is bound to
+    pub index: i32,
+    pub name: String,
+}
+
+impl SceneReference {
+    fn read<F: Read + Seek>(file: &mut F) -> Result<Self, SceneError> {
+        let index = read_i32_le(file).map_err(|e| ioe(e, "reference index"))?;
+        let name = read_name(file)?;
+        Ok(Self { index, name })
+    }
+}
+
+/// Lights that are enabled together, referenced by name
+#[derive(Debug, Clone, PartialEq)]
+pub struct LightGroup {
+    pub index: i32,
+    pub light_names: Vec<String>,
+}
+
+impl LightGroup {
+    fn read<F: Read + Seek>(file: &mut F) -> Result<Self, SceneError> {
+        let index = read_i32_le(file).map_err(|e| ioe(e, "light group index"))?;
+        let light_names = read_list(file, read_name, "light names")?;
+        Ok(Self { index, light_names })
+    }
+}
+
+/// The cameras, lights and fogs that make a stage. The objects are stored in the cameras, lights
+/// and fogs of the BCH file, and can be found with `BCH::get_camera`, `BCH::get_light` and
+/// `BCH::get_fog`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Scene {
+    pub name: String,
+    pub cameras: Vec<SceneReference>,
+    pub light_groups: Vec<LightGroup>,
+    pub fogs: Vec<SceneReference>,
+    pub meta_data_pointer_offset: u32,
+}
+
+impl Scene {
+    pub fn read<F: Read + Seek>(file: &mut F) -> Result<Self, SceneError> {
+        let name = read_name(file)?;
+        let cameras = read_list(file, SceneReference::read, "cameras")?;
+        let light_groups = read_list(file, LightGroup::read, "light groups")?;
+        let fogs = read_list(file, SceneReference::read, "fogs")?;
+        let meta_data_pointer_offset =
+            read_u32_le(file).map_err(|e| ioe(e, "meta data pointer offset"))?;
+
+        Ok(Self {
+            name,
+            cameras,
+            light_groups,
+            fogs,
+            meta_data_pointer_offset,
+        })
+    }
+
+    /// return the name of every light of the scene, in all its light groups
+    pub fn get_light_names(&self) -> impl Iterator<Item = &String> {
+        self.light_groups
+            .iter()
+            .flat_map(|light_group| light_group.light_names.iter())
+    }
+}
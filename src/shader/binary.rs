@@ -0,0 +1,404 @@
+use crate::deserialize::{read_null_terminated_ascii_string, read_u16_le, read_u32_le, read_u8};
+use std::io;
+use std::io::{Cursor, Read, Seek, SeekFrom};
+
+#[derive(Debug)]
+pub enum ShaderBinaryError {
+    IOError(io::Error, &'static str),
+    InvalidMagic(&'static str, [u8; 4]),
+    InvalidProgramType(u8),
+    InvalidConstantType(u16),
+    InvalidOutputType(u16),
+}
+
+fn ioe(err: io::Error, content: &'static str) -> ShaderBinaryError {
+    ShaderBinaryError::IOError(err, content)
+}
+
+fn check_magic<F: Read>(
+    file: &mut F,
+    expected: &[u8; 4],
+    content: &'static str,
+) -> Result<(), ShaderBinaryError> {
+    let mut magic = [0; 4];
+    file.read_exact(&mut magic).map_err(|e| ioe(e, content))?;
+    if &magic != expected {
+        return Err(ShaderBinaryError::InvalidMagic(content, magic));
+    };
+    Ok(())
+}
+
+/// read a table stored as an offset relative to `base` followed by the number of entries, and put
+/// the cursor after it
+fn read_table<F, O, P>(
+    file: &mut F,
+    base: u64,
+    treat: P,
+    content: &'static str,
+) -> Result<Vec<O>, ShaderBinaryError>
+where
+    F: Read + Seek,
+    P: Fn(&mut F) -> Result<O, ShaderBinaryError>,
+{
+    let offset = read_u32_le(file).map_err(|e| ioe(e, content))?;
+    let count = read_u32_le(file).map_err(|e| ioe(e, content))?;
+    let position = file.stream_position().map_err(|e| ioe(e, content))?;
+    file.seek(SeekFrom::Start(base + offset as u64))
+        .map_err(|e| ioe(e, content))?;
+    let mut result = Vec::with_capacity(count as usize);
+    for _ in 0..count {
+        result.push(treat(file)?);
+    }
+    file.seek(SeekFrom::Start(position))
+        .map_err(|e| ioe(e, content))?;
+    Ok(result)
+}
+
+/// convert a 24 bit float, as used by the PICA200 (1 bit sign, 7 bit exponent, 16 bit mantissa),
+/// to a f32
+pub fn float24_to_f32(value: u32) -> f32 {
+    let sign = (value >> 23) & 1;
+    let exponent = (value >> 16) & 0x7f;
+    let mantissa = value & 0xffff;
+    let bits = if exponent == 0 {
+        // denormals are flushed to zero
+        sign << 31
+    } else if exponent == 0x7f {
+        (sign << 31) | (0xff << 23) | (mantissa << 7)
+    } else {
+        (sign << 31) | ((exponent + 64) << 23) | (mantissa << 7)
+    };
+    f32::from_bits(bits)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ShaderProgramType {
+    Vertex,
+    Geometry,
+}
+
+impl ShaderProgramType {
+    pub fn new(value: u8) -> Option<Self> {
+        Some(match value {
+            0 => Self::Vertex,
+            1 => Self::Geometry,
+            _ => return None,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ShaderConstantValue {
+    Bool(bool),
+    Integer([u8; 4]),
+    Float([f32; 4]),
+}
+
+/// A value the program put in an uniform register before running
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ShaderConstant {
+    /// the index of the register, in the bool, integer or float register bank depending on the
+    /// value
+    pub register: u16,
+    pub value: ShaderConstantValue,
+}
+
+impl ShaderConstant {
+    fn read<F: Read>(file: &mut F) -> Result<Self, ShaderBinaryError> {
+        let constant_type = read_u16_le(file).map_err(|e| ioe(e, "constant type"))?;
+        let register = read_u16_le(file).map_err(|e| ioe(e, "constant register"))?;
+        let mut data = [0; 4];
+        for word in data.iter_mut() {
+            *word = read_u32_le(file).map_err(|e| ioe(e, "constant value"))?;
+        }
+        let value = match constant_type {
+            0 => ShaderConstantValue::Bool(data[0] & 1 != 0),
+            1 => ShaderConstantValue::Integer(data[0].to_le_bytes()),
+            2 => ShaderConstantValue::Float([
+                float24_to_f32(data[0]),
+                float24_to_f32(data[1]),
+                float24_to_f32(data[2]),
+                float24_to_f32(data[3]),
+            ]),
+            _ => return Err(ShaderBinaryError::InvalidConstantType(constant_type)),
+        };
+        Ok(Self { register, value })
+    }
+}
+
+/// A named position in the instructions
+#[derive(Debug, Clone, PartialEq)]
+pub struct ShaderLabel {
+    pub id: u8,
+    /// the position of the first instruction, in words
+    pub location: u32,
+    /// the number of instructions
+    pub size: u32,
+    pub name: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ShaderOutputType {
+    Position,
+    QuaternionNormal,
+    Color,
+    TexCoord0,
+    TexCoord0W,
+    TexCoord1,
+    TexCoord2,
+    View,
+}
+
+impl ShaderOutputType {
+    pub fn new(value: u16) -> Option<Self> {
+        Some(match value {
+            0 => Self::Position,
+            1 => Self::QuaternionNormal,
+            2 => Self::Color,
+            3 => Self::TexCoord0,
+            4 => Self::TexCoord0W,
+            5 => Self::TexCoord1,
+            6 => Self::TexCoord2,
+            8 => Self::View,
+            _ => return None,
+        })
+    }
+}
+
+/// Tell which vertex attribute is written in an output register
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ShaderOutput {
+    pub output_type: ShaderOutputType,
+    pub register: u16,
+    /// the components of the register that are written, with bit 0 for x to bit 3 for w
+    pub mask: u16,
+}
+
+impl ShaderOutput {
+    fn read<F: Read>(file: &mut F) -> Result<Self, ShaderBinaryError> {
+        let output_type_value = read_u16_le(file).map_err(|e| ioe(e, "output type"))?;
+        let output_type = ShaderOutputType::new(output_type_value)
+            .ok_or(ShaderBinaryError::InvalidOutputType(output_type_value))?;
+        let register = read_u16_le(file).map_err(|e| ioe(e, "output register"))?;
+        let mask = read_u16_le(file).map_err(|e| ioe(e, "output mask"))?;
+        read_u16_le(file).map_err(|e| ioe(e, "output padding"))?;
+        Ok(Self {
+            output_type,
+            register,
+            mask,
+        })
+    }
+}
+
+/// A named range of registers. Registers 0x00 to 0x0f are the inputs, 0x10 to 0x6f the float
+/// uniforms, 0x70 to 0x73 the integer uniforms and 0x78 to 0x87 the bool uniforms.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ShaderUniform {
+    pub name: String,
+    pub start_register: u16,
+    pub end_register: u16,
+}
+
+/// A vertex or geometry shader, with its entry point in the instructions of the `ShaderBinary`
+/// and the description of its registers (a DVLE)
+#[derive(Debug, Clone, PartialEq)]
+pub struct ShaderProgram {
+    pub program_type: ShaderProgramType,
+    pub merge_output_maps: bool,
+    /// the position of the first instruction, in words
+    pub main_offset: u32,
+    /// the position of the last instruction, in words
+    pub end_offset: u32,
+    pub input_register_mask: u16,
+    pub output_register_mask: u16,
+    pub geometry_shader_type: u8,
+    pub fixed_vertex_start_register: u8,
+    pub fixed_vertex_count: u8,
+    pub variable_vertex_count: u8,
+    pub constants: Vec<ShaderConstant>,
+    pub labels: Vec<ShaderLabel>,
+    pub outputs: Vec<ShaderOutput>,
+    pub uniforms: Vec<ShaderUniform>,
+}
+
+impl ShaderProgram {
+    fn read<F: Read + Seek>(file: &mut F) -> Result<Self, ShaderBinaryError> {
+        let base = file
+            .stream_position()
+            .map_err(|e| ioe(e, "program offset"))?;
+        check_magic(file, b"DVLE", "DVLE magic")?;
+        read_u16_le(file).map_err(|e| ioe(e, "program version"))?;
+        let program_type_value = read_u8(file).map_err(|e| ioe(e, "program type"))?;
+        let program_type = ShaderProgramType::new(program_type_value)
+            .ok_or(ShaderBinaryError::InvalidProgramType(program_type_value))?;
+        let merge_output_maps =
+            read_u8(file).map_err(|e| ioe(e, "merge output maps flag"))? & 1 != 0;
+        let main_offset = read_u32_le(file).map_err(|e| ioe(e, "main offset"))?;
+        let end_offset = read_u32_le(file).map_err(|e| ioe(e, "end offset"))?;
+        let input_register_mask = read_u16_le(file).map_err(|e| ioe(e, "input register mask"))?;
+        let output_register_mask = read_u16_le(file).map_err(|e| ioe(e, "output register mask"))?;
+        let geometry_shader_type = read_u8(file).map_err(|e| ioe(e, "geometry shader type"))?;
+        let fixed_vertex_start_register =
+            read_u8(file).map_err(|e| ioe(e, "fixed vertex start register"))?;
+        let fixed_vertex_count = read_u8(file).map_err(|e| ioe(e, "fixed vertex count"))?;
+        let variable_vertex_count = read_u8(file).map_err(|e| ioe(e, "variable vertex count"))?;
+
+        let constants = read_table(file, base, ShaderConstant::read, "constant table")?;
+
+        // the names are only known once the symbol table is found
+        let raw_labels = read_table(
+            file,
+            base,
+            |file| {
+                let id = read_u8(file).map_err(|e| ioe(e, "label id"))?;
+                file.seek(SeekFrom::Current(3))
+                    .map_err(|e| ioe(e, "label padding"))?;
+                let location = read_u32_le(file).map_err(|e| ioe(e, "label location"))?;
+                let size = read_u32_le(file).map_err(|e| ioe(e, "label size"))?;
+                let symbol = read_u32_le(file).map_err(|e| ioe(e, "label symbol"))?;
+                Ok((id, location, size, symbol))
+            },
+            "label table",
+        )?;
+
+        let outputs = read_table(file, base, ShaderOutput::read, "output table")?;
+
+        let raw_uniforms = read_table(
+            file,
+            base,
+            |file| {
+                let symbol = read_u32_le(file).map_err(|e| ioe(e, "uniform symbol"))?;
+                let start_register =
+                    read_u16_le(file).map_err(|e| ioe(e, "uniform start register"))?;
+                let end_register = read_u16_le(file).map_err(|e| ioe(e, "uniform end register"))?;
+                Ok((symbol, start_register, end_register))
+            },
+            "uniform table",
+        )?;
+
+        let symbol_table_offset = read_u32_le(file).map_err(|e| ioe(e, "symbol table offset"))?;
+        let mut read_symbol = |symbol: u32| -> Result<String, ShaderBinaryError> {
+            file.seek(SeekFrom::Start(
+                base + symbol_table_offset as u64 + symbol as u64,
+            ))
+            .map_err(|e| ioe(e, "symbol"))?;
+            read_null_terminated_ascii_string(file).map_err(|e| ioe(e, "symbol"))
+        };
+
+        let mut labels = Vec::with_capacity(raw_labels.len());
+        for (id, location, size, symbol) in raw_labels {
+            labels.push(ShaderLabel {
+                id,
+                location,
+                size,
+                name: read_symbol(symbol)?,
+            });
+        }
+
+        let mut uniforms = Vec::with_capacity(raw_uniforms.len());
+        for (symbol, start_register, end_register) in raw_uniforms {
+            uniforms.push(ShaderUniform {
+                name: read_symbol(symbol)?,
+                start_register,
+                end_register,
+            });
+        }
+
+        Ok(Self {
+            program_type,
+            merge_output_maps,
+            main_offset,
+            end_offset,
+            input_register_mask,
+            output_register_mask,
+            geometry_shader_type,
+            fixed_vertex_start_register,
+            fixed_vertex_count,
+            variable_vertex_count,
+            constants,
+            labels,
+            outputs,
+            uniforms,
+        })
+    }
+
+    /// return the output that write the given register, if any
+    pub fn get_output(&self, register: u16) -> Option<&ShaderOutput> {
+        self.outputs
+            .iter()
+            .find(|output| output.register == register)
+    }
+
+    /// return the uniform that contain the given register, if any
+    pub fn get_uniform(&self, register: u16) -> Option<&ShaderUniform> {
+        self.uniforms
+            .iter()
+            .find(|uniform| uniform.start_register <= register && register <= uniform.end_register)
+    }
+}
+
+/// A compiled shader binary (a DVLB), with the instructions and operand descriptors (a DVLP)
+/// shared by every program
+#[derive(Debug, Clone, PartialEq)]
+pub struct ShaderBinary {
+    pub instructions: Vec<u32>,
+    pub operand_descriptors: Vec<u32>,
+    pub programs: Vec<ShaderProgram>,
+}
+
+impl ShaderBinary {
+    pub fn read<F: Read + Seek>(file: &mut F) -> Result<Self, ShaderBinaryError> {
+        let base = file.stream_position().map_err(|e| ioe(e, "DVLB offset"))?;
+        check_magic(file, b"DVLB", "DVLB magic")?;
+        let program_count = read_u32_le(file).map_err(|e| ioe(e, "program count"))?;
+        let mut program_offsets = Vec::with_capacity(program_count as usize);
+        for _ in 0..program_count {
+            program_offsets.push(read_u32_le(file).map_err(|e| ioe(e, "program offset"))?);
+        }
+
+        // the DVLP follow the DVLB header
+        let dvlp_base = file.stream_position().map_err(|e| ioe(e, "DVLP offset"))?;
+        check_magic(file, b"DVLP", "DVLP magic")?;
+        read_u32_le(file).map_err(|e| ioe(e, "DVLP version"))?;
+        let instructions = read_table(
+            file,
+            dvlp_base,
+            |file| read_u32_le(file).map_err(|e| ioe(e, "instruction")),
+            "instructions",
+        )?;
+        let operand_descriptors = read_table(
+            file,
+            dvlp_base,
+            |file| {
+                let descriptor = read_u32_le(file).map_err(|e| ioe(e, "operand descriptor"))?;
+                read_u32_le(file).map_err(|e| ioe(e, "operand descriptor padding"))?;
+                Ok(descriptor)
+            },
+            "operand descriptors",
+        )?;
+
+        let mut programs = Vec::with_capacity(program_offsets.len());
+        for program_offset in program_offsets {
+            file.seek(SeekFrom::Start(base + program_offset as u64))
+                .map_err(|e| ioe(e, "program"))?;
+            programs.push(ShaderProgram::read(file)?);
+        }
+
+        Ok(Self {
+            instructions,
+            operand_descriptors,
+            programs,
+        })
+    }
+
+    pub fn from_bytes(data: &[u8]) -> Result<Self, ShaderBinaryError> {
+        Self::read(&mut Cursor::new(data))
+    }
+
+    /// return the first program of the given type
+    pub fn get_program(&self, program_type: ShaderProgramType) -> Option<&ShaderProgram> {
+        self.programs
+            .iter()
+            .find(|program| program.program_type == program_type)
+    }
+}
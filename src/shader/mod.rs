@@ -0,0 +1,9 @@
+mod binary;
+pub use binary::{
+    float24_to_f32, ShaderBinary, ShaderBinaryError, ShaderProgram, ShaderProgramType,
+};
+pub use binary::{ShaderConstant, ShaderConstantValue, ShaderLabel, ShaderUniform};
+pub use binary::{ShaderOutput, ShaderOutputType};
+
+mod shaderdata;
+pub use shaderdata::{Shader, ShaderError};
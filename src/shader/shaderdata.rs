@@ -0,0 +1,94 @@
+use crate::deserialize::{read_referenced_null_terminated_ascii_string, read_u32_le};
+use crate::shader::{ShaderBinary, ShaderBinaryError};
+use crate::{MetaData, MetaDataError};
+use std::io;
+use std::io::{Read, Seek, SeekFrom};
+
+#[derive(Debug)]
+pub enum ShaderError {
+    IOError(io::Error, &'static str),
+    NullReference(&'static str),
+    ShaderBinaryError(ShaderBinaryError),
+    MetaDataError(MetaDataError),
+}
+
+fn ioe(err: io::Error, content: &'static str) -> ShaderError {
+    ShaderError::IOError(err, content)
+}
+
+/// read a list of words stored as an offset followed by the number of words, and put the cursor
+/// after it
+fn read_words<F: Read + Seek>(
+    file: &mut F,
+    content: &'static str,
+) -> Result<Vec<u32>, ShaderError> {
+    let offset = read_u32_le(file).map_err(|e| ioe(e, content))?;
+    let count = read_u32_le(file).map_err(|e| ioe(e, content))?;
+    let position = file.stream_position().map_err(|e| ioe(e, content))?;
+    file.seek(SeekFrom::Start(offset as u64))
+        .map_err(|e| ioe(e, content))?;
+    let mut words = Vec::with_capacity(count as usize);
+    for _ in 0..count {
+        words.push(read_u32_le(file).map_err(|e| ioe(e, content))?);
+    }
+    file.seek(SeekFrom::Start(position))
+        .map_err(|e| ioe(e, content))?;
+    Ok(words)
+}
+
+/// A custom shader used by materials, referenced by `MaterialParameters::shader_reference`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Shader {
+    pub name: String,
+    /// the raw DVLB, as stored in the file
+    pub data: Vec<u8>,
+    /// the parsed content of `data`
+    pub binary: ShaderBinary,
+    /// the PICA200 commands that upload the whole shader
+    pub all_commands: Vec<u32>,
+    /// the PICA200 commands that upload the instructions and operand descriptors
+    pub program_commands: Vec<u32>,
+    /// the PICA200 commands that configure the shader units
+    pub setup_commands: Vec<u32>,
+    pub meta_data: Option<MetaData>,
+}
+
+impl Shader {
+    pub fn read<F: Read + Seek>(file: &mut F) -> Result<Self, ShaderError> {
+        let data_offset = read_u32_le(file).map_err(|e| ioe(e, "shader data offset"))?;
+        let data_length = read_u32_le(file).map_err(|e| ioe(e, "shader data length"))?;
+        let position = file
+            .stream_position()
+            .map_err(|e| ioe(e, "shader data offset"))?;
+        file.seek(SeekFrom::Start(data_offset as u64))
+            .map_err(|e| ioe(e, "shader data"))?;
+        let mut data = vec![0; data_length as usize];
+        file.read_exact(&mut data)
+            .map_err(|e| ioe(e, "shader data"))?;
+        file.seek(SeekFrom::Start(position))
+            .map_err(|e| ioe(e, "after shader data"))?;
+        let binary = ShaderBinary::from_bytes(&data).map_err(ShaderError::ShaderBinaryError)?;
+
+        let all_commands = read_words(file, "all commands")?;
+        let program_commands = read_words(file, "program commands")?;
+        let setup_commands = read_words(file, "setup commands")?;
+
+        let name = read_referenced_null_terminated_ascii_string(file)
+            .map_err(|e| ioe(e, "shader name"))?
+            .ok_or(ShaderError::NullReference("shader name"))?;
+
+        let meta_data_offset = read_u32_le(file).map_err(|e| ioe(e, "meta data offset"))?;
+        let meta_data =
+            MetaData::read_at(file, meta_data_offset).map_err(ShaderError::MetaDataError)?;
+
+        Ok(Self {
+            name,
+            data,
+            binary,
+            all_commands,
+            program_commands,
+            setup_commands,
+            meta_data,
+        })
+    }
+}
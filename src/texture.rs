@@ -0,0 +1,114 @@
+use crate::deserialize::{read_referenced_null_terminated_ascii_string, read_u32_le, read_u8};
+use crate::TextureFormat;
+use crate::{decode_texture, get_texture_length, TextureDecodeError};
+use crate::{PICACommandReader, PICACommandReaderError, PicaCommand};
+use std::io;
+use std::io::{Read, Seek, SeekFrom};
+
+#[derive(Debug)]
+pub enum TextureError {
+    IOError(io::Error, &'static str),
+    PICACommandReaderError(PICACommandReaderError, &'static str),
+    NullReference(&'static str),
+    InvalidFormat(u32),
+}
+
+#[derive(Debug)]
+pub enum TexturePngError {
+    TextureDecodeError(TextureDecodeError),
+    PngEncodingError(png::EncodingError),
+}
+
+fn ioe(err: io::Error, content: &'static str) -> TextureError {
+    TextureError::IOError(err, content)
+}
+
+#[derive(Debug)]
+pub struct Texture {
+    pub name: String,
+    pub width: u32,
+    pub height: u32,
+    pub format: TextureFormat,
+    pub mipmap_count: u8,
+    /// the raw pixel data, as stored for the GPU, with every mipmap level following the first one
+    pub data: Vec<u8>,
+}
+
+impl Texture {
+    pub fn read<F: Read + Seek>(file: &mut F) -> Result<Texture, TextureError> {
+        let texture_unit0_commands_offset =
+            read_u32_le(file).map_err(|e| ioe(e, "texture unit 0 commands offset"))?;
+        let texture_unit0_commands_word_count =
+            read_u32_le(file).map_err(|e| ioe(e, "texture unit 0 commands word count"))?;
+
+        // the commands for texture unit 1 and 2 describe the same texture
+        file.seek(SeekFrom::Current(0x10))
+            .map_err(|e| ioe(e, "texture unit 1 and 2 commands"))?;
+
+        // the format is also present in the commands
+        file.seek(SeekFrom::Current(1))
+            .map_err(|e| ioe(e, "format"))?;
+        let mipmap_count = read_u8(file).map_err(|e| ioe(e, "mipmap count"))?;
+        file.seek(SeekFrom::Current(2))
+            .map_err(|e| ioe(e, "padding"))?;
+
+        let name = read_referenced_null_terminated_ascii_string(file)
+            .map_err(|e| ioe(e, "texture name"))?
+            .ok_or(TextureError::NullReference("texture name"))?;
+
+        file.seek(SeekFrom::Start(texture_unit0_commands_offset as u64))
+            .map_err(|e| ioe(e, "texture unit 0 commands"))?;
+        let texture_commands =
+            PICACommandReader::read(file, texture_unit0_commands_word_count as u64)
+                .map_err(|e| TextureError::PICACommandReaderError(e, "texture unit 0 commands"))?;
+
+        let (width, height) = texture_commands.get_texture_unit0_size();
+        let format =
+            texture_commands
+                .get_texture_unit0_format()
+                .ok_or(TextureError::InvalidFormat(
+                    texture_commands.get(PicaCommand::TextureUnitType(0)),
+                ))?;
+
+        let mut data_length = 0;
+        for level in 0..mipmap_count.max(1) {
+            data_length += get_texture_length(width >> level, height >> level, format);
+        }
+
+        file.seek(SeekFrom::Start(
+            texture_commands.get_texture_unit0_address() as u64,
+        ))
+        .map_err(|e| ioe(e, "texture data"))?;
+        let mut data = vec![0; data_length];
+        file.read_exact(&mut data)
+            .map_err(|e| ioe(e, "texture data"))?;
+
+        Ok(Texture {
+            name,
+            width,
+            height,
+            format,
+            mipmap_count,
+            data,
+        })
+    }
+
+    /// decode the first mipmap level to RGBA8
+    pub fn decode(&self) -> Result<Vec<u8>, TextureDecodeError> {
+        decode_texture(&self.data, self.width, self.height, self.format)
+    }
+
+    /// decode the first mipmap level to a PNG image
+    pub fn encode_png(&self) -> Result<Vec<u8>, TexturePngError> {
+        let rgba = self.decode().map_err(TexturePngError::TextureDecodeError)?;
+        let mut result = Vec::new();
+        let mut encoder = png::Encoder::new(&mut result, self.width, self.height);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+        encoder
+            .write_header()
+            .and_then(|mut writer| writer.write_image_data(&rgba))
+            .map_err(TexturePngError::PngEncodingError)?;
+        Ok(result)
+    }
+}
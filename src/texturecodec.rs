@@ -0,0 +1,356 @@
+//! Decode the texture formats of the PICA200 to RGBA8.
+//!
+//! The output rows are in the order they are stored in memory. The first row is the one sampled
+//! with a v texture coordinate of 1, so the image can be saved as is for formats where v points
+//! up, like OBJ.
+use crate::TextureFormat;
+
+#[derive(Debug)]
+pub enum TextureDecodeError {
+    InvalidSize(u32, u32),
+    NotLongEnought { expected: usize, found: usize },
+}
+
+const ETC1_MODIFIER_TABLE: [[i32; 4]; 8] = [
+    [2, 8, -2, -8],
+    [5, 17, -5, -17],
+    [9, 29, -9, -29],
+    [13, 42, -13, -42],
+    [18, 60, -18, -60],
+    [24, 80, -24, -80],
+    [33, 106, -33, -106],
+    [47, 183, -47, -183],
+];
+
+/// return the position in a 8x8 tile of the pixel at `index` in morton order
+fn morton_position(index: u32) -> (u32, u32) {
+    let x = (index & 1) | (index >> 1 & 2) | (index >> 2 & 4);
+    let y = (index >> 1 & 1) | (index >> 2 & 2) | (index >> 3 & 4);
+    (x, y)
+}
+
+fn extend_4_to_8(value: u8) -> u8 {
+    value << 4 | value
+}
+
+fn extend_5_to_8(value: u8) -> u8 {
+    value << 3 | value >> 2
+}
+
+fn extend_6_to_8(value: u8) -> u8 {
+    value << 2 | value >> 4
+}
+
+/// decode the pixel at `index` in the data, where index count pixel in storage order
+fn decode_pixel(data: &[u8], index: usize, format: TextureFormat) -> [u8; 4] {
+    let read_u16 = |offset: usize| u16::from_le_bytes([data[offset], data[offset + 1]]);
+    let read_nibble = |offset: usize| (data[offset / 2] >> (offset % 2 * 4)) & 0xf;
+    match format {
+        TextureFormat::Rgba8 => {
+            let offset = index * 4;
+            [
+                data[offset + 3],
+                data[offset + 2],
+                data[offset + 1],
+                data[offset],
+            ]
+        }
+        TextureFormat::Rgb8 => {
+            let offset = index * 3;
+            [data[offset + 2], data[offset + 1], data[offset], 0xff]
+        }
+        TextureFormat::Rgba5551 => {
+            let value = read_u16(index * 2);
+            [
+                extend_5_to_8((value >> 11 & 0x1f) as u8),
+                extend_5_to_8((value >> 6 & 0x1f) as u8),
+                extend_5_to_8((value >> 1 & 0x1f) as u8),
+                (value & 1) as u8 * 0xff,
+            ]
+        }
+        TextureFormat::Rgb565 => {
+            let value = read_u16(index * 2);
+            [
+                extend_5_to_8((value >> 11 & 0x1f) as u8),
+                extend_6_to_8((value >> 5 & 0x3f) as u8),
+                extend_5_to_8((value & 0x1f) as u8),
+                0xff,
+            ]
+        }
+        TextureFormat::Rgba4 => {
+            let value = read_u16(index * 2);
+            [
+                extend_4_to_8((value >> 12 & 0xf) as u8),
+                extend_4_to_8((value >> 8 & 0xf) as u8),
+                extend_4_to_8((value >> 4 & 0xf) as u8),
+                extend_4_to_8((value & 0xf) as u8),
+            ]
+        }
+        TextureFormat::La8 => {
+            let offset = index * 2;
+            let luminance = data[offset + 1];
+            [luminance, luminance, luminance, data[offset]]
+        }
+        TextureFormat::HiLo8 => {
+            let offset = index * 2;
+            [data[offset + 1], data[offset], 0, 0xff]
+        }
+        TextureFormat::L8 => [data[index], data[index], data[index], 0xff],
+        TextureFormat::A8 => [0, 0, 0, data[index]],
+        TextureFormat::La4 => {
+            let luminance = extend_4_to_8(data[index] >> 4);
+            [luminance, luminance, luminance, extend_4_to_8(data[index] & 0xf)]
+        }
+        TextureFormat::L4 => {
+            let luminance = extend_4_to_8(read_nibble(index));
+            [luminance, luminance, luminance, 0xff]
+        }
+        TextureFormat::A4 => [0, 0, 0, extend_4_to_8(read_nibble(index))],
+        TextureFormat::Etc1 | TextureFormat::Etc1A4 => {
+            unreachable!("etc1 textures are not decoded pixel per pixel")
+        }
+    }
+}
+
+/// decode a 4x4 ETC1 block, with the pixels in the output ordered by line
+fn decode_etc1_block(block: u64) -> [[u8; 3]; 16] {
+    let high = (block >> 32) as u32;
+    let low = block as u32;
+
+    let flip = high & 1 == 1;
+    let differential = high >> 1 & 1 == 1;
+
+    let mut base_colors = [[0; 3]; 2];
+    for channel in 0..3 {
+        let shift = 27 - channel * 8;
+        if differential {
+            let base = (high >> shift & 0x1f) as i32;
+            let mut delta = (high >> (shift - 3) & 0x7) as i32;
+            if delta >= 4 {
+                delta -= 8;
+            }
+            base_colors[0][channel as usize] = extend_5_to_8(base as u8);
+            base_colors[1][channel as usize] = extend_5_to_8(((base + delta) & 0x1f) as u8);
+        } else {
+            base_colors[0][channel as usize] = extend_4_to_8((high >> (shift + 1) & 0xf) as u8);
+            base_colors[1][channel as usize] = extend_4_to_8((high >> (shift - 3) & 0xf) as u8);
+        }
+    }
+
+    let tables = [
+        ETC1_MODIFIER_TABLE[(high >> 5 & 0x7) as usize],
+        ETC1_MODIFIER_TABLE[(high >> 2 & 0x7) as usize],
+    ];
+
+    let mut result = [[0; 3]; 16];
+    for y in 0..4 {
+        for x in 0..4 {
+            let sub_block = if flip { y >= 2 } else { x >= 2 } as usize;
+            let pixel_index = x * 4 + y;
+            let modifier_index = (low >> (pixel_index + 16) & 1) << 1 | (low >> pixel_index & 1);
+            let modifier = tables[sub_block][modifier_index as usize];
+            let mut color = [0; 3];
+            for (channel, value) in color.iter_mut().enumerate() {
+                *value = (base_colors[sub_block][channel] as i32 + modifier).clamp(0, 255) as u8;
+            }
+            result[(y * 4 + x) as usize] = color;
+        }
+    }
+    result
+}
+
+fn decode_etc1(data: &[u8], width: u32, height: u32, has_alpha: bool) -> Vec<u8> {
+    let mut output = vec![0; (width * height * 4) as usize];
+    let block_size = if has_alpha { 16 } else { 8 };
+    let mut offset = 0;
+    for tile_y in (0..height).step_by(8) {
+        for tile_x in (0..width).step_by(8) {
+            for block in 0..4 {
+                let block_x = tile_x + (block & 1) * 4;
+                let block_y = tile_y + (block >> 1) * 4;
+
+                let read_u64 = |offset: usize| {
+                    let mut buffer = [0; 8];
+                    buffer.copy_from_slice(&data[offset..offset + 8]);
+                    u64::from_le_bytes(buffer)
+                };
+                let alpha = if has_alpha {
+                    read_u64(offset)
+                } else {
+                    u64::MAX
+                };
+                let colors = decode_etc1_block(read_u64(offset + block_size - 8));
+                offset += block_size;
+
+                for y in 0..4 {
+                    for x in 0..4 {
+                        let color = colors[(y * 4 + x) as usize];
+                        let alpha = extend_4_to_8((alpha >> ((x * 4 + y) * 4) & 0xf) as u8);
+                        let output_offset = (((block_y + y) * width + block_x + x) * 4) as usize;
+                        output[output_offset..output_offset + 4]
+                            .copy_from_slice(&[color[0], color[1], color[2], alpha]);
+                    }
+                }
+            }
+        }
+    }
+    output
+}
+
+/// return the size in byte of a texture of the given size and format
+pub fn get_texture_length(width: u32, height: u32, format: TextureFormat) -> usize {
+    (width * height * format.get_bits_per_pixel() / 8) as usize
+}
+
+/// decode a texture, with its 8x8 tiles swizzled as stored on the 3DS, to a linear RGBA8 buffer
+pub fn decode_texture(
+    data: &[u8],
+    width: u32,
+    height: u32,
+    format: TextureFormat,
+) -> Result<Vec<u8>, TextureDecodeError> {
+    if width & 7 != 0 || height & 7 != 0 {
+        return Err(TextureDecodeError::InvalidSize(width, height));
+    };
+    let expected = get_texture_length(width, height, format);
+    if data.len() < expected {
+        return Err(TextureDecodeError::NotLongEnought {
+            expected,
+            found: data.len(),
+        });
+    };
+
+    match format {
+        TextureFormat::Etc1 => return Ok(decode_etc1(data, width, height, false)),
+        TextureFormat::Etc1A4 => return Ok(decode_etc1(data, width, height, true)),
+        _ => (),
+    };
+
+    let mut output = vec![0; (width * height * 4) as usize];
+    let mut index = 0;
+    for tile_y in (0..height).step_by(8) {
+        for tile_x in (0..width).step_by(8) {
+            for pixel in 0..64 {
+                let (x, y) = morton_position(pixel);
+                let output_offset = (((tile_y + y) * width + tile_x + x) * 4) as usize;
+                output[output_offset..output_offset + 4]
+                    .copy_from_slice(&decode_pixel(data, index, format));
+                index += 1;
+            }
+        }
+    }
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get_pixel(output: &[u8], width: u32, x: u32, y: u32) -> [u8; 4] {
+        let offset = ((y * width + x) * 4) as usize;
+        [
+            output[offset],
+            output[offset + 1],
+            output[offset + 2],
+            output[offset + 3],
+        ]
+    }
+
+    #[test]
+    fn rgba8_morton_tiles() {
+        // two 8x8 tiles, each pixel storing its storage index in red and its tile in green,
+        // as ABGR in memory
+        let mut data = Vec::new();
+        for index in 0..128u8 {
+            data.extend_from_slice(&[0xff, 0x00, index / 64, index % 64]);
+        }
+        let output = decode_texture(&data, 16, 8, TextureFormat::Rgba8).unwrap();
+
+        let rows = [
+            [0, 1, 4, 5, 16, 17, 20, 21],
+            [2, 3, 6, 7, 18, 19, 22, 23],
+            [8, 9, 12, 13, 24, 25, 28, 29],
+        ];
+        for (y, row) in rows.iter().enumerate() {
+            for (x, index) in row.iter().enumerate() {
+                assert_eq!(
+                    get_pixel(&output, 16, x as u32, y as u32),
+                    [*index, 0, 0, 0xff]
+                );
+                assert_eq!(
+                    get_pixel(&output, 16, x as u32 + 8, y as u32),
+                    [*index, 1, 0, 0xff]
+                );
+            }
+        }
+        assert_eq!(get_pixel(&output, 16, 7, 7), [63, 0, 0, 0xff]);
+        assert_eq!(get_pixel(&output, 16, 15, 7), [63, 1, 0, 0xff]);
+    }
+
+    #[test]
+    fn rgb565_morton_tile() {
+        let mut values = [0u16; 64];
+        values[0] = 0xf800;
+        values[1] = 0x07e0;
+        values[2] = 0x001f;
+        values[3] = 0x8410;
+        values[4] = 0xffff;
+        values[63] = 0x0841;
+        let data: Vec<u8> = values
+            .iter()
+            .flat_map(|value| value.to_le_bytes())
+            .collect();
+        let output = decode_texture(&data, 8, 8, TextureFormat::Rgb565).unwrap();
+
+        assert_eq!(get_pixel(&output, 8, 0, 0), [0xff, 0x00, 0x00, 0xff]);
+        assert_eq!(get_pixel(&output, 8, 1, 0), [0x00, 0xff, 0x00, 0xff]);
+        assert_eq!(get_pixel(&output, 8, 0, 1), [0x00, 0x00, 0xff, 0xff]);
+        assert_eq!(get_pixel(&output, 8, 1, 1), [0x84, 0x82, 0x84, 0xff]);
+        assert_eq!(get_pixel(&output, 8, 2, 0), [0xff, 0xff, 0xff, 0xff]);
+        assert_eq!(get_pixel(&output, 8, 7, 7), [0x08, 0x08, 0x08, 0xff]);
+        assert_eq!(get_pixel(&output, 8, 3, 0), [0x00, 0x00, 0x00, 0xff]);
+    }
+
+    /// an individual, non flipped block with the base colors 0x884422 and 0x112233, and the
+    /// modifier tables 0 and 1
+    const ETC1_BLOCK: u64 = 0x8142_2304_8002_8010;
+
+    /// check the pixels of `ETC1_BLOCK` decoded at the given position, with the expected alpha of
+    /// the pixels (0, 0), (1, 0), (0, 1), (2, 0) and (3, 3)
+    fn check_etc1_block(output: &[u8], block_x: u32, block_y: u32, alpha: [u8; 5]) {
+        let pixel = |x, y| get_pixel(output, 8, block_x + x, block_y + y);
+        assert_eq!(pixel(0, 0), [0x8a, 0x46, 0x24, alpha[0]]);
+        assert_eq!(pixel(1, 0), [0x90, 0x4c, 0x2a, alpha[1]]);
+        assert_eq!(pixel(0, 1), [0x86, 0x42, 0x20, alpha[2]]);
+        assert_eq!(pixel(2, 0), [0x16, 0x27, 0x38, alpha[3]]);
+        assert_eq!(pixel(3, 3), [0x00, 0x11, 0x22, alpha[4]]);
+    }
+
+    #[test]
+    fn etc1_block() {
+        let mut data = ETC1_BLOCK.to_le_bytes().to_vec();
+        data.resize(32, 0);
+        let output = decode_texture(&data, 8, 8, TextureFormat::Etc1).unwrap();
+
+        check_etc1_block(&output, 0, 0, [0xff; 5]);
+        // an empty block is its base color plus the first modifier
+        assert_eq!(get_pixel(&output, 8, 4, 0), [2, 2, 2, 0xff]);
+        assert_eq!(get_pixel(&output, 8, 0, 4), [2, 2, 2, 0xff]);
+        assert_eq!(get_pixel(&output, 8, 7, 7), [2, 2, 2, 0xff]);
+    }
+
+    #[test]
+    fn etc1a4_block() {
+        // the second block of the tile, with the alpha of each pixel being its index in column
+        // order
+        let mut data = vec![0; 16];
+        data.extend_from_slice(&0xfedc_ba98_7654_3210u64.to_le_bytes());
+        data.extend_from_slice(&ETC1_BLOCK.to_le_bytes());
+        data.resize(64, 0);
+        let output = decode_texture(&data, 8, 8, TextureFormat::Etc1A4).unwrap();
+
+        check_etc1_block(&output, 4, 0, [0x00, 0x44, 0x11, 0x88, 0xff]);
+        assert_eq!(get_pixel(&output, 8, 0, 0), [2, 2, 2, 0]);
+        assert_eq!(get_pixel(&output, 8, 4, 4), [2, 2, 2, 0]);
+    }
+}